@@ -1847,6 +1847,7 @@ mod __test {
             doc_tests: DocTests::No,
             bless: false,
             compare_mode: None,
+            verify_incremental_queries: false,
         };
 
         let build = Build::new(config);
@@ -1888,6 +1889,7 @@ mod __test {
             doc_tests: DocTests::No,
             bless: false,
             compare_mode: None,
+            verify_incremental_queries: false,
         };
 
         let build = Build::new(config);
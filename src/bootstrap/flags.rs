@@ -52,6 +52,8 @@ pub enum Subcommand {
         /// Whether to automatically update stderr/stdout files
         bless: bool,
         compare_mode: Option<String>,
+        /// Whether incremental tests recompute every green query
+        verify_incremental_queries: bool,
         test_args: Vec<String>,
         rustc_args: Vec<String>,
         fail_fast: bool,
@@ -188,6 +190,11 @@ To learn more about a subcommand, run `./x.py <subcommand> -h`"
                     "mode describing what file the actual ui output will be compared to",
                     "COMPARE MODE",
                 );
+                opts.optflag(
+                    "",
+                    "verify-incremental-queries",
+                    "recompute every green query in each revision of incremental tests",
+                );
             }
             "bench" => {
                 opts.optmulti("", "test-args", "extra arguments", "ARGS");
@@ -286,6 +293,8 @@ Arguments:
         ./x.py test src/libstd --stage 0 --no-doc
         ./x.py test src/test/ui --bless
         ./x.py test src/test/ui --compare-mode nll
+        ./x.py test src/test/ui --compare-mode parallel
+        ./x.py test src/test/incremental --verify-incremental-queries
 
     Note that `test src/test/* --stage N` does NOT depend on `build src/rustc --stage N`;
     just like `build src/libstd --stage N` it tests the compiler produced by the previous
@@ -360,6 +369,7 @@ Arguments:
                 paths,
                 bless: matches.opt_present("bless"),
                 compare_mode: matches.opt_str("compare-mode"),
+                verify_incremental_queries: matches.opt_present("verify-incremental-queries"),
                 test_args: matches.opt_strs("test-args"),
                 rustc_args: matches.opt_strs("rustc-args"),
                 fail_fast: !matches.opt_present("no-fail-fast"),
@@ -475,6 +485,13 @@ impl Subcommand {
             _ => None,
         }
     }
+
+    pub fn verify_incremental_queries(&self) -> bool {
+        match *self {
+            Subcommand::Test { verify_incremental_queries, .. } => verify_incremental_queries,
+            _ => false,
+        }
+    }
 }
 
 fn split(s: &[String]) -> Vec<String> {
@@ -1017,6 +1017,10 @@ impl Step for Compiletest {
             cmd.arg("--bless");
         }
 
+        if builder.config.cmd.verify_incremental_queries() {
+            cmd.arg("--verify-incremental-queries");
+        }
+
         let compare_mode = builder.config.cmd.compare_mode().or_else(|| {
             if builder.config.test_compare_mode {
                 self.compare_mode
@@ -0,0 +1,158 @@
+#!/usr/bin/env python
+
+"""
+Answers questions about a dependency graph written by rustc with
+`-Z dump-dep-graph-json=<file>`.
+
+Usage:
+    dep-graph-query.py <file> dependents <node> [--direct]
+    dep-graph-query.py <file> dependencies <node> [--direct]
+    dep-graph-query.py <file> path <from> <to>
+    dep-graph-query.py <file> top [<n>] [--kind <kind>]
+    dep-graph-query.py <file> find <pattern>
+
+Nodes are given by their label, e.g. `TypeckTables(foo[8f3a]::bar[0])`, or
+by a substring of it if that matches only one node. The disambiguator of the
+crate, the `[8f3a]` above, may be left out, as in `TypeckTables(foo::bar[0])`.
+
+`dependents` lists the nodes that have to be recomputed when the node changes,
+`path` prints a chain of nodes through which a change of `<from>` (usually an
+input like a `Hir` node) reaches `<to>`, and `top` lists the nodes that are
+read by the most other nodes.
+"""
+
+from __future__ import print_function
+import collections
+import json
+import re
+import sys
+
+CRATE_DISAMBIGUATOR = re.compile(r'^(\w+\(\w+)\[[0-9a-f]{4}\]')
+
+
+class DepGraph(object):
+    def __init__(self, path):
+        with open(path) as f:
+            self.nodes = json.load(f)['nodes']
+        self.dependents = [[] for _ in self.nodes]
+        for index, node in enumerate(self.nodes):
+            for target in node['edges']:
+                self.dependents[target].append(index)
+
+    def label(self, index):
+        return self.nodes[index]['label']
+
+    def lookup(self, pattern):
+        labels = [(node['label'], CRATE_DISAMBIGUATOR.sub(r'\1', node['label']))
+                  for node in self.nodes]
+        exact = [i for i, names in enumerate(labels) if pattern in names]
+        if exact:
+            return exact[0]
+        matches = [i for i, names in enumerate(labels)
+                   if any(pattern in name for name in names)]
+        if not matches:
+            fail('no node matches `{}`'.format(pattern))
+        if len(matches) > 1:
+            candidates = '\n'.join('    ' + self.label(i) for i in matches[:20])
+            more = '\n    ...' if len(matches) > 20 else ''
+            fail('`{}` matches {} nodes:\n{}{}'.format(pattern, len(matches), candidates, more))
+        return matches[0]
+
+    def successors(self, index, reverse):
+        return self.dependents[index] if reverse else self.nodes[index]['edges']
+
+    def reachable(self, start, reverse):
+        seen = set([start])
+        queue = collections.deque([start])
+        while queue:
+            for next in self.successors(queue.popleft(), reverse):
+                if next not in seen:
+                    seen.add(next)
+                    queue.append(next)
+        seen.remove(start)
+        return seen
+
+    def path(self, source, target):
+        # Walk from the input towards the nodes that read it, so that the
+        # path is printed in the order in which a change propagates.
+        parents = {source: None}
+        queue = collections.deque([source])
+        while queue:
+            index = queue.popleft()
+            if index == target:
+                path = []
+                while index is not None:
+                    path.append(index)
+                    index = parents[index]
+                return list(reversed(path))
+            for next in self.dependents[index]:
+                if next not in parents:
+                    parents[next] = index
+                    queue.append(next)
+        return None
+
+
+def fail(message):
+    print('error: ' + message, file=sys.stderr)
+    sys.exit(1)
+
+
+def take_flag(args, flag, has_value=False):
+    if flag not in args:
+        return None
+    position = args.index(flag)
+    if not has_value:
+        del args[position]
+        return True
+    if position + 1 >= len(args):
+        fail('`{}` needs a value'.format(flag))
+    value = args[position + 1]
+    del args[position:position + 2]
+    return value
+
+
+def main(args):
+    if len(args) < 2:
+        print(__doc__.strip(), file=sys.stderr)
+        sys.exit(1)
+
+    graph = DepGraph(args[0])
+    command, args = args[1], args[2:]
+    direct = take_flag(args, '--direct')
+    kind = take_flag(args, '--kind', has_value=True)
+
+    if command in ('dependents', 'dependencies') and len(args) == 1:
+        index = graph.lookup(args[0])
+        reverse = command == 'dependents'
+        if direct:
+            result = set(graph.successors(index, reverse))
+        else:
+            result = graph.reachable(index, reverse)
+        for label in sorted(graph.label(i) for i in result):
+            print(label)
+    elif command == 'path' and len(args) == 2:
+        source, target = graph.lookup(args[0]), graph.lookup(args[1])
+        path = graph.path(source, target)
+        if path is None:
+            fail('`{}` does not depend on `{}`'.format(graph.label(target),
+                                                       graph.label(source)))
+        for index in path:
+            print(graph.label(index))
+    elif command == 'top' and len(args) <= 1:
+        count = int(args[0]) if args else 20
+        indices = [i for i, node in enumerate(graph.nodes)
+                   if kind is None or node['kind'] == kind]
+        indices.sort(key=lambda i: len(graph.dependents[i]), reverse=True)
+        for index in indices[:count]:
+            print('{:8} {}'.format(len(graph.dependents[index]), graph.label(index)))
+    elif command == 'find' and len(args) == 1:
+        for node in graph.nodes:
+            if args[0] in node['label']:
+                print(node['label'])
+    else:
+        print(__doc__.strip(), file=sys.stderr)
+        sys.exit(1)
+
+
+if __name__ == '__main__':
+    main(sys.argv[1:])
@@ -0,0 +1,108 @@
+#!/usr/bin/env python
+
+"""
+Measures the time of incremental rebuilds of a crate, to compare how much work
+is skipped when query results are loaded from the on-disk cache.
+
+Usage: incremental-rebuild-bench.py <rustc> <crate root> [<rustc args>...]
+
+The crate is compiled once from scratch, then rebuilt twice without changes
+("no-op"), and twice after appending an unused private function to the crate
+root ("small edit"). Each rebuild is timed with `-Z time-passes`, and the time
+spent loading the on-disk query cache is reported along with the total. All of
+this is done twice, with the on-disk query cache and without it
+(`-Z incremental-queries=no`), so that the two can be compared directly.
+
+The queries cached on disk besides the existing ones are those which are
+re-executed for every body or codegen unit that has to be rebuilt, even when
+the items they are about didn't change: the layouts of types (`layout_raw`),
+the trait queries behind type checking and MIR building (`is_copy_raw`,
+`is_sized_raw`, `is_freeze_raw`, `needs_drop_raw` and `evaluate_obligation`),
+and the signatures type checking reads from the items a body uses (`fn_sig`,
+`impl_trait_ref` and `variances_of`). Their keys are types or canonical goals,
+except for the signature queries, so they cannot be forced from their dep-node
+and had to be recomputed whenever they were needed. A rebuild after a small
+edit is where this shows: the "small edit" rows should show less time type
+checking and in codegen with the cache than without it, for a small increase
+in the time spent loading the query cache.
+"""
+
+from __future__ import print_function
+import os
+import re
+import shutil
+import subprocess
+import sys
+import tempfile
+import time
+
+TIME_PASSES_LINE = re.compile(r'^\s*time: ([0-9.]+)(?:;[^\t]*)?\t(.*)$')
+TYPECK_PASSES = ['type collecting', 'wf checking', 'item-types checking',
+                 'item-bodies checking']
+
+
+def compile(rustc, crate_root, incr_dir, args):
+    cmd = [rustc, '-C', 'incremental=' + incr_dir, '-Z', 'time-passes',
+           '--out-dir', os.path.dirname(incr_dir), crate_root] + args
+    start = time.time()
+    output = subprocess.check_output(cmd, stderr=subprocess.STDOUT)
+    elapsed = time.time() - start
+
+    passes = {}
+    for line in output.decode('utf-8', 'replace').splitlines():
+        match = TIME_PASSES_LINE.match(line)
+        if match:
+            name = match.group(2).strip()
+            passes[name] = passes.get(name, 0.0) + float(match.group(1))
+    return elapsed, passes
+
+
+def report(label, elapsed, passes):
+    print('{:<12} {:8.3f}s total, {:8.3f}s loading the query cache, '
+          '{:8.3f}s type checking, {:8.3f}s codegen'.format(
+              label,
+              elapsed,
+              passes.get('load query result cache', 0.0),
+              sum(passes.get(name, 0.0) for name in TYPECK_PASSES),
+              passes.get('codegen', 0.0)))
+
+
+def main():
+    if len(sys.argv) < 3:
+        print(__doc__.strip(), file=sys.stderr)
+        sys.exit(1)
+
+    rustc, crate_root, args = sys.argv[1], sys.argv[2], sys.argv[3:]
+    work_dir = tempfile.mkdtemp(prefix='incr-bench-')
+    try:
+        src_dir = os.path.join(work_dir, 'src')
+        shutil.copytree(os.path.dirname(os.path.abspath(crate_root)), src_dir)
+        root = os.path.join(src_dir, os.path.basename(crate_root))
+        incr_dir = os.path.join(work_dir, 'incr')
+        os.mkdir(incr_dir)
+
+        with open(root) as f:
+            original = f.read()
+
+        for label, extra_args in [('with the on-disk query cache', []),
+                                  ('without it', ['-Z', 'incremental-queries=no'])]:
+            print(label + ':')
+            with open(root, 'w') as f:
+                f.write(original)
+            shutil.rmtree(incr_dir)
+            os.mkdir(incr_dir)
+
+            bench_args = args + extra_args
+            report('from scratch', *compile(rustc, root, incr_dir, bench_args))
+            for _ in range(2):
+                report('no-op', *compile(rustc, root, incr_dir, bench_args))
+            for i in range(2):
+                with open(root, 'a') as f:
+                    f.write('\n#[allow(dead_code)]\nfn __incr_bench_{}() {{}}\n'.format(i))
+                report('small edit', *compile(rustc, root, incr_dir, bench_args))
+    finally:
+        shutil.rmtree(work_dir)
+
+
+if __name__ == '__main__':
+    main()
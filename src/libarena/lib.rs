@@ -122,6 +122,12 @@ impl<T> TypedArena<T> {
 
         self.chunks.borrow().iter().any(|chunk| chunk.start() <= ptr && ptr < chunk.end())
     }
+
+    /// Returns the size of the chunks allocated by this arena, including the
+    /// space of the current chunk which is not used yet.
+    pub fn allocated_bytes(&self) -> usize {
+        self.chunks.borrow().iter().map(|chunk| chunk.storage.cap() * mem::size_of::<T>()).sum()
+    }
     /// Allocates an object in the `TypedArena`, returning a reference to it.
     #[inline]
     pub fn alloc(&self, object: T) -> &mut T {
@@ -308,6 +314,12 @@ impl DroplessArena {
         self.chunks.borrow().iter().any(|chunk| chunk.start() <= ptr && ptr < chunk.end())
     }
 
+    /// Returns the size of the chunks allocated by this arena, including the
+    /// space of the current chunk which is not used yet.
+    pub fn allocated_bytes(&self) -> usize {
+        self.chunks.borrow().iter().map(|chunk| chunk.storage.cap()).sum()
+    }
+
     #[inline]
     fn align(&self, align: usize) {
         let final_address = ((self.ptr.get() as usize) + align - 1) & !(align - 1);
@@ -451,6 +463,10 @@ impl SyncDroplessArena {
         self.lock.lock().in_arena(ptr)
     }
 
+    pub fn allocated_bytes(&self) -> usize {
+        self.lock.lock().allocated_bytes()
+    }
+
     #[inline(always)]
     pub fn alloc_raw(&self, bytes: usize, align: usize) -> &mut [u8] {
         // Extend the lifetime of the result since it's limited to the lock guard
@@ -479,6 +495,7 @@ mod tests {
     use test::Bencher;
     use super::TypedArena;
     use std::cell::Cell;
+    use std::mem;
 
     #[allow(dead_code)]
     #[derive(Debug, Eq, PartialEq)]
@@ -492,6 +509,22 @@ mod tests {
     pub fn test_unused() {
         let arena: TypedArena<Point> = TypedArena::default();
         assert!(arena.chunks.borrow().is_empty());
+        assert_eq!(arena.allocated_bytes(), 0);
+    }
+
+    #[test]
+    pub fn test_allocated_bytes() {
+        let arena: TypedArena<Point> = TypedArena::default();
+        arena.alloc(Point { x: 1, y: 2, z: 3 });
+        let allocated = arena.allocated_bytes();
+        assert!(allocated >= mem::size_of::<Point>());
+        assert_eq!(allocated % mem::size_of::<Point>(), 0);
+
+        // Allocating more objects than fit into the first chunk adds a chunk.
+        for i in 0..(allocated / mem::size_of::<Point>()) {
+            arena.alloc(Point { x: i as i32, y: 0, z: 0 });
+        }
+        assert!(arena.allocated_bytes() > allocated);
     }
 
     #[test]
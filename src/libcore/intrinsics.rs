@@ -1308,6 +1308,24 @@ extern "rust-intrinsic" {
     /// Emits a `!nontemporal` store according to LLVM (see their docs).
     /// Probably will never become stable.
     pub fn nontemporal_store<T>(ptr: *mut T, val: T);
+
+    /// Allocates `size` bytes with the given alignment on the heap of the
+    /// compile-time interpreter. At runtime, this always returns a null pointer.
+    ///
+    /// The allocation must be freed with `const_deallocate`, or made global with
+    /// `const_make_global`, before the final value of a constant may point to it.
+    #[cfg(not(stage0))]
+    pub fn const_allocate(size: usize, align: usize) -> *mut u8;
+    /// Frees an allocation made by `const_allocate`. `size` and `align` must be
+    /// the ones it was allocated with. At runtime, this does nothing.
+    #[cfg(not(stage0))]
+    pub fn const_deallocate(ptr: *mut u8, size: usize, align: usize);
+    /// Turns an allocation made by `const_allocate` into immutable memory that
+    /// outlives the evaluation, so the final value of a constant may refer to it.
+    /// Everything it points to must already be global. At runtime, this returns
+    /// `ptr` unchanged.
+    #[cfg(not(stage0))]
+    pub fn const_make_global(ptr: *mut u8) -> *const u8;
 }
 
 mod real_intrinsics {
@@ -243,10 +243,8 @@ macro_rules! define_dep_nodes {
                                     hash
                                 };
 
-                                if cfg!(debug_assertions) &&
-                                   !dep_node.kind.can_reconstruct_query_key() &&
-                                   (tcx.sess.opts.debugging_opts.incremental_info ||
-                                    tcx.sess.opts.debugging_opts.query_dep_graph)
+                                if !dep_node.kind.can_reconstruct_query_key() &&
+                                   tcx.sess.opts.enable_dep_node_debug_strs()
                                 {
                                     tcx.dep_graph.register_dep_node_debug_str(dep_node, || {
                                         arg.to_debug_str(tcx)
@@ -266,10 +264,8 @@ macro_rules! define_dep_nodes {
                                     hash
                                 };
 
-                                if cfg!(debug_assertions) &&
-                                   !dep_node.kind.can_reconstruct_query_key() &&
-                                   (tcx.sess.opts.debugging_opts.incremental_info ||
-                                    tcx.sess.opts.debugging_opts.query_dep_graph)
+                                if !dep_node.kind.can_reconstruct_query_key() &&
+                                   tcx.sess.opts.enable_dep_node_debug_strs()
                                 {
                                     tcx.dep_graph.register_dep_node_debug_str(dep_node, || {
                                         tupled_args.to_debug_str(tcx)
@@ -463,6 +459,7 @@ define_dep_nodes!( <'tcx>
     [] MirBuilt(DefId),
     [] MirConst(DefId),
     [] MirValidated(DefId),
+    [] MirInlinerCallees(DefId),
     [] MirOptimized(DefId),
     [] MirShim { instance_def: InstanceDef<'tcx> },
 
@@ -4,6 +4,8 @@ use rustc_data_structures::fx::{FxHashMap, FxHashSet};
 use rustc_data_structures::indexed_vec::{Idx, IndexVec};
 use smallvec::SmallVec;
 use rustc_data_structures::sync::{Lrc, Lock, AtomicU32, Ordering};
+use std::cmp::Reverse;
+use std::collections::VecDeque;
 use std::env;
 use std::hash::Hash;
 use std::collections::hash_map::Entry;
@@ -77,6 +79,32 @@ struct DepGraphData {
 
     // Used for testing, only populated when -Zquery-dep-graph is specified.
     loaded_from_cache: Lock<FxHashMap<DepNodeIndex, bool>>,
+
+    /// For every node of the previous session which could not be marked green, the dependency
+    /// which prevented it. Only populated when -Zincremental-explain is specified.
+    red_causes: Lock<FxHashMap<SerializedDepNodeIndex, RedCause>>,
+}
+
+/// Why a node of the previous session could not be marked green, recorded for
+/// `-Z incremental-explain`. Each variant names the dependency at fault.
+#[derive(Clone, Copy, Debug)]
+enum RedCause {
+    /// The dependency was red, either immediately or after re-executing it.
+    Dependency(SerializedDepNodeIndex),
+    /// The dependency could not be re-executed from its `DepNode` alone.
+    NotForceable(SerializedDepNodeIndex),
+    /// The dependency refers to an item which does not exist anymore.
+    Removed(SerializedDepNodeIndex),
+}
+
+impl RedCause {
+    fn dependency(self) -> SerializedDepNodeIndex {
+        match self {
+            RedCause::Dependency(index) |
+            RedCause::NotForceable(index) |
+            RedCause::Removed(index) => index,
+        }
+    }
 }
 
 pub fn hash_result<R>(hcx: &mut StableHashingContext<'_>, result: &R) -> Option<Fingerprint>
@@ -105,6 +133,7 @@ impl DepGraph {
                 previous: prev_graph,
                 colors: DepNodeColorMap::new(prev_graph_node_count),
                 loaded_from_cache: Default::default(),
+                red_causes: Default::default(),
             })),
         }
     }
@@ -652,6 +681,8 @@ impl DepGraph {
                             immediately red",
                             dep_node,
                             data.previous.index_to_node(dep_dep_node_index));
+                    self.record_red_cause(tcx, data, prev_dep_node_index,
+                                          RedCause::Dependency(dep_dep_node_index));
                     return None
                 }
                 None => {
@@ -684,6 +715,8 @@ impl DepGraph {
                                 if dep_dep_node.extract_def_id(tcx).is_none() {
                                     // If the node does not exist anymore, we
                                     // just fail to mark green.
+                                    self.record_red_cause(tcx, data, prev_dep_node_index,
+                                                          RedCause::Removed(dep_dep_node_index));
                                     return None
                                 } else {
                                     // If the node does exist, it should have
@@ -718,6 +751,8 @@ impl DepGraph {
                                         dependency {:?} was red after forcing",
                                        dep_node,
                                        dep_dep_node);
+                                self.record_red_cause(tcx, data, prev_dep_node_index,
+                                                      RedCause::Dependency(dep_dep_node_index));
                                 return None
                             }
                             None => {
@@ -736,6 +771,8 @@ impl DepGraph {
                         // The DepNode could not be forced.
                         debug!("try_mark_previous_green({:?}) - END - dependency {:?} \
                                 could not be forced", dep_node, dep_dep_node);
+                        self.record_red_cause(tcx, data, prev_dep_node_index,
+                                              RedCause::NotForceable(dep_dep_node_index));
                         return None
                     }
                 }
@@ -788,6 +825,19 @@ impl DepGraph {
         Some(dep_node_index)
     }
 
+    #[inline]
+    fn record_red_cause(
+        &self,
+        tcx: TyCtxt<'_, '_, '_>,
+        data: &DepGraphData,
+        prev_dep_node_index: SerializedDepNodeIndex,
+        cause: RedCause,
+    ) {
+        if unlikely!(tcx.sess.opts.debugging_opts.incremental_explain) {
+            data.red_causes.borrow_mut().insert(prev_dep_node_index, cause);
+        }
+    }
+
     /// Atomically emits some loaded diagnotics, assuming that this only gets called with
     /// `did_allocation` set to `true` on a single thread.
     #[cold]
@@ -837,6 +887,157 @@ impl DepGraph {
         self.node_color(dep_node).map(|c| c.is_green()).unwrap_or(false)
     }
 
+    /// Returns the fingerprint of `dep_node` in the current session together with the
+    /// fingerprints of all of its dependencies. Used by the incremental artifact store to
+    /// record what a codegen unit was built from. Returns `None` if one of the dependencies
+    /// cannot be recognized in another session, i.e. if it is anonymous or has no stable
+    /// hash.
+    pub fn dependency_fingerprints(
+        &self,
+        dep_node: &DepNode
+    ) -> Option<(Fingerprint, Vec<(DepNode, Fingerprint)>)> {
+        let data = self.data.as_ref()?;
+        let current = data.current.borrow();
+        let dep_node_index = *current.node_to_node_index.get(dep_node)?;
+        let node_data = &current.data[dep_node_index];
+
+        let dependencies = node_data.edges.iter().map(|&edge| {
+            let dep_dep_node = &current.data[edge];
+            if dep_dep_node.node.kind.is_anon() || dep_dep_node.fingerprint == Fingerprint::ZERO {
+                None
+            } else {
+                Some((dep_dep_node.node, dep_dep_node.fingerprint))
+            }
+        }).collect::<Option<Vec<_>>>()?;
+
+        Some((node_data.fingerprint, dependencies))
+    }
+
+    /// Returns a path through the previous dep-graph from `dep_node` to each of the input nodes
+    /// it depends on, together with whether the input's fingerprint is different in the current
+    /// session. The paths are the shortest ones and start with `dep_node`. Used to report a
+    /// green query instance whose result turns out to be different, see
+    /// `-Z incremental-verify-queries`.
+    pub fn paths_to_inputs(&self, dep_node: &DepNode) -> Vec<(Vec<DepNode>, bool)> {
+        let data = match self.data {
+            Some(ref data) => data,
+            None => return vec![],
+        };
+        let start = match data.previous.node_to_index_opt(dep_node) {
+            Some(start) => start,
+            None => return vec![],
+        };
+
+        // Breadth-first, remembering where each node was reached from.
+        let mut reached_from = FxHashMap::default();
+        let mut queue = VecDeque::new();
+        let mut inputs = vec![];
+        reached_from.insert(start, start);
+        queue.push_back(start);
+        while let Some(index) = queue.pop_front() {
+            if data.previous.index_to_node(index).kind.is_input() {
+                inputs.push(index);
+                continue;
+            }
+            for &dependency in data.previous.edge_targets_from(index) {
+                if let Entry::Vacant(entry) = reached_from.entry(dependency) {
+                    entry.insert(index);
+                    queue.push_back(dependency);
+                }
+            }
+        }
+
+        let current = data.current.borrow();
+        inputs.into_iter().map(|input| {
+            let mut path = vec![];
+            let mut index = input;
+            while index != start {
+                path.push(data.previous.index_to_node(index));
+                index = reached_from[&index];
+            }
+            path.push(*dep_node);
+            path.reverse();
+
+            let input_node = data.previous.index_to_node(input);
+            let changed = current.node_to_node_index.get(&input_node).map_or(false, |&index| {
+                current.data[index].fingerprint != data.previous.fingerprint_by_index(input)
+            });
+            (path, changed)
+        }).collect()
+    }
+
+    /// Tries to create `dep_node` from a list of dependencies recorded by
+    /// `dependency_fingerprints`, possibly in a different session than the previous one.
+    /// This succeeds if each of the dependencies can be marked green or re-executed and then
+    /// has the recorded fingerprint, just like `try_mark_green` does for the dependencies of
+    /// the previous dep-graph.
+    pub fn try_mark_green_from_dependencies(
+        &self,
+        tcx: TyCtxt<'_, '_, '_>,
+        dep_node: &DepNode,
+        fingerprint: Fingerprint,
+        dependencies: &[(DepNode, Fingerprint)]
+    ) -> Option<DepNodeIndex> {
+        debug!("try_mark_green_from_dependencies({:?}) - BEGIN", dep_node);
+
+        let data = self.data.as_ref()?;
+        let tcx = tcx.global_tcx();
+        let mut current_deps = SmallVec::new();
+
+        for &(ref dep_dep_node, expected_fingerprint) in dependencies {
+            if !self.dep_node_exists(dep_dep_node) {
+                match dep_dep_node.kind {
+                    // These inputs are pre-allocated, so the node does not
+                    // exist anymore.
+                    DepKind::Hir |
+                    DepKind::HirBody |
+                    DepKind::CrateMetadata => return None,
+                    kind if !kind.is_input() &&
+                            self.try_mark_green(tcx, dep_dep_node).is_some() => {}
+                    _ => {
+                        if !crate::ty::query::force_from_dep_node(tcx, dep_dep_node) {
+                            debug!("try_mark_green_from_dependencies({:?}) - END - \
+                                    dependency {:?} could not be forced",
+                                   dep_node, dep_dep_node);
+                            return None
+                        }
+                    }
+                }
+            }
+
+            let dep_dep_node_index = *data.current
+                                          .borrow()
+                                          .node_to_node_index
+                                          .get(dep_dep_node)?;
+            if self.fingerprint_of(dep_dep_node_index) != expected_fingerprint {
+                debug!("try_mark_green_from_dependencies({:?}) - END - \
+                        dependency {:?} has a different fingerprint",
+                       dep_node, dep_dep_node);
+                return None
+            }
+            current_deps.push(dep_dep_node_index);
+        }
+
+        let dep_node_index = data.current.borrow_mut().alloc_node(*dep_node,
+                                                                  current_deps,
+                                                                  fingerprint);
+
+        // The node might have existed in the previous session with a different
+        // set of dependencies, so we have to give it a color.
+        if let Some(prev_index) = data.previous.node_to_index_opt(dep_node) {
+            let color = if data.previous.fingerprint_by_index(prev_index) == fingerprint {
+                DepNodeColor::Green(dep_node_index)
+            } else {
+                DepNodeColor::Red
+            };
+            data.colors.insert(prev_index, color);
+        }
+
+        debug!("try_mark_green_from_dependencies({:?}) - END - successfully marked as green",
+               dep_node);
+        Some(dep_node_index)
+    }
+
     // This method loads all on-disk cacheable query results into memory, so
     // they can be written out to the new cache file again. Most query results
     // will already be in memory but in the case where we marked something as
@@ -892,6 +1093,118 @@ impl DepGraph {
         let dep_node_index = data.current.borrow().node_to_node_index[dep_node];
         data.loaded_from_cache.borrow().get(&dep_node_index).cloned()
     }
+
+    /// Prints the report of `-Z incremental-explain`. For every codegen unit which could not be
+    /// reused, and for every kind of node which was re-executed, it shows the changed input at
+    /// the root of the chain of red dependencies which prevented marking the node green.
+    pub fn print_red_cause_report(&self) {
+        let data = match self.data {
+            Some(ref data) => data,
+            None => return,
+        };
+        let red_causes = data.red_causes.borrow();
+
+        // Follows the recorded causes down to a node which was red on its own.
+        let root_cause = |mut index: SerializedDepNodeIndex| {
+            let mut path = vec![index];
+            while let Some(&cause) = red_causes.get(&index) {
+                index = cause.dependency();
+                path.push(index);
+                match cause {
+                    RedCause::Dependency(_) => {}
+                    RedCause::NotForceable(_) | RedCause::Removed(_) => {
+                        return (cause, path);
+                    }
+                }
+            }
+            (RedCause::Dependency(index), path)
+        };
+        let describe = |cause: RedCause| {
+            let index = cause.dependency();
+            let node = data.previous.index_to_node(index);
+            match cause {
+                RedCause::NotForceable(_) => {
+                    format!("`{:?}` could not be re-executed", node)
+                }
+                RedCause::Removed(_) => format!("`{:?}` was removed", node),
+                RedCause::Dependency(_) if node.kind.is_input() => {
+                    format!("`{:?}` changed", node)
+                }
+                RedCause::Dependency(_) => match data.colors.get(index) {
+                    Some(DepNodeColor::Red) => format!("`{:?}` has a different result", node),
+                    _ => format!("`{:?}` is new or could not be hashed", node),
+                },
+            }
+        };
+
+        let mut nodes: Vec<_> = red_causes.keys().cloned().collect();
+        nodes.sort();
+
+        println!("[incremental]");
+        println!("[incremental] Recompilation Causes");
+        println!("[incremental] --------------------------------\
+                  ----------------------------------------------\
+                  ------------");
+
+        if nodes.is_empty() {
+            println!("[incremental] Everything that was tried could be reused.");
+            return;
+        }
+
+        println!("[incremental]");
+        for &index in &nodes {
+            let node = data.previous.index_to_node(index);
+            if node.kind != DepKind::CompileCodegenUnit {
+                continue;
+            }
+            let (cause, path) = root_cause(index);
+            println!("[incremental] {:?} was recompiled because {}", node, describe(cause));
+            let path: Vec<String> = path[1..path.len() - 1].iter().map(|&index| {
+                format!("{:?}", data.previous.index_to_node(index))
+            }).collect();
+            if !path.is_empty() {
+                println!("[incremental]     via {}", path.join(" <- "));
+            }
+        }
+
+        struct KindStat {
+            re_executed: usize,
+            changed: usize,
+            causes: FxHashMap<String, usize>,
+        }
+
+        let mut stats: FxHashMap<DepKind, KindStat> = FxHashMap::default();
+        for &index in &nodes {
+            let kind = data.previous.index_to_node(index).kind;
+            let stat = stats.entry(kind).or_insert_with(|| KindStat {
+                re_executed: 0,
+                changed: 0,
+                causes: FxHashMap::default(),
+            });
+            stat.re_executed += 1;
+            if data.colors.get(index) == Some(DepNodeColor::Red) {
+                stat.changed += 1;
+            }
+            *stat.causes.entry(describe(root_cause(index).0)).or_insert(0) += 1;
+        }
+
+        let mut stats: Vec<_> = stats.into_iter().collect();
+        stats.sort_by_key(|&(kind, ref stat)| (Reverse(stat.re_executed), format!("{:?}", kind)));
+
+        println!("[incremental]");
+        println!("[incremental]  {:<36}| {:<13}| {:<13}|", "Node Kind", "Not Green", "Changed");
+        for (kind, stat) in stats {
+            println!("[incremental]  {:<36}|{:>13} |{:>13} |",
+                     format!("{:?}", kind), stat.re_executed, stat.changed);
+
+            let mut causes: Vec<_> = stat.causes.into_iter().collect();
+            causes.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+            for (cause, count) in causes.into_iter().take(5) {
+                println!("[incremental]      {} x because {}", count, cause);
+            }
+        }
+        println!("[incremental]");
+    }
 }
 
 /// A "work product" is an intermediate result that we save into the
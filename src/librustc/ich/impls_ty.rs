@@ -438,6 +438,7 @@ impl_stable_hash_for!(
         ReallocateNonBasePtr,
         DeallocateNonBasePtr,
         HeapAllocZeroBytes,
+        HeapAllocLeaked,
         Unreachable,
         ReadFromReturnPointer,
         UnimplementedTraitSelection,
@@ -452,6 +453,7 @@ impl_stable_hash_for!(
         GeneratorResumedAfterPanic,
         ReferencedConstant,
         InfiniteLoop,
+        StepLimitReached(limit),
         ReadUndefBytes(offset),
         InvalidDiscriminant(val),
         Panic { msg, file, line, col },
@@ -66,7 +66,6 @@
 extern crate getopts;
 #[macro_use] extern crate lazy_static;
 #[macro_use] extern crate scoped_tls;
-#[cfg(windows)]
 extern crate libc;
 #[macro_use] extern crate rustc_data_structures;
 
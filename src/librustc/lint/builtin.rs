@@ -22,6 +22,12 @@ declare_lint! {
     "constant evaluation detected erroneous expression"
 }
 
+declare_lint! {
+    pub LONG_RUNNING_CONST_EVAL,
+    Warn,
+    "constant evaluation that came close to the `const_eval_limit`"
+}
+
 declare_lint! {
     pub UNUSED_IMPORTS,
     Warn,
@@ -421,6 +427,7 @@ impl LintPass for HardwiredLints {
             PUB_USE_OF_PRIVATE_EXTERN_CRATE,
             INVALID_TYPE_PARAM_DEFAULT,
             CONST_ERR,
+            LONG_RUNNING_CONST_EVAL,
             RENAMED_AND_REMOVED_LINTS,
             SAFE_EXTERN_STATICS,
             SAFE_PACKED_BORROWS,
@@ -13,6 +13,7 @@ use rustc_data_structures::sync::Once;
 pub fn update_limits(sess: &Session, krate: &ast::Crate) {
     update_limit(krate, &sess.recursion_limit, "recursion_limit", 64);
     update_limit(krate, &sess.type_length_limit, "type_length_limit", 1048576);
+    update_limit(krate, &sess.const_eval_limit, "const_eval_limit", 1_000_000);
 }
 
 fn update_limit(krate: &ast::Crate, limit: &Once<usize>, name: &str, default: usize) {
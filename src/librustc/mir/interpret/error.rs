@@ -274,6 +274,8 @@ pub enum EvalErrorKind<'tcx, O> {
     Layout(layout::LayoutError<'tcx>),
     HeapAllocZeroBytes,
     HeapAllocNonPowerOfTwoAlignment(u64),
+    /// Heap memory which was neither freed nor made global outlives the evaluation
+    HeapAllocLeaked,
     Unreachable,
     Panic {
         msg: Symbol,
@@ -294,6 +296,8 @@ pub enum EvalErrorKind<'tcx, O> {
     GeneratorResumedAfterReturn,
     GeneratorResumedAfterPanic,
     InfiniteLoop,
+    /// The interpreter executed more steps than `#![const_eval_limit]` allows
+    StepLimitReached(usize),
 }
 
 pub type EvalResult<'tcx, T = ()> = Result<T, EvalError<'tcx>>;
@@ -395,6 +399,8 @@ impl<'tcx, O> EvalErrorKind<'tcx, O> {
             HeapAllocNonPowerOfTwoAlignment(_) =>
                 "tried to re-, de-, or allocate heap memory with alignment that is not a power of \
                 two",
+            HeapAllocLeaked =>
+                "heap memory that was not made global outlives the evaluation",
             Unreachable =>
                 "entered unreachable code",
             Panic { .. } =>
@@ -426,6 +432,8 @@ impl<'tcx, O> EvalErrorKind<'tcx, O> {
             GeneratorResumedAfterPanic => "generator resumed after panicking",
             InfiniteLoop =>
                 "duplicate interpreter state observed here, const evaluation will never terminate",
+            StepLimitReached(_) =>
+                "const evaluation took too long",
         }
     }
 }
@@ -501,6 +509,9 @@ impl<'tcx, O: fmt::Debug> fmt::Debug for EvalErrorKind<'tcx, O> {
                 write!(f, "the evaluated program panicked at '{}', {}:{}:{}", msg, file, line, col),
             InvalidDiscriminant(val) =>
                 write!(f, "encountered invalid enum discriminant {}", val),
+            StepLimitReached(limit) =>
+                write!(f, "const evaluation took too long: it reached the limit of {} steps",
+                       limit),
             _ => write!(f, "{}", self.description()),
         }
     }
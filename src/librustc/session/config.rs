@@ -48,6 +48,22 @@ pub enum Sanitizer {
     Thread,
 }
 
+/// The output format of `-Z time-passes` and `-Z time-llvm-passes`.
+#[derive(Clone, Copy, Debug, PartialEq, Hash)]
+pub enum TimePassesFormat {
+    /// Indented, human readable lines.
+    Text,
+    /// One JSON object per line, for consumption by scripts.
+    Json,
+}
+
+/// The extra detail `-Z borrowck-explain` adds to MIR borrowck errors.
+#[derive(Clone, Copy, Debug, PartialEq, Hash)]
+pub enum BorrowckExplain {
+    /// A note listing the outlives constraints that keep the borrow alive.
+    Graph,
+}
+
 #[derive(Clone, Copy, Debug, PartialEq, Hash)]
 pub enum OptLevel {
     No,         // -O0
@@ -619,13 +635,15 @@ impl Options {
     /// Returns `true` if there is a reason to build the dep graph.
     pub fn build_dep_graph(&self) -> bool {
         self.incremental.is_some() || self.debugging_opts.dump_dep_graph
+            || self.debugging_opts.dump_dep_graph_json.is_some()
             || self.debugging_opts.query_dep_graph
     }
 
     #[inline(always)]
     pub fn enable_dep_node_debug_strs(&self) -> bool {
-        cfg!(debug_assertions)
-            && (self.debugging_opts.query_dep_graph || self.debugging_opts.incremental_info)
+        (cfg!(debug_assertions)
+            && (self.debugging_opts.query_dep_graph || self.debugging_opts.incremental_info))
+            || self.debugging_opts.incremental_explain
     }
 
     pub fn file_path_mapping(&self) -> FilePathMapping {
@@ -817,11 +835,16 @@ macro_rules! options {
                   or the path to the linker plugin");
         pub const parse_merge_functions: Option<&str> =
             Some("one of: `disabled`, `trampolines`, or `aliases`");
+        pub const parse_time_passes_format: Option<&str> =
+            Some("either `text` or `json`");
+        pub const parse_borrowck_explain: Option<&str> =
+            Some("`graph`");
     }
 
     #[allow(dead_code)]
     mod $mod_set {
-        use super::{$struct_name, Passes, Sanitizer, LtoCli, LinkerPluginLto};
+        use super::{$struct_name, Passes, Sanitizer, LtoCli, LinkerPluginLto, TimePassesFormat};
+        use super::BorrowckExplain;
         use rustc_target::spec::{LinkerFlavor, MergeFunctions, PanicStrategy, RelroLevel};
         use std::path::PathBuf;
         use std::str::FromStr;
@@ -981,6 +1004,23 @@ macro_rules! options {
             true
         }
 
+        fn parse_time_passes_format(slot: &mut TimePassesFormat, v: Option<&str>) -> bool {
+            match v {
+                Some("text") => *slot = TimePassesFormat::Text,
+                Some("json") => *slot = TimePassesFormat::Json,
+                _ => return false,
+            }
+            true
+        }
+
+        fn parse_borrowck_explain(slot: &mut Option<BorrowckExplain>, v: Option<&str>) -> bool {
+            match v {
+                Some("graph") => *slot = Some(BorrowckExplain::Graph),
+                _ => return false,
+            }
+            true
+        }
+
         fn parse_linker_flavor(slote: &mut Option<LinkerFlavor>, v: Option<&str>) -> bool {
             match v.and_then(LinkerFlavor::from_str) {
                 Some(lf) => *slote = Some(lf),
@@ -1164,12 +1204,18 @@ options! {DebuggingOptions, DebuggingSetter, basic_debugging_options,
         "make unnamed regions display as '# (where # is some non-ident unique id)"),
     borrowck: Option<String> = (None, parse_opt_string, [UNTRACKED],
         "select which borrowck is used (`ast`, `mir`, `migrate`, or `compare`)"),
+    borrowck_explain: Option<BorrowckExplain> = (None, parse_borrowck_explain, [UNTRACKED],
+        "explain MIR borrowck errors in more detail (`graph`: list the outlives constraints \
+         that keep the borrow alive)"),
     two_phase_borrows: bool = (false, parse_bool, [UNTRACKED],
         "use two-phase reserved/active distinction for `&mut` borrows in MIR borrowck"),
     two_phase_beyond_autoref: bool = (false, parse_bool, [UNTRACKED],
         "when using two-phase-borrows, allow two phases even for non-autoref `&mut` borrows"),
     time_passes: bool = (false, parse_bool, [UNTRACKED],
         "measure time of each rustc pass"),
+    time_passes_format: TimePassesFormat = (TimePassesFormat::Text,
+        parse_time_passes_format, [UNTRACKED],
+        "output format of `-Z time-passes` and `-Z time-llvm-passes` (`text` or `json`)"),
     count_llvm_insns: bool = (false, parse_bool,
         [UNTRACKED_WITH_WARNING(true,
         "The output generated by `-Z count_llvm_insns` might not be reliable \
@@ -1245,16 +1291,34 @@ options! {DebuggingOptions, DebuggingSetter, basic_debugging_options,
         "enable incremental compilation support for queries (experimental)"),
     incremental_info: bool = (false, parse_bool, [UNTRACKED],
         "print high-level information about incremental reuse (or the lack thereof)"),
+    incremental_crate_cache_size: Option<usize> = (None, parse_opt_uint, [UNTRACKED],
+        "maximum size in MiB of the incremental cache of a crate; larger caches are deleted"),
+    incremental_cache_size: Option<usize> = (None, parse_opt_uint, [UNTRACKED],
+        "maximum size in MiB of the incremental compilation directory; the caches of \
+         the least recently compiled crates are deleted first"),
+    incremental_gc: bool = (false, parse_bool, [UNTRACKED],
+        "only garbage collect the `-C incremental` directory, then exit without compiling"),
+    incremental_artifact_store: Option<PathBuf> = (None, parse_opt_pathbuf, [UNTRACKED],
+        "share the object files of codegen units with other sessions through a content-addressed \
+         store in the given directory (requires `-C incremental`)"),
+    incremental_explain: bool = (false, parse_bool, [UNTRACKED],
+        "print which changed inputs caused codegen units and queries to be re-executed"),
     incremental_dump_hash: bool = (false, parse_bool, [UNTRACKED],
         "dump hash information in textual format to stdout"),
     incremental_verify_ich: bool = (false, parse_bool, [UNTRACKED],
         "verify incr. comp. hashes of green query instances"),
+    incremental_verify_queries: Option<usize> = (None, parse_opt_uint, [UNTRACKED],
+        "recompute the results of green query instances from scratch and compare their hashes; \
+         only every N-th instance is checked if N is greater than 1"),
     incremental_ignore_spans: bool = (false, parse_bool, [UNTRACKED],
         "ignore spans during ICH computation -- used for testing"),
     instrument_mcount: bool = (false, parse_bool, [TRACKED],
         "insert function instrument code for mcount-based tracing"),
     dump_dep_graph: bool = (false, parse_bool, [UNTRACKED],
         "dump the dependency graph to $RUST_DEP_GRAPH (default: /tmp/dep_graph.gv)"),
+    dump_dep_graph_json: Option<PathBuf> = (None, parse_opt_pathbuf, [UNTRACKED],
+        "dump the complete dependency graph to the given file in a compact JSON format, \
+         which can be explored with `src/etc/dep-graph-query.py`"),
     query_dep_graph: bool = (false, parse_bool, [UNTRACKED],
         "enable queries of the dependency graph for regression testing"),
     profile_queries: bool = (false, parse_bool, [UNTRACKED],
@@ -1285,6 +1349,12 @@ options! {DebuggingOptions, DebuggingSetter, basic_debugging_options,
         "print the result of the monomorphization collection pass"),
     mir_opt_level: usize = (1, parse_uint, [TRACKED],
         "set the MIR optimization level (0-3, default: 1)"),
+    inline_mir: Option<bool> = (None, parse_opt_bool, [TRACKED],
+        "enable MIR inlining (default: yes when optimizing, or at mir-opt-level >= 2)"),
+    inline_mir_threshold: Option<usize> = (None, parse_opt_uint, [TRACKED],
+        "the cost above which MIR inlining rejects a callee (default: 50)"),
+    inline_mir_hint_threshold: Option<usize> = (None, parse_opt_uint, [TRACKED],
+        "the cost above which MIR inlining rejects a callee marked `#[inline]` (default: 100)"),
     mutable_noalias: Option<bool> = (None, parse_opt_bool, [TRACKED],
         "emit noalias metadata for mutable references (default: yes on LLVM >= 6)"),
     dump_mir: Option<String> = (None, parse_opt_string, [UNTRACKED],
@@ -1299,6 +1369,9 @@ options! {DebuggingOptions, DebuggingSetter, basic_debugging_options,
         "the directory the MIR is dumped into"),
     dump_mir_graphviz: bool = (false, parse_bool, [UNTRACKED],
         "in addition to `.mir` files, create graphviz `.dot` files"),
+    dump_mir_html: bool = (false, parse_bool, [UNTRACKED],
+        "in addition to `.mir` files, create one self-contained `.html` file per function \
+        that shows its MIR after each dumped pass"),
     dump_mir_exclude_pass_number: bool = (false, parse_bool, [UNTRACKED],
         "if set, exclude the pass number when dumping MIR (used in tests)"),
     mir_emit_retag: bool = (false, parse_bool, [TRACKED],
@@ -1344,7 +1417,9 @@ options! {DebuggingOptions, DebuggingSetter, basic_debugging_options,
     dont_buffer_diagnostics: bool = (false, parse_bool, [UNTRACKED],
         "emit diagnostics rather than buffering (breaks NLL error downgrading, sorting)."),
     polonius: bool = (false, parse_bool, [UNTRACKED],
-        "enable polonius-based borrow-checker"),
+        "use polonius to find borrows that conflict with later accesses and lifetimes that \
+         don't outlive each other; move errors still come from NLL \
+         (implies `-Z borrowck=mir -Z two-phase-borrows`)"),
     codegen_time_graph: bool = (false, parse_bool, [UNTRACKED],
         "generate a graphical HTML report of time spent in codegen and LLVM"),
     thinlto: Option<bool> = (None, parse_opt_bool, [TRACKED],
@@ -1401,6 +1476,17 @@ options! {DebuggingOptions, DebuggingSetter, basic_debugging_options,
         "run the self profiler"),
     profile_json: bool = (false, parse_bool, [UNTRACKED],
         "output a json file with profiler results"),
+    self_profile_trace: Option<PathBuf> = (None, parse_opt_pathbuf, [UNTRACKED],
+        "write the timeline of the self profiler to a file, in Chrome's trace event format"),
+    self_profile_folded: Option<PathBuf> = (None, parse_opt_pathbuf, [UNTRACKED],
+        "write the stacks of queries recorded by the self profiler to a file, \
+         as folded stacks for flame graphs"),
+    self_profile_query_keys: bool = (false, parse_bool, [UNTRACKED],
+        "include the query keys in the output of `-Z self-profile-trace` and \
+         `-Z self-profile-folded`"),
+    self_profile_memory: bool = (false, parse_bool, [UNTRACKED],
+        "record the growth of the arenas and of the resident set size (RSS) of the process \
+         for every query in the self profiler; this slows down compilation considerably"),
     emit_stack_sizes: bool = (false, parse_bool, [UNTRACKED],
         "emits a section containing stack size metadata"),
     plt: Option<bool> = (None, parse_opt_bool, [TRACKED],
@@ -2052,6 +2138,13 @@ pub fn build_session_options_and_crate_config(
         );
     }
 
+    if debugging_opts.incremental_artifact_store.is_some() && incremental.is_none() {
+        early_error(
+            error_format,
+            "`-Z incremental-artifact-store` requires `-C incremental=<dir>`",
+        );
+    }
+
     let mut prints = Vec::<PrintRequest>::new();
     if cg.target_cpu.as_ref().map_or(false, |s| s == "help") {
         prints.push(PrintRequest::TargetCPUs);
@@ -2575,7 +2668,7 @@ mod tests {
     use crate::lint;
     use crate::middle::cstore;
     use crate::session::config::{build_configuration, build_session_options_and_crate_config};
-    use crate::session::config::{LtoCli, LinkerPluginLto};
+    use crate::session::config::{BorrowckExplain, LtoCli, LinkerPluginLto, TimePassesFormat};
     use crate::session::build_session;
     use crate::session::search_paths::SearchPath;
     use std::collections::{BTreeMap, BTreeSet};
@@ -3124,6 +3217,10 @@ mod tests {
         assert_eq!(reference.dep_tracking_hash(), opts.dep_tracking_hash());
         opts.debugging_opts.time_passes = true;
         assert_eq!(reference.dep_tracking_hash(), opts.dep_tracking_hash());
+        opts.debugging_opts.time_passes_format = TimePassesFormat::Json;
+        assert_eq!(reference.dep_tracking_hash(), opts.dep_tracking_hash());
+        opts.debugging_opts.borrowck_explain = Some(BorrowckExplain::Graph);
+        assert_eq!(reference.dep_tracking_hash(), opts.dep_tracking_hash());
         opts.debugging_opts.count_llvm_insns = true;
         assert_eq!(reference.dep_tracking_hash(), opts.dep_tracking_hash());
         opts.debugging_opts.time_llvm_passes = true;
@@ -3164,6 +3261,8 @@ mod tests {
         assert_eq!(reference.dep_tracking_hash(), opts.dep_tracking_hash());
         opts.debugging_opts.dump_dep_graph = true;
         assert_eq!(reference.dep_tracking_hash(), opts.dep_tracking_hash());
+        opts.debugging_opts.dump_dep_graph_json = Some(PathBuf::from("dep_graph.json"));
+        assert_eq!(reference.dep_tracking_hash(), opts.dep_tracking_hash());
         opts.debugging_opts.query_dep_graph = true;
         assert_eq!(reference.dep_tracking_hash(), opts.dep_tracking_hash());
         opts.debugging_opts.no_analysis = true;
@@ -3184,6 +3283,8 @@ mod tests {
         assert_eq!(reference.dep_tracking_hash(), opts.dep_tracking_hash());
         opts.debugging_opts.dump_mir_graphviz = true;
         assert_eq!(reference.dep_tracking_hash(), opts.dep_tracking_hash());
+        opts.debugging_opts.dump_mir_html = true;
+        assert_eq!(reference.dep_tracking_hash(), opts.dep_tracking_hash());
 
         // Make sure changing a [TRACKED] option changes the hash
         opts = reference.clone();
@@ -3234,6 +3335,18 @@ mod tests {
         opts.debugging_opts.mir_opt_level = 3;
         assert!(reference.dep_tracking_hash() != opts.dep_tracking_hash());
 
+        opts = reference.clone();
+        opts.debugging_opts.inline_mir = Some(false);
+        assert!(reference.dep_tracking_hash() != opts.dep_tracking_hash());
+
+        opts = reference.clone();
+        opts.debugging_opts.inline_mir_threshold = Some(123);
+        assert!(reference.dep_tracking_hash() != opts.dep_tracking_hash());
+
+        opts = reference.clone();
+        opts.debugging_opts.inline_mir_hint_threshold = Some(123);
+        assert!(reference.dep_tracking_hash() != opts.dep_tracking_hash());
+
         opts = reference.clone();
         opts.debugging_opts.relro_level = Some(RelroLevel::Full);
         assert!(reference.dep_tracking_hash() != opts.dep_tracking_hash());
@@ -12,7 +12,7 @@ use crate::middle::dependency_format;
 use crate::session::config::OutputType;
 use crate::session::search_paths::{PathKind, SearchPath};
 use crate::util::nodemap::{FxHashMap, FxHashSet};
-use crate::util::common::{duration_to_secs_str, ErrorReported};
+use crate::util::common::{duration_to_secs_str, set_time_passes_format, ErrorReported};
 use crate::util::common::ProfileQueriesMsg;
 
 use rustc_data_structures::base_n;
@@ -107,6 +107,10 @@ pub struct Session {
     /// The maximum number of stackframes allowed in const eval.
     pub const_eval_stack_frame_limit: usize,
 
+    /// The maximum number of steps const eval may take for a single constant,
+    /// as set by `#![const_eval_limit]`, or zero for no limit.
+    pub const_eval_limit: Once<usize>,
+
     /// The metadata::creader module may inject an allocator/panic_runtime
     /// dependency if it didn't already find one, and this tracks what was
     /// injected.
@@ -138,6 +142,10 @@ pub struct Session {
     /// Data about code being compiled, gathered during compilation.
     pub code_stats: Lock<CodeStats>,
 
+    /// The files `-Z dump-mir-html` has started to write. Their passes are
+    /// appended to a `.part` file, which is completed once all MIR is dumped.
+    pub mir_html_dumps: Lock<FxHashSet<PathBuf>>,
+
     next_node_id: OneThread<Cell<ast::NodeId>>,
 
     /// If `-zfuel=crate=n` is specified, `Some(crate)`.
@@ -844,6 +852,19 @@ impl Session {
         }
     }
 
+    /// Formats a query key for `SelfProfiler::start_query`, if `-Z self-profile-query-keys` is
+    /// set. This has to happen before the profiler is borrowed: the `Debug` output of keys like
+    /// `DefId` runs queries, which record events of their own.
+    #[inline]
+    pub fn profiler_query_key<K: fmt::Debug>(&self, key: &K) -> Option<String> {
+        if unlikely!(self.self_profiling_active) &&
+           self.opts.debugging_opts.self_profile_query_keys {
+            Some(format!("{:?}", key))
+        } else {
+            None
+        }
+    }
+
     pub fn print_profiler_results(&self) {
         let mut profiler = self.self_profiling.borrow_mut();
         profiler.print_results(&self.opts);
@@ -854,6 +875,24 @@ impl Session {
         profiler.save_results(&self.opts);
     }
 
+    /// Writes the timeline of the self profiler for `-Z self-profile-trace` and
+    /// `-Z self-profile-folded`.
+    pub fn save_profiler_timeline(&self) {
+        let profiler = self.self_profiling.borrow();
+        if let Some(ref path) = self.opts.debugging_opts.self_profile_trace {
+            if let Err(e) = profiler.save_chrome_trace(path) {
+                self.err(&format!("failed to write self-profile trace `{}`: {}",
+                                  path.display(), e));
+            }
+        }
+        if let Some(ref path) = self.opts.debugging_opts.self_profile_folded {
+            if let Err(e) = profiler.save_folded_stacks(path) {
+                self.err(&format!("failed to write self-profile stacks `{}`: {}",
+                                  path.display(), e));
+            }
+        }
+    }
+
     pub fn print_perf_stats(&self) {
         println!(
             "Total time spent computing symbol hashes:      {}",
@@ -1148,6 +1187,8 @@ pub fn build_session_(
     let print_fuel_crate = sopts.debugging_opts.print_fuel.clone();
     let print_fuel = AtomicU64::new(0);
 
+    set_time_passes_format(sopts.debugging_opts.time_passes_format);
+
     let working_dir = env::current_dir().unwrap_or_else(|e|
         p_s.span_diagnostic
             .fatal(&format!("Current directory is invalid: {}", e))
@@ -1162,7 +1203,10 @@ pub fn build_session_(
     };
 
     let self_profiling_active = sopts.debugging_opts.self_profile ||
-                                sopts.debugging_opts.profile_json;
+                                sopts.debugging_opts.profile_json ||
+                                sopts.debugging_opts.self_profile_trace.is_some() ||
+                                sopts.debugging_opts.self_profile_folded.is_some();
+    let self_profiler = SelfProfiler::new(sopts.debugging_opts.self_profile_memory);
 
     let sess = Session {
         target: target_cfg,
@@ -1186,6 +1230,7 @@ pub fn build_session_(
         recursion_limit: Once::new(),
         type_length_limit: Once::new(),
         const_eval_stack_frame_limit: 100,
+        const_eval_limit: Once::new(),
         next_node_id: OneThread::new(Cell::new(NodeId::from_u32(1))),
         allocator_kind: Once::new(),
         injected_panic_runtime: Once::new(),
@@ -1193,7 +1238,7 @@ pub fn build_session_(
         incr_comp_session: OneThread::new(RefCell::new(IncrCompSession::NotInitialized)),
         cgu_reuse_tracker,
         self_profiling_active,
-        self_profiling: Lock::new(SelfProfiler::new()),
+        self_profiling: Lock::new(self_profiler),
         profile_channel: Lock::new(None),
         perf_stats: PerfStats {
             symbol_hash_time: Lock::new(Duration::from_secs(0)),
@@ -1203,6 +1248,7 @@ pub fn build_session_(
             normalize_projection_ty: AtomicUsize::new(0),
         },
         code_stats: Default::default(),
+        mir_html_dumps: Default::default(),
         optimization_fuel_crate,
         optimization_fuel,
         print_fuel_crate,
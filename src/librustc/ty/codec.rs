@@ -15,6 +15,7 @@ use std::intrinsics;
 use crate::ty::{self, Ty, TyCtxt};
 use crate::ty::subst::Substs;
 use crate::mir::interpret::Allocation;
+use crate::ty::layout::LayoutDetails;
 
 /// The shorthand encoding uses an enum's variant index `usize`
 /// and is offset by this value so it never matches a real variant.
@@ -264,6 +265,15 @@ pub fn decode_allocation<'a, 'tcx, D>(decoder: &mut D)
     Ok(decoder.tcx().intern_const_alloc(Decodable::decode(decoder)?))
 }
 
+#[inline]
+pub fn decode_layout<'a, 'tcx, D>(decoder: &mut D)
+    -> Result<&'tcx LayoutDetails, D::Error>
+    where D: TyDecoder<'a, 'tcx>,
+          'tcx: 'a,
+{
+    Ok(decoder.tcx().intern_layout(Decodable::decode(decoder)?))
+}
+
 #[macro_export]
 macro_rules! __impl_decoder_methods {
     ($($name:ident -> $ty:ty;)*) => {
@@ -404,6 +414,15 @@ macro_rules! implement_ty_decoder {
                     decode_allocation(self)
                 }
             }
+
+            impl<$($typaram),*> SpecializedDecoder<&'tcx $crate::ty::layout::LayoutDetails>
+            for $DecoderName<$($typaram),*> {
+                fn specialized_decode(
+                    &mut self
+                ) -> Result<&'tcx $crate::ty::layout::LayoutDetails, Self::Error> {
+                    decode_layout(self)
+                }
+            }
         }
     }
 }
@@ -108,6 +108,19 @@ pub struct GlobalArenas<'tcx> {
     const_allocs: TypedArena<interpret::Allocation>,
 }
 
+impl<'tcx> GlobalArenas<'tcx> {
+    fn allocated_bytes(&self) -> usize {
+        self.layout.allocated_bytes() +
+        self.generics.allocated_bytes() +
+        self.trait_def.allocated_bytes() +
+        self.adt_def.allocated_bytes() +
+        self.steal_mir.allocated_bytes() +
+        self.mir.allocated_bytes() +
+        self.tables.allocated_bytes() +
+        self.const_allocs.allocated_bytes()
+    }
+}
+
 type InternedSet<'tcx, T> = Lock<FxHashMap<Interned<'tcx, T>, ()>>;
 
 pub struct CtxtInterners<'tcx> {
@@ -1534,7 +1547,8 @@ impl<'a, 'gcx, 'tcx> TyCtxt<'a, 'gcx, 'tcx> {
     /// or by opting into an edition after 2015.
     pub fn two_phase_borrows(self) -> bool {
         self.sess.rust_2018() || self.features().nll ||
-        self.sess.opts.debugging_opts.two_phase_borrows
+        self.sess.opts.debugging_opts.two_phase_borrows ||
+        self.sess.opts.debugging_opts.polonius
     }
 
     /// What mode(s) of borrowck should we run? AST? MIR? both?
@@ -1562,13 +1576,18 @@ impl<'a, 'gcx, 'tcx> TyCtxt<'a, 'gcx, 'tcx> {
         //   a user's attempt to specify `-Z borrowck=compare`, which
         //   we arguably do not need anymore and should remove.)
         //
+        // * `-Z polonius` means the same, as the point of it is to see
+        //   the errors Polonius finds, not those of the AST borrowck.
+        //
         // * Otherwise, if no `-Z borrowck=...` flag was given (or
         //   if `borrowck=ast` was specified), then use the default
         //   as required by the edition.
         //
         // * Otherwise, use the behavior requested via `-Z borrowck=...`
 
-        if self.features().nll { return BorrowckMode::Mir; }
+        if self.features().nll || self.sess.opts.debugging_opts.polonius {
+            return BorrowckMode::Mir;
+        }
 
         match self.sess.opts.borrowck_mode {
             mode @ BorrowckMode::Mir |
@@ -2191,6 +2210,36 @@ impl<'a, 'tcx> TyCtxt<'a, 'tcx, 'tcx> {
     }
 }
 
+impl<'a, 'gcx, 'tcx> TyCtxt<'a, 'gcx, 'tcx> {
+    /// Returns the number of bytes allocated by the global arenas of the
+    /// current worker thread and by the interner arena shared by all threads.
+    pub fn arena_allocated_bytes(self) -> usize {
+        self.global_arenas.allocated_bytes() + self.global_interners.arena.allocated_bytes()
+    }
+
+    /// Returns the number of entries of each interner of the global context.
+    pub fn interner_sizes(self) -> Vec<(&'static str, usize)> {
+        let interners = &self.global_interners;
+        vec![
+            ("type", interners.type_.borrow().len()),
+            ("type_list", interners.type_list.borrow().len()),
+            ("substs", interners.substs.borrow().len()),
+            ("canonical_var_infos", interners.canonical_var_infos.borrow().len()),
+            ("region", interners.region.borrow().len()),
+            ("existential_predicates", interners.existential_predicates.borrow().len()),
+            ("predicates", interners.predicates.borrow().len()),
+            ("clauses", interners.clauses.borrow().len()),
+            ("goal", interners.goal.borrow().len()),
+            ("goal_list", interners.goal_list.borrow().len()),
+            ("projs", interners.projs.borrow().len()),
+            ("lazy_const", interners.lazy_const.borrow().len()),
+            ("stability", self.stability_interner.borrow().len()),
+            ("allocation", self.allocation_interner.borrow().len()),
+            ("layout", self.layout_interner.borrow().len()),
+        ]
+    }
+}
+
 
 /// An entry in an interner.
 struct Interned<'tcx, T: 'tcx+?Sized>(&'tcx T);
@@ -191,6 +191,18 @@ impl<'tcx> QueryDescription<'tcx> for queries::evaluate_obligation<'tcx> {
     fn describe(_tcx: TyCtxt<'_, '_, '_>, goal: CanonicalPredicateGoal<'tcx>) -> Cow<'static, str> {
         format!("evaluating trait selection obligation `{}`", goal.value.value).into()
     }
+
+    #[inline]
+    fn cache_on_disk(_: TyCtxt<'_, 'tcx, 'tcx>, _: Self::Key) -> bool {
+        true
+    }
+
+    #[inline]
+    fn try_load_from_disk<'a>(tcx: TyCtxt<'a, 'tcx, 'tcx>,
+                              id: SerializedDepNodeIndex)
+                              -> Option<Self::Value> {
+        tcx.queries.on_disk_cache.try_load_query_result(tcx, id)
+    }
 }
 
 impl<'tcx> QueryDescription<'tcx> for queries::evaluate_goal<'tcx> {
@@ -268,6 +280,18 @@ impl<'tcx> QueryDescription<'tcx> for queries::is_copy_raw<'tcx> {
                 -> Cow<'static, str> {
         format!("computing whether `{}` is `Copy`", env.value).into()
     }
+
+    #[inline]
+    fn cache_on_disk(_: TyCtxt<'_, 'tcx, 'tcx>, _: Self::Key) -> bool {
+        true
+    }
+
+    #[inline]
+    fn try_load_from_disk<'a>(tcx: TyCtxt<'a, 'tcx, 'tcx>,
+                              id: SerializedDepNodeIndex)
+                              -> Option<Self::Value> {
+        tcx.queries.on_disk_cache.try_load_query_result(tcx, id)
+    }
 }
 
 impl<'tcx> QueryDescription<'tcx> for queries::is_sized_raw<'tcx> {
@@ -275,6 +299,18 @@ impl<'tcx> QueryDescription<'tcx> for queries::is_sized_raw<'tcx> {
                 -> Cow<'static, str> {
         format!("computing whether `{}` is `Sized`", env.value).into()
     }
+
+    #[inline]
+    fn cache_on_disk(_: TyCtxt<'_, 'tcx, 'tcx>, _: Self::Key) -> bool {
+        true
+    }
+
+    #[inline]
+    fn try_load_from_disk<'a>(tcx: TyCtxt<'a, 'tcx, 'tcx>,
+                              id: SerializedDepNodeIndex)
+                              -> Option<Self::Value> {
+        tcx.queries.on_disk_cache.try_load_query_result(tcx, id)
+    }
 }
 
 impl<'tcx> QueryDescription<'tcx> for queries::is_freeze_raw<'tcx> {
@@ -282,6 +318,18 @@ impl<'tcx> QueryDescription<'tcx> for queries::is_freeze_raw<'tcx> {
                 -> Cow<'static, str> {
         format!("computing whether `{}` is freeze", env.value).into()
     }
+
+    #[inline]
+    fn cache_on_disk(_: TyCtxt<'_, 'tcx, 'tcx>, _: Self::Key) -> bool {
+        true
+    }
+
+    #[inline]
+    fn try_load_from_disk<'a>(tcx: TyCtxt<'a, 'tcx, 'tcx>,
+                              id: SerializedDepNodeIndex)
+                              -> Option<Self::Value> {
+        tcx.queries.on_disk_cache.try_load_query_result(tcx, id)
+    }
 }
 
 impl<'tcx> QueryDescription<'tcx> for queries::needs_drop_raw<'tcx> {
@@ -289,6 +337,18 @@ impl<'tcx> QueryDescription<'tcx> for queries::needs_drop_raw<'tcx> {
                 -> Cow<'static, str> {
         format!("computing whether `{}` needs drop", env.value).into()
     }
+
+    #[inline]
+    fn cache_on_disk(_: TyCtxt<'_, 'tcx, 'tcx>, _: Self::Key) -> bool {
+        true
+    }
+
+    #[inline]
+    fn try_load_from_disk<'a>(tcx: TyCtxt<'a, 'tcx, 'tcx>,
+                              id: SerializedDepNodeIndex)
+                              -> Option<Self::Value> {
+        tcx.queries.on_disk_cache.try_load_query_result(tcx, id)
+    }
 }
 
 impl<'tcx> QueryDescription<'tcx> for queries::layout_raw<'tcx> {
@@ -296,6 +356,18 @@ impl<'tcx> QueryDescription<'tcx> for queries::layout_raw<'tcx> {
                 -> Cow<'static, str> {
         format!("computing layout of `{}`", env.value).into()
     }
+
+    #[inline]
+    fn cache_on_disk(_: TyCtxt<'_, 'tcx, 'tcx>, _: Self::Key) -> bool {
+        true
+    }
+
+    #[inline]
+    fn try_load_from_disk<'a>(tcx: TyCtxt<'a, 'tcx, 'tcx>,
+                              id: SerializedDepNodeIndex)
+                              -> Option<Self::Value> {
+        tcx.queries.on_disk_cache.try_load_query_result(tcx, id)
+    }
 }
 
 impl<'tcx> QueryDescription<'tcx> for queries::super_predicates_of<'tcx> {
@@ -1020,3 +1092,6 @@ impl_disk_cacheable_query!(predicates_of, |_, def_id| def_id.is_local());
 impl_disk_cacheable_query!(used_trait_imports, |_, def_id| def_id.is_local());
 impl_disk_cacheable_query!(codegen_fn_attrs, |_, _| true);
 impl_disk_cacheable_query!(specialization_graph_of, |_, _| true);
+impl_disk_cacheable_query!(fn_sig, |_, def_id| def_id.is_local());
+impl_disk_cacheable_query!(impl_trait_ref, |_, def_id| def_id.is_local());
+impl_disk_cacheable_query!(variances_of, |_, def_id| def_id.is_local());
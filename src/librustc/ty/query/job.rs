@@ -198,7 +198,12 @@ impl<'tcx> QueryLatch<'tcx> {
             // we have to be in the `wait` call. This is ensured by the deadlock handler
             // getting the self.info lock.
             rayon_core::mark_blocked();
-            waiter.condvar.wait(&mut info);
+
+            // Condition variables may wake up spuriously. We were really resumed once
+            // `set` or the deadlock handler removed us from the list of waiters.
+            while info.waiters.iter().any(|w| Lrc::ptr_eq(w, waiter)) {
+                waiter.condvar.wait(&mut info);
+            }
         }
     }
 
@@ -483,7 +488,7 @@ pub unsafe fn handle_deadlock() {
     let syntax_pos_globals = &*syntax_pos_globals;
     thread::spawn(move || {
         tls::GCX_PTR.set(gcx_ptr, || {
-            syntax_pos::GLOBALS.set(syntax_pos_globals, || {
+            syntax::GLOBALS.set(syntax_globals, || {
                 syntax_pos::GLOBALS.set(syntax_pos_globals, || {
                     tls::with_thread_locals(|| {
                         tls::with_global(|tcx| deadlock(tcx, &registry))
@@ -507,7 +512,8 @@ fn deadlock(tcx: TyCtxt<'_, '_, '_>, registry: &rayon_core::Registry) {
     });
 
     let mut wakelist = Vec::new();
-    let mut jobs: Vec<_> = tcx.queries.collect_active_jobs();
+    let active_jobs: Vec<_> = tcx.queries.collect_active_jobs();
+    let mut jobs = active_jobs.clone();
 
     let mut found_cycle = false;
 
@@ -524,7 +530,11 @@ fn deadlock(tcx: TyCtxt<'_, '_, '_>, registry: &rayon_core::Registry) {
     // which in turn will wait on X causing a deadlock. We have a false dependency from
     // X to Y due to Rayon waiting and a true dependency from Y to X. The algorithm here
     // only considers the true dependency and won't detect a cycle.
-    assert!(found_cycle);
+    // Report the active queries instead of hanging, so such cases can be found.
+    if !found_cycle {
+        report_deadlock(tcx, &active_jobs);
+        process::abort();
+    }
 
     // FIXME: Ensure this won't cause a deadlock before we return
     for waiter in wakelist.into_iter() {
@@ -533,3 +543,35 @@ fn deadlock(tcx: TyCtxt<'_, '_, '_>, registry: &rayon_core::Registry) {
 
     on_panic.disable();
 }
+
+/// Prints the active queries along with the queries waiting on them. This is
+/// used when the deadlock handler could not find a query cycle to break.
+#[cfg(parallel_compiler)]
+fn report_deadlock<'tcx>(tcx: TyCtxt<'_, 'tcx, '_>, jobs: &[Lrc<QueryJob<'tcx>>]) {
+    // Only the names of the queries and their spans are printed: describing a
+    // query, or formatting its key, may execute other queries, which would
+    // block this thread as well.
+    let source_map = tcx.sess.source_map();
+    eprintln!("error: internal compiler error: deadlock detected without a query cycle");
+    eprintln!("note: the following {} queries were active:", jobs.len());
+    for job in jobs {
+        eprintln!("  `{}` at {}",
+                  job.info.query.name(),
+                  source_map.span_to_string(job.info.span));
+        if let Some(ref parent) = job.parent {
+            eprintln!("    called by `{}` at {}",
+                      parent.info.query.name(),
+                      source_map.span_to_string(parent.info.span));
+        }
+        for waiter in job.latch.info.lock().waiters.iter() {
+            match waiter.query {
+                Some(ref query) => {
+                    eprintln!("    waited on by `{}` at {}",
+                              query.info.query.name(),
+                              source_map.span_to_string(waiter.span));
+                }
+                None => eprintln!("    waited on by code outside of queries"),
+            }
+        }
+    }
+}
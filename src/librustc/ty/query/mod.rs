@@ -247,6 +247,12 @@ define_queries! { <'tcx>
 
         [no_hash] fn mir_validated: MirValidated(DefId) -> &'tcx Steal<mir::Mir<'tcx>>,
 
+        /// The functions called directly by the validated MIR of a local function, with the
+        /// substitutions they are called with. The MIR inliner uses this to detect call cycles
+        /// without requesting the optimized MIR of the functions involved.
+        [] fn mir_inliner_callees: MirInlinerCallees(DefId)
+            -> Lrc<Vec<(DefId, &'tcx Substs<'tcx>)>>,
+
         /// MIR after our optimization passes have run. This is MIR that is ready
         /// for codegen. This is also the only query that can fetch non-local MIR, at present.
         [] fn optimized_mir: MirOptimized(DefId) -> &'tcx mir::Mir<'tcx>,
@@ -224,6 +224,15 @@ impl<'sess> OnDiskCache<'sess> {
                 encode_query_results::<check_match<'_>, _>(tcx, enc, qri)?;
                 encode_query_results::<codegen_fn_attrs<'_>, _>(tcx, enc, qri)?;
                 encode_query_results::<specialization_graph_of<'_>, _>(tcx, enc, qri)?;
+                encode_query_results::<fn_sig<'_>, _>(tcx, enc, qri)?;
+                encode_query_results::<impl_trait_ref<'_>, _>(tcx, enc, qri)?;
+                encode_query_results::<variances_of<'_>, _>(tcx, enc, qri)?;
+                encode_query_results::<is_copy_raw<'_>, _>(tcx, enc, qri)?;
+                encode_query_results::<is_sized_raw<'_>, _>(tcx, enc, qri)?;
+                encode_query_results::<is_freeze_raw<'_>, _>(tcx, enc, qri)?;
+                encode_query_results::<needs_drop_raw<'_>, _>(tcx, enc, qri)?;
+                encode_query_results::<layout_raw<'_>, _>(tcx, enc, qri)?;
+                encode_query_results::<evaluate_obligation<'_>, _>(tcx, enc, qri)?;
 
                 // const eval is special, it only encodes successfully evaluated constants
                 use crate::ty::query::QueryAccessors;
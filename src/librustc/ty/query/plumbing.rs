@@ -390,7 +390,8 @@ impl<'a, 'gcx, 'tcx> TyCtxt<'a, 'gcx, 'tcx> {
 
         if dep_node.kind.is_anon() {
             profq_msg!(self, ProfileQueriesMsg::ProviderBegin);
-            self.sess.profiler(|p| p.start_query(Q::NAME, Q::CATEGORY));
+            let profiler_key = self.sess.profiler_query_key(&key);
+            self.sess.profiler(|p| p.start_query(Q::NAME, Q::CATEGORY, profiler_key));
 
             let ((result, dep_node_index), diagnostics) = with_diagnostics(|diagnostics| {
                 self.start_query(job.job.clone(), diagnostics, |tcx| {
@@ -473,16 +474,29 @@ impl<'a, 'gcx, 'tcx> TyCtxt<'a, 'gcx, 'tcx> {
             None
         };
 
+        let verify_query =
+            unlikely!(self.sess.opts.debugging_opts.incremental_verify_queries.is_some()) &&
+            self.should_verify_query(dep_node);
+
         let result = if let Some(result) = result {
             profq_msg!(self, ProfileQueriesMsg::CacheHit);
             self.sess.profiler(|p| p.record_query_hit(Q::NAME, Q::CATEGORY));
 
+            // If -Zincremental-verify-queries is specified, recompute the
+            // result that was loaded from the cache and make sure that it
+            // still has the same fingerprint.
+            if verify_query {
+                let recomputed = self.recompute_green_query::<Q>(key);
+                self.incremental_verify_ich::<Q>(&recomputed, dep_node, dep_node_index);
+            }
+
             result
         } else {
             // We could not load a result from the on-disk cache, so
             // recompute.
 
-            self.sess.profiler(|p| p.start_query(Q::NAME, Q::CATEGORY));
+            let profiler_key = self.sess.profiler_query_key(&key);
+            self.sess.profiler(|p| p.start_query(Q::NAME, Q::CATEGORY, profiler_key));
 
             // The dep-graph for this computation is already in
             // place
@@ -491,12 +505,15 @@ impl<'a, 'gcx, 'tcx> TyCtxt<'a, 'gcx, 'tcx> {
             });
 
             self.sess.profiler(|p| p.end_query(Q::NAME, Q::CATEGORY));
+
             result
         };
 
         // If -Zincremental-verify-ich is specified, re-hash results from
         // the cache and make sure that they have the expected fingerprint.
-        if unlikely!(self.sess.opts.debugging_opts.incremental_verify_ich) {
+        // -Zincremental-verify-queries does the same, which checks results
+        // that were just recomputed.
+        if unlikely!(self.sess.opts.debugging_opts.incremental_verify_ich) || verify_query {
             self.incremental_verify_ich::<Q>(&result, dep_node, dep_node_index);
         }
 
@@ -530,8 +547,68 @@ impl<'a, 'gcx, 'tcx> TyCtxt<'a, 'gcx, 'tcx> {
 
         let old_hash = self.dep_graph.fingerprint_of(dep_node_index);
 
-        assert!(new_hash == old_hash, "Found unstable fingerprints \
-            for {:?}", dep_node);
+        if new_hash != old_hash {
+            self.report_unstable_fingerprint::<Q>(dep_node);
+        }
+    }
+
+    /// Whether `-Z incremental-verify-queries` samples the query instance
+    /// with the given `DepNode`. The sample only depends on the `DepNode`, so
+    /// that the same instances are checked in every session.
+    fn should_verify_query(self, dep_node: &DepNode) -> bool {
+        match self.sess.opts.debugging_opts.incremental_verify_queries {
+            Some(n) if n > 1 => dep_node.hash.to_smaller_hash() % n as u64 == 0,
+            Some(_) => true,
+            None => false,
+        }
+    }
+
+    /// Recomputes the result of a green query instance from scratch, for
+    /// `-Z incremental-verify-queries`. This runs in the job of the query
+    /// being loaded, like a regular execution, but without recording any
+    /// dependencies. The diagnostics of the instance were already emitted
+    /// when it was marked green, so those of the recomputation are dropped.
+    #[inline(never)]
+    #[cold]
+    fn recompute_green_query<Q: QueryDescription<'gcx>>(self, key: Q::Key) -> Q::Value {
+        let job = tls::with_related_context(self, |icx| icx.query.clone())
+            .expect("green query instance loaded outside of its query job");
+
+        let (result, _) = with_diagnostics(|diagnostics| {
+            self.start_query(job, diagnostics, |tcx| {
+                tcx.dep_graph.with_ignore(|| Q::compute(tcx.global_tcx(), key))
+            })
+        });
+        result
+    }
+
+    /// Reports a green query instance whose result doesn't have the fingerprint
+    /// it had in the previous session, and stops compilation. Since the
+    /// instance is green, none of its dependencies changed as far as the
+    /// dep-graph knows, so the error shows the paths to the inputs it depends
+    /// on: either one of them changed after all, or the query reads something
+    /// which isn't tracked.
+    #[inline(never)]
+    #[cold]
+    fn report_unstable_fingerprint<Q: QueryDescription<'gcx>>(self, dep_node: &DepNode) {
+        let mut err = self.sess.struct_err(&format!(
+            "incremental verification failed: the result of the green query `{}` \
+             has a different fingerprint than in the previous session",
+            Q::NAME));
+        err.note(&format!("dep-node: {:?}", dep_node));
+
+        let mut paths = self.dep_graph.paths_to_inputs(dep_node);
+        // Inputs which changed after all are the likely culprits.
+        paths.sort_by_key(|&(_, changed)| !changed);
+        for (path, changed) in paths {
+            let path = path.iter().map(|node| format!("{:?}", node)).collect::<Vec<_>>();
+            err.note(&format!("{} input: {}",
+                              if changed { "changed" } else { "unchanged" },
+                              path.join(" -> ")));
+        }
+
+        err.emit();
+        self.sess.abort_if_errors();
     }
 
     #[inline(always)]
@@ -553,7 +630,8 @@ impl<'a, 'gcx, 'tcx> TyCtxt<'a, 'gcx, 'tcx> {
                 key, dep_node);
 
         profq_msg!(self, ProfileQueriesMsg::ProviderBegin);
-        self.sess.profiler(|p| p.start_query(Q::NAME, Q::CATEGORY));
+        let profiler_key = self.sess.profiler_query_key(&key);
+        self.sess.profiler(|p| p.start_query(Q::NAME, Q::CATEGORY, profiler_key));
 
         let ((result, dep_node_index), diagnostics) = with_diagnostics(|diagnostics| {
             self.start_query(job.job.clone(), diagnostics, |tcx| {
@@ -1255,6 +1333,7 @@ pub fn force_from_dep_node<'a, 'gcx, 'lcx>(tcx: TyCtxt<'a, 'gcx, 'lcx>,
         DepKind::MirConstQualif => { force!(mir_const_qualif, def_id!()); }
         DepKind::MirConst => { force!(mir_const, def_id!()); }
         DepKind::MirValidated => { force!(mir_validated, def_id!()); }
+        DepKind::MirInlinerCallees => { force!(mir_inliner_callees, def_id!()); }
         DepKind::MirOptimized => { force!(optimized_mir, def_id!()); }
 
         DepKind::BorrowCheck => { force!(borrowck, def_id!()); }
@@ -1498,4 +1577,7 @@ impl_load_from_cache!(
     UsedTraitImports => used_trait_imports,
     CodegenFnAttrs => codegen_fn_attrs,
     SpecializationGraph => specialization_graph_of,
+    FnSignature => fn_sig,
+    ImplTraitRef => impl_trait_ref,
+    ItemVariances => variances_of,
 );
@@ -3,11 +3,13 @@
 use rustc_data_structures::sync::Lock;
 
 use std::cell::{RefCell, Cell};
-use std::collections::HashMap;
+use std::cmp;
+use std::collections::{BTreeMap, HashMap};
 use std::fmt::Debug;
 use std::hash::{Hash, BuildHasher};
 use std::panic;
 use std::env;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::time::{Duration, Instant};
 
 use std::sync::mpsc::{Sender};
@@ -16,6 +18,8 @@ use crate::ty::TyCtxt;
 use crate::dep_graph::{DepNode};
 use lazy_static;
 use crate::session::Session;
+use crate::session::config::TimePassesFormat;
+use rustc_serialize::json::{self, as_json};
 
 // The name of the associated type for `Fn` return types
 pub const FN_OUTPUT_NAME: &str = "Output";
@@ -26,6 +30,11 @@ pub const FN_OUTPUT_NAME: &str = "Output";
 pub struct ErrorReported;
 
 thread_local!(static TIME_DEPTH: Cell<usize> = Cell::new(0));
+thread_local!(static TIME_PARENTS: RefCell<Vec<String>> = RefCell::new(Vec::new()));
+
+// This is global rather than stored in the `Session` because the codegen
+// worker threads report their timings without having access to one.
+static TIME_PASSES_JSON: AtomicBool = AtomicBool::new(false);
 
 lazy_static! {
     static ref DEFAULT_HOOK: Box<dyn Fn(&panic::PanicInfo<'_>) + Sync + Send + 'static> = {
@@ -138,6 +147,33 @@ pub fn set_time_depth(depth: usize) {
     TIME_DEPTH.with(|slot| slot.set(depth));
 }
 
+/// Read the name of the innermost pass currently being timed by `time()`.
+/// Like `time_depth()`, this is used to carry the nesting of passes over to
+/// other threads.
+pub fn time_parent() -> Option<String> {
+    TIME_PARENTS.with(|parents| parents.borrow().last().cloned())
+}
+
+/// Sets the pass that passes timed on this thread are reported to be nested
+/// in, see `time_parent()`.
+pub fn set_time_parent(parent: Option<String>) {
+    TIME_PARENTS.with(|parents| *parents.borrow_mut() = parent.into_iter().collect());
+}
+
+/// Selects how `time()` and `print_time_passes_entry()` print their results.
+/// This affects all threads and is set when the `Session` is created.
+pub fn set_time_passes_format(format: TimePassesFormat) {
+    TIME_PASSES_JSON.store(format == TimePassesFormat::Json, Ordering::Relaxed);
+}
+
+pub fn time_passes_format() -> TimePassesFormat {
+    if TIME_PASSES_JSON.load(Ordering::Relaxed) {
+        TimePassesFormat::Json
+    } else {
+        TimePassesFormat::Text
+    }
+}
+
 pub fn time<T, F>(sess: &Session, what: &str, f: F) -> T where
     F: FnOnce() -> T,
 {
@@ -160,16 +196,23 @@ pub fn time_ext<T, F>(do_it: bool, sess: Option<&Session>, what: &str, f: F) ->
             profq_msg(sess, ProfileQueriesMsg::TimeBegin(what.to_string()))
         }
     }
+    TIME_PARENTS.with(|parents| parents.borrow_mut().push(what.to_string()));
+    let start_cpu = thread_cpu_time();
     let start = Instant::now();
     let rv = f();
     let dur = start.elapsed();
+    let cpu_dur = match (start_cpu, thread_cpu_time()) {
+        (Some(start_cpu), Some(end_cpu)) => Some(end_cpu - start_cpu),
+        _ => None,
+    };
+    TIME_PARENTS.with(|parents| parents.borrow_mut().pop());
     if let Some(sess) = sess {
         if cfg!(debug_assertions) {
             profq_msg(sess, ProfileQueriesMsg::TimeEnd)
         }
     }
 
-    print_time_passes_entry_internal(what, dur);
+    print_time_passes_entry_internal(what, dur, cpu_dur);
 
     TIME_DEPTH.with(|slot| slot.set(old));
 
@@ -187,14 +230,27 @@ pub fn print_time_passes_entry(do_it: bool, what: &str, dur: Duration) {
         r
     });
 
-    print_time_passes_entry_internal(what, dur);
+    print_time_passes_entry_internal(what, dur, None);
 
     TIME_DEPTH.with(|slot| slot.set(old));
 }
 
-fn print_time_passes_entry_internal(what: &str, dur: Duration) {
+fn print_time_passes_entry_internal(what: &str, dur: Duration, cpu_dur: Option<Duration>) {
     let indentation = TIME_DEPTH.with(|slot| slot.get());
 
+    if time_passes_format() == TimePassesFormat::Json {
+        let parent = time_parent();
+        println!("{{\"pass\":{},\"depth\":{},\"parent\":{},\"wall_time\":{},\
+                  \"cpu_time\":{},\"rss\":{}}}",
+                 as_json(&what),
+                 indentation.saturating_sub(1),
+                 parent.as_ref().map_or("null".to_string(), |p| as_json(p).to_string()),
+                 duration_to_secs_str(dur),
+                 cpu_dur.map_or("null".to_string(), duration_to_secs_str),
+                 get_resident().map_or("null".to_string(), |n| n.to_string()));
+        return;
+    }
+
     let mem_string = match get_resident() {
         Some(n) => {
             let mb = n as f64 / 1_000_000.0;
@@ -209,6 +265,64 @@ fn print_time_passes_entry_internal(what: &str, dur: Duration) {
              what);
 }
 
+/// Prints the timings LLVM collected for `-Z time-llvm-passes` in the JSON
+/// format of `-Z time-passes-format=json`, one line per pass. `report` is the
+/// object produced by LLVM's `TimerGroup::printAllJSONValues`, whose keys
+/// have the form `time.<group>.<pass>.<wall|user|sys|mem>`.
+pub fn print_llvm_pass_timings_json(report: &str) {
+    #[derive(Default)]
+    struct PassTimes {
+        wall: f64,
+        user: f64,
+        sys: f64,
+        mem: Option<f64>,
+    }
+
+    let report = match json::Json::from_str(report) {
+        Ok(json::Json::Object(report)) => report,
+        _ => return,
+    };
+    let mut passes: BTreeMap<(String, String), PassTimes> = BTreeMap::new();
+    for (key, value) in report {
+        let value = match value.as_f64() {
+            Some(value) => value,
+            None => continue,
+        };
+        let key = key.trim_start_matches("time.");
+        let (name, field) = match key.rfind('.') {
+            Some(i) => (&key[..i], &key[i + 1..]),
+            None => continue,
+        };
+        let (group, pass) = match name.find('.') {
+            Some(i) => (name[..i].to_string(), name[i + 1..].to_string()),
+            None => continue,
+        };
+        let times = passes.entry((group, pass)).or_default();
+        match field {
+            "wall" => times.wall = value,
+            "user" => times.user = value,
+            "sys" => times.sys = value,
+            "mem" => times.mem = Some(value),
+            _ => {}
+        }
+    }
+
+    // Like LLVM's own report, list the most expensive passes first.
+    let mut passes: Vec<_> = passes.into_iter().collect();
+    passes.sort_by(|a, b| b.1.wall.partial_cmp(&a.1.wall).unwrap_or(cmp::Ordering::Equal));
+    for ((group, pass), times) in passes {
+        // Individual LLVM passes are often much shorter than a millisecond,
+        // so use more precision than for the passes of rustc itself.
+        println!("{{\"llvm_pass\":{},\"group\":{},\"wall_time\":{:.6},\"cpu_time\":{:.6},\
+                  \"mem\":{}}}",
+                 as_json(&pass),
+                 as_json(&group),
+                 times.wall,
+                 times.user + times.sys,
+                 times.mem.map_or("null".to_string(), |mem| (mem as u64).to_string()));
+    }
+}
+
 // Hack up our own formatting for the duration to make it easier for scripts
 // to parse (always use the same number of decimal places and the same unit).
 pub fn duration_to_secs_str(dur: Duration) -> String {
@@ -252,7 +366,7 @@ pub fn record_time<T, F>(accu: &Lock<Duration>, f: F) -> T where
 
 // Memory reporting
 #[cfg(unix)]
-fn get_resident() -> Option<usize> {
+pub fn get_resident() -> Option<usize> {
     use std::fs;
 
     let field = 1;
@@ -264,7 +378,7 @@ fn get_resident() -> Option<usize> {
 }
 
 #[cfg(windows)]
-fn get_resident() -> Option<usize> {
+pub fn get_resident() -> Option<usize> {
     type BOOL = i32;
     type DWORD = u32;
     type HANDLE = *mut u8;
@@ -300,6 +414,68 @@ fn get_resident() -> Option<usize> {
     }
 }
 
+// The CPU time spent by the current thread so far, in user and kernel mode.
+// Passes which run on several threads, like LLVM's optimizations and code
+// generation, only report the time of the thread which started them.
+#[cfg(target_os = "linux")]
+fn thread_cpu_time() -> Option<Duration> {
+    use std::mem;
+
+    let mut usage: libc::rusage = unsafe { mem::zeroed() };
+    if unsafe { libc::getrusage(libc::RUSAGE_THREAD, &mut usage) } != 0 {
+        return None;
+    }
+    let to_duration = |tv: libc::timeval| {
+        Duration::new(tv.tv_sec as u64, tv.tv_usec as u32 * 1000)
+    };
+    Some(to_duration(usage.ru_utime) + to_duration(usage.ru_stime))
+}
+
+#[cfg(windows)]
+fn thread_cpu_time() -> Option<Duration> {
+    type BOOL = i32;
+    type DWORD = u32;
+    type HANDLE = *mut u8;
+    use std::mem;
+    #[repr(C)]
+    #[allow(non_snake_case)]
+    struct FILETIME {
+        dwLowDateTime: DWORD,
+        dwHighDateTime: DWORD,
+    }
+    extern "system" {
+        fn GetCurrentThread() -> HANDLE;
+        fn GetThreadTimes(hThread: HANDLE,
+                          lpCreationTime: *mut FILETIME,
+                          lpExitTime: *mut FILETIME,
+                          lpKernelTime: *mut FILETIME,
+                          lpUserTime: *mut FILETIME) -> BOOL;
+    }
+    let mut creation: FILETIME = unsafe { mem::zeroed() };
+    let mut exit: FILETIME = unsafe { mem::zeroed() };
+    let mut kernel: FILETIME = unsafe { mem::zeroed() };
+    let mut user: FILETIME = unsafe { mem::zeroed() };
+    let ok = unsafe {
+        GetThreadTimes(GetCurrentThread(), &mut creation, &mut exit, &mut kernel, &mut user)
+    };
+    if ok == 0 {
+        return None;
+    }
+    // `FILETIME` counts in units of 100 nanoseconds.
+    let to_duration = |ft: FILETIME| {
+        let ticks = ((ft.dwHighDateTime as u64) << 32) | ft.dwLowDateTime as u64;
+        Duration::new(ticks / 10_000_000, (ticks % 10_000_000) as u32 * 100)
+    };
+    Some(to_duration(kernel) + to_duration(user))
+}
+
+// Other platforms have no portable per-thread CPU clock, so `cpu_time` is
+// `null` there.
+#[cfg(not(any(target_os = "linux", windows)))]
+fn thread_cpu_time() -> Option<Duration> {
+    None
+}
+
 pub fn indent<R, F>(op: F) -> R where
     R: Debug,
     F: FnOnce() -> R,
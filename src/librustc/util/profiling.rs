@@ -1,10 +1,16 @@
 use std::collections::{BTreeMap, HashMap};
+use std::collections::hash_map::Entry;
+use std::fmt::Write as FmtWrite;
 use std::fs;
 use std::io::{self, Write};
+use std::path::Path;
 use std::thread::ThreadId;
 use std::time::Instant;
 
 use crate::session::config::{Options, OptLevel};
+use crate::ty::tls;
+use crate::util::common::get_resident;
+use rustc_serialize::json::as_json;
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Ord, PartialOrd)]
 pub enum ProfileCategory {
@@ -19,7 +25,13 @@ pub enum ProfileCategory {
 
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub enum ProfilerEvent {
-    QueryStart { query_name: &'static str, category: ProfileCategory, time: Instant },
+    QueryStart {
+        query_name: &'static str,
+        category: ProfileCategory,
+        time: Instant,
+        /// Index of the query key in `SelfProfiler::query_keys`, if keys are recorded.
+        key: Option<usize>,
+    },
     QueryEnd { query_name: &'static str, category: ProfileCategory, time: Instant },
     GenericActivityStart { category: ProfileCategory, time: Instant },
     GenericActivityEnd { category: ProfileCategory, time: Instant },
@@ -29,6 +41,10 @@ pub enum ProfilerEvent {
     IncrementalLoadResultEnd { query_name: &'static str, time: Instant },
     QueryBlockedStart { query_name: &'static str, category: ProfileCategory, time: Instant },
     QueryBlockedEnd { query_name: &'static str, category: ProfileCategory, time: Instant },
+    /// Recorded right after every start and right before every end of a query or activity
+    /// with `-Z self-profile-memory`. `resident_bytes` is the resident set size (RSS) of the
+    /// whole process, not the heap of the compiler; it is 0 on platforms where it is unknown.
+    MemorySample { arena_bytes: usize, resident_bytes: usize },
 }
 
 impl ProfilerEvent {
@@ -46,18 +62,71 @@ impl ProfilerEvent {
             QueryCacheHit { .. } |
             QueryCount { .. } |
             IncrementalLoadResultEnd { .. } |
-            QueryBlockedEnd { .. } => false,
+            QueryBlockedEnd { .. } |
+            MemorySample { .. } => false,
         }
     }
 }
 
 pub struct SelfProfiler {
     events: HashMap<ThreadId, Vec<ProfilerEvent>>,
+    /// The threads in the order in which they recorded their first event.
+    threads: Vec<ThreadId>,
+    /// The query keys passed to `start_query`, see `Session::profiler_query_key`.
+    query_keys: Vec<String>,
+    /// Whether queries and activities should record `MemorySample` events.
+    record_memory: bool,
+    /// The number of entries of the interners of the type context, see `record_interner_sizes`.
+    interner_sizes: Vec<(&'static str, usize)>,
+    start_time: Instant,
+}
+
+/// Whether a frame of the timeline begins or ends, see `SelfProfiler::walk_timeline`.
+#[derive(Clone, Copy, PartialEq)]
+enum Phase {
+    Begin,
+    End,
+}
+
+/// The growth of the memory usage between two `MemorySample` events, in bytes.
+#[derive(Clone, Copy, Debug, Default)]
+struct MemoryGrowth {
+    arena_bytes: i64,
+    resident_bytes: i64,
+}
+
+impl MemoryGrowth {
+    fn between(start: (usize, usize), end: (usize, usize)) -> MemoryGrowth {
+        MemoryGrowth {
+            arena_bytes: end.0 as i64 - start.0 as i64,
+            resident_bytes: end.1 as i64 - start.1 as i64,
+        }
+    }
+
+    fn add(&mut self, other: MemoryGrowth) {
+        self.arena_bytes += other.arena_bytes;
+        self.resident_bytes += other.resident_bytes;
+    }
+
+    fn sub(&mut self, other: MemoryGrowth) {
+        self.arena_bytes -= other.arena_bytes;
+        self.resident_bytes -= other.resident_bytes;
+    }
+}
+
+/// The memory samples of a frame of the query stack in `calculate_thread_results`.
+#[derive(Default)]
+struct MemoryFrame {
+    /// The `(arena_bytes, resident_bytes)` sample at the start of the frame.
+    start: Option<(usize, usize)>,
+    /// The growth during the frame's children, which is not attributed to the frame.
+    children: MemoryGrowth,
 }
 
 struct CategoryResultData {
     query_times: BTreeMap<&'static str, u64>,
     query_cache_stats: BTreeMap<&'static str, (u64, u64)>, //(hits, total)
+    query_memory: BTreeMap<&'static str, MemoryGrowth>,
 }
 
 impl CategoryResultData {
@@ -65,6 +134,7 @@ impl CategoryResultData {
         CategoryResultData {
             query_times: BTreeMap::new(),
             query_cache_stats: BTreeMap::new(),
+            query_memory: BTreeMap::new(),
         }
     }
 
@@ -72,6 +142,14 @@ impl CategoryResultData {
         self.query_times.iter().map(|(_, time)| time).sum()
     }
 
+    fn total_memory(&self) -> MemoryGrowth {
+        let mut total = MemoryGrowth::default();
+        for growth in self.query_memory.values() {
+            total.add(*growth);
+        }
+        total
+    }
+
     fn total_cache_data(&self) -> (u64, u64) {
         let (mut hits, mut total) = (0, 0);
 
@@ -92,6 +170,9 @@ impl Default for CategoryResultData {
 
 struct CalculatedResults {
     categories: BTreeMap<ProfileCategory, CategoryResultData>,
+    /// The highest resident set size of any `MemorySample` and the stack of queries and
+    /// activities which were running at the time.
+    peak_memory: Option<(usize, Vec<String>)>,
     crate_name: Option<String>,
     optimization_level: OptLevel,
     incremental: bool,
@@ -102,6 +183,7 @@ impl CalculatedResults {
     fn new() -> CalculatedResults {
         CalculatedResults {
             categories: BTreeMap::new(),
+            peak_memory: None,
             crate_name: None,
             optimization_level: OptLevel::No,
             incremental: false,
@@ -122,6 +204,15 @@ impl CalculatedResults {
                 *h += hits;
                 *t += total;
             }
+
+            for (query, growth) in data.query_memory {
+                cr1_data.query_memory.entry(query).or_default().add(growth);
+            }
+        }
+
+        let peak = |p: &Option<(usize, Vec<String>)>| p.as_ref().map(|&(bytes, _)| bytes);
+        if peak(&cr2.peak_memory) > peak(&cr1.peak_memory) {
+            cr1.peak_memory = cr2.peak_memory;
         }
 
         cr1
@@ -160,9 +251,14 @@ fn calculate_percent(numerator: u64, denominator: u64) -> f32 {
 }
 
 impl SelfProfiler {
-    pub fn new() -> SelfProfiler {
+    pub fn new(record_memory: bool) -> SelfProfiler {
         let mut profiler = SelfProfiler {
             events: HashMap::new(),
+            threads: Vec::new(),
+            query_keys: Vec::new(),
+            record_memory,
+            interner_sizes: Vec::new(),
+            start_time: Instant::now(),
         };
 
         profiler.start_activity(ProfileCategory::Other);
@@ -175,11 +271,13 @@ impl SelfProfiler {
         self.record(ProfilerEvent::GenericActivityStart {
             category,
             time: Instant::now(),
-        })
+        });
+        self.record_memory_sample();
     }
 
     #[inline]
     pub fn end_activity(&mut self, category: ProfileCategory) {
+        self.record_memory_sample();
         self.record(ProfilerEvent::GenericActivityEnd {
             category,
             time: Instant::now(),
@@ -209,16 +307,27 @@ impl SelfProfiler {
     }
 
     #[inline]
-    pub fn start_query(&mut self, query_name: &'static str, category: ProfileCategory) {
+    pub fn start_query(&mut self,
+                       query_name: &'static str,
+                       category: ProfileCategory,
+                       key: Option<String>) {
+        let key = key.map(|key| {
+            self.query_keys.push(key);
+            self.query_keys.len() - 1
+        });
+
         self.record(ProfilerEvent::QueryStart {
             query_name,
             category,
             time: Instant::now(),
+            key,
         });
+        self.record_memory_sample();
     }
 
     #[inline]
     pub fn end_query(&mut self, query_name: &'static str, category: ProfileCategory) {
+        self.record_memory_sample();
         self.record(ProfilerEvent::QueryEnd {
             query_name,
             category,
@@ -260,12 +369,36 @@ impl SelfProfiler {
         })
     }
 
+    /// Records the sizes of the interners of the type context, which are part of the memory
+    /// report of `-Z self-profile-memory`.
+    pub fn record_interner_sizes(&mut self, interner_sizes: Vec<(&'static str, usize)>) {
+        self.interner_sizes = interner_sizes;
+    }
+
+    /// Records the bytes allocated by the arenas of the type context, if there is one, and the
+    /// resident set size of the whole process for `-Z self-profile-memory`. The latter also
+    /// counts the memory of other threads and of the allocator, and reading it is a system call.
+    #[inline]
+    fn record_memory_sample(&mut self) {
+        if self.record_memory {
+            let arena_bytes = tls::with_opt(|tcx| tcx.map_or(0, |tcx| tcx.arena_allocated_bytes()));
+            self.record(ProfilerEvent::MemorySample {
+                arena_bytes,
+                resident_bytes: get_resident().unwrap_or(0),
+            })
+        }
+    }
+
     #[inline]
     fn record(&mut self, event: ProfilerEvent) {
         let thread_id = std::thread::current().id();
-        let events = self.events.entry(thread_id).or_default();
-
-        events.push(event);
+        match self.events.entry(thread_id) {
+            Entry::Occupied(mut events) => events.get_mut().push(event),
+            Entry::Vacant(events) => {
+                self.threads.push(thread_id);
+                events.insert(vec![event]);
+            }
+        }
     }
 
     fn calculate_thread_results(events: &Vec<ProfilerEvent>) -> CalculatedResults {
@@ -278,20 +411,26 @@ impl SelfProfiler {
 
         let mut results = CalculatedResults::new();
 
-        //(event, child time to subtract)
-        let mut query_stack = Vec::new();
+        //(event, child time to subtract, memory samples)
+        let mut query_stack: Vec<(&ProfilerEvent, u64, MemoryFrame)> = Vec::new();
+
+        // The memory sample recorded right before the end of the innermost frame
+        let mut end_sample = None;
+        let mut previous_event_was_start = false;
 
         for event in events {
+            let event_is_start = event.is_start_event();
+
             match event {
                 QueryStart { .. } | GenericActivityStart { .. } => {
-                    query_stack.push((event, 0));
+                    query_stack.push((event, 0, MemoryFrame::default()));
                 },
                 QueryEnd { query_name, category, time: end_time } => {
                     let previous_query = query_stack.pop();
                     if let Some((QueryStart {
                                     query_name: p_query_name,
                                     time: start_time,
-                                    category: _ }, child_time_to_subtract)) = previous_query {
+                                    .. }, child_time_to_subtract, memory)) = previous_query {
                         assert_eq!(
                             p_query_name,
                             query_name,
@@ -304,9 +443,15 @@ impl SelfProfiler {
 
                         *result_data.query_times.entry(query_name).or_default() += self_time_ns;
 
-                        if let Some((_, child_time_to_subtract)) = query_stack.last_mut() {
+                        if let Some((_, child_time_to_subtract, _)) = query_stack.last_mut() {
                             *child_time_to_subtract += time_ns;
                         }
+
+                        SelfProfiler::attribute_memory(&mut query_stack,
+                                                       result_data,
+                                                       query_name,
+                                                       memory,
+                                                       end_sample.take());
                     } else {
                         bug!("Saw a query end but the previous event wasn't a query start");
                     }
@@ -315,7 +460,8 @@ impl SelfProfiler {
                     let previous_event = query_stack.pop();
                     if let Some((GenericActivityStart {
                                     category: previous_category,
-                                    time: start_time }, child_time_to_subtract)) = previous_event {
+                                    time: start_time }, child_time_to_subtract, memory))
+                                    = previous_event {
                         assert_eq!(
                             previous_category,
                             category,
@@ -330,13 +476,39 @@ impl SelfProfiler {
                             .entry("{time spent not running queries}")
                             .or_default() += self_time_ns;
 
-                        if let Some((_, child_time_to_subtract)) = query_stack.last_mut() {
+                        if let Some((_, child_time_to_subtract, _)) = query_stack.last_mut() {
                             *child_time_to_subtract += time_ns;
                         }
+
+                        SelfProfiler::attribute_memory(&mut query_stack,
+                                                       result_data,
+                                                       "{time spent not running queries}",
+                                                       memory,
+                                                       end_sample.take());
                     } else {
                         bug!("Saw an activity end but the previous event wasn't an activity start");
                     }
                 },
+                MemorySample { arena_bytes, resident_bytes } => {
+                    let sample = (*arena_bytes, *resident_bytes);
+                    if previous_event_was_start {
+                        if let Some((_, _, memory)) = query_stack.last_mut() {
+                            memory.start = Some(sample);
+                        }
+                    } else {
+                        end_sample = Some(sample);
+                    }
+
+                    let peak = results.peak_memory.as_ref().map_or(0, |&(bytes, _)| bytes);
+                    if *resident_bytes > peak {
+                        let stack = query_stack.iter().map(|&(event, _, _)| match *event {
+                            QueryStart { query_name, .. } => query_name.to_string(),
+                            GenericActivityStart { category, .. } => format!("{:?}", category),
+                            _ => bug!("unexpected event on the query stack"),
+                        }).collect();
+                        results.peak_memory = Some((*resident_bytes, stack));
+                    }
+                },
                 QueryCacheHit { category, query_name } => {
                     let result_data = results.categories.entry(*category).or_default();
 
@@ -357,6 +529,8 @@ impl SelfProfiler {
                 //we don't summarize parallel query blocking in the simple output mode
                 QueryBlockedStart { .. } | QueryBlockedEnd { .. } => { },
             }
+
+            previous_event_was_start = event_is_start;
         }
 
         //normalize the times to ms
@@ -369,6 +543,25 @@ impl SelfProfiler {
         results
     }
 
+    /// Attributes the memory growth of a frame which just ended to `name`, except for the
+    /// growth during its children, and adds it to the children of the enclosing frame.
+    fn attribute_memory(query_stack: &mut [(&ProfilerEvent, u64, MemoryFrame)],
+                        result_data: &mut CategoryResultData,
+                        name: &'static str,
+                        memory: MemoryFrame,
+                        end_sample: Option<(usize, usize)>) {
+        if let (Some(start), Some(end)) = (memory.start, end_sample) {
+            let growth = MemoryGrowth::between(start, end);
+            let mut self_growth = growth;
+            self_growth.sub(memory.children);
+            result_data.query_memory.entry(name).or_default().add(self_growth);
+
+            if let Some((_, _, parent)) = query_stack.last_mut() {
+                parent.children.add(growth);
+            }
+        }
+    }
+
     fn get_results(&self, opts: &Options) -> CalculatedResults {
         self.events
             .iter()
@@ -387,7 +580,10 @@ impl SelfProfiler {
         let out = io::stderr();
         let mut lock = out.lock();
 
-        let crate_name = results.crate_name.map(|n| format!(" for {}", n)).unwrap_or_default();
+        let crate_name = results.crate_name
+            .as_ref()
+            .map(|n| format!(" for {}", n))
+            .unwrap_or_default();
 
         writeln!(lock, "Self profiling results{}:", crate_name).unwrap();
         writeln!(lock).unwrap();
@@ -439,11 +635,76 @@ impl SelfProfiler {
             }
         }
 
+        if self.record_memory {
+            self.print_memory_results(&mut lock, &results);
+        }
+
         writeln!(lock).unwrap();
         writeln!(lock, "Optimization level: {:?}", opts.optimize).unwrap();
         writeln!(lock, "Incremental: {}", if results.incremental { "on" } else { "off" }).unwrap();
     }
 
+    /// Prints the memory growth per category and the queries which contributed most to the peak
+    /// memory usage for `-Z self-profile-memory`. The growth of a query excludes the growth
+    /// during the queries it called.
+    fn print_memory_results(&self, lock: &mut dyn Write, results: &CalculatedResults) {
+        const KB: i64 = 1024;
+
+        writeln!(lock).unwrap();
+        writeln!(lock, "Memory growth:").unwrap();
+        writeln!(lock).unwrap();
+        writeln!(lock, "| Phase                                     | Arenas (KB)    \
+                        | RSS (KB)").unwrap();
+        writeln!(lock, "| ----------------------------------------- | -------------- \
+                        | --------------").unwrap();
+
+        let mut categories: Vec<_> = results.categories.iter().collect();
+        categories.sort_by_cached_key(|(_, d)| d.total_memory().resident_bytes);
+
+        for (category, data) in categories.iter().rev() {
+            let total = data.total_memory();
+            writeln!(lock,
+                     "| {0: <41} | {1: >14} | {2: >14}",
+                     format!("{:?}", category),
+                     total.arena_bytes / KB,
+                     total.resident_bytes / KB).unwrap();
+        }
+
+        let mut queries: Vec<_> = results.categories.values().flat_map(|data| {
+            data.query_memory.iter()
+        }).collect();
+        queries.sort_by_key(|&(_, growth)| growth.resident_bytes.max(growth.arena_bytes));
+
+        writeln!(lock).unwrap();
+        writeln!(lock, "Largest contributors:").unwrap();
+        writeln!(lock).unwrap();
+        for (query, growth) in queries.iter().rev().take(if results.verbose { 50 } else { 10 }) {
+            writeln!(lock,
+                     "| {0: <41} | {1: >14} | {2: >14}",
+                     query,
+                     growth.arena_bytes / KB,
+                     growth.resident_bytes / KB).unwrap();
+        }
+
+        if let Some((bytes, ref stack)) = results.peak_memory {
+            writeln!(lock).unwrap();
+            writeln!(lock, "Peak RSS: {} MB, while running {}",
+                     bytes / (1024 * 1024),
+                     stack.join(" > ")).unwrap();
+        }
+
+        if !self.interner_sizes.is_empty() {
+            writeln!(lock).unwrap();
+            writeln!(lock, "Interned values:").unwrap();
+            writeln!(lock).unwrap();
+            let mut interner_sizes = self.interner_sizes.clone();
+            interner_sizes.sort_by_key(|&(_, count)| count);
+            for (name, count) in interner_sizes.iter().rev() {
+                writeln!(lock, "| {0: <41} | {1: >14}", name, count).unwrap();
+            }
+        }
+    }
+
     pub fn save_results(&self, opts: &Options) {
         let results = self.get_results(opts);
 
@@ -458,12 +719,17 @@ impl SelfProfiler {
             let (hits, total) = data.total_cache_data();
             let hit_percent = calculate_percent(hits, total);
 
+            let memory = data.total_memory();
+
             category_data.push_str(&format!("{{ \"category\": \"{:?}\", \"time_ms\": {}, \
-                                                \"query_count\": {}, \"query_hits\": {} }}",
+                                                \"query_count\": {}, \"query_hits\": {}, \
+                                                \"arena_bytes\": {}, \"resident_bytes\": {} }}",
                                             category,
                                             data.total_time(),
                                             total,
-                                            format!("{:.2}", hit_percent)));
+                                            format!("{:.2}", hit_percent),
+                                            memory.arena_bytes,
+                                            memory.resident_bytes));
         }
 
         let json = format!("{{ \"category_data\": {}, \"compilation_options\": {} }}",
@@ -472,4 +738,117 @@ impl SelfProfiler {
 
         fs::write("self_profiler_results.json", json).unwrap();
     }
+
+    /// Calls `f` for the beginning and the end of every frame of the timeline of one thread,
+    /// with the frame's name and category. Frames which are still running are ended at the time
+    /// of the last event.
+    fn walk_timeline<F>(&self, events: &[ProfilerEvent], mut f: F)
+        where F: FnMut(Phase, &str, &str, Instant)
+    {
+        use self::ProfilerEvent::*;
+
+        let mut stack: Vec<(String, String)> = Vec::new();
+        let mut last_time = self.start_time;
+
+        for event in events {
+            let (name, category, time) = match *event {
+                QueryStart { query_name, category, time, key } => {
+                    let name = match key {
+                        Some(key) => format!("{}({})", query_name, self.query_keys[key]),
+                        None => query_name.to_string(),
+                    };
+                    (name, format!("{:?}", category), time)
+                }
+                GenericActivityStart { category, time } => {
+                    (format!("{:?}", category), "Activity".to_string(), time)
+                }
+                IncrementalLoadResultStart { query_name, time } => {
+                    (format!("{} (load from disk)", query_name), "Incremental".to_string(), time)
+                }
+                QueryBlockedStart { query_name, category, time } => {
+                    (format!("{} (blocked)", query_name), format!("{:?}", category), time)
+                }
+                QueryEnd { time, .. } |
+                GenericActivityEnd { time, .. } |
+                IncrementalLoadResultEnd { time, .. } |
+                QueryBlockedEnd { time, .. } => {
+                    if let Some((name, category)) = stack.pop() {
+                        f(Phase::End, &name, &category, time);
+                    }
+                    last_time = time;
+                    continue;
+                }
+                QueryCacheHit { .. } | QueryCount { .. } | MemorySample { .. } => continue,
+            };
+
+            f(Phase::Begin, &name, &category, time);
+            stack.push((name, category));
+            last_time = time;
+        }
+
+        while let Some((name, category)) = stack.pop() {
+            f(Phase::End, &name, &category, last_time);
+        }
+    }
+
+    /// Writes the events of every thread in the trace event format of Chrome's `about:tracing`,
+    /// which can also be loaded into tools like Perfetto or speedscope.
+    pub fn save_chrome_trace(&self, path: &Path) -> io::Result<()> {
+        let mut json = String::from("[");
+
+        for (tid, thread) in self.threads.iter().enumerate() {
+            self.walk_timeline(&self.events[thread], |phase, name, category, time| {
+                if json.len() > 1 {
+                    json.push_str(",\n");
+                }
+                write!(json,
+                       "{{ \"name\": {}, \"cat\": {}, \"ph\": \"{}\", \"ts\": {}, \
+                          \"pid\": 0, \"tid\": {} }}",
+                       as_json(&name),
+                       as_json(&category),
+                       if phase == Phase::Begin { "B" } else { "E" },
+                       time_between_ns(self.start_time, time) as f64 / 1000.0,
+                       tid).unwrap();
+            });
+        }
+
+        json.push_str("]\n");
+        fs::write(path, json)
+    }
+
+    /// Writes the self time of every stack of frames in the "folded stacks" format read by
+    /// `flamegraph.pl` and similar tools: one line per stack with the frames separated by `;`,
+    /// followed by the time in microseconds.
+    pub fn save_folded_stacks(&self, path: &Path) -> io::Result<()> {
+        let mut stacks: BTreeMap<String, u64> = BTreeMap::new();
+
+        for thread in &self.threads {
+            let mut stack: Vec<String> = Vec::new();
+            let mut last_time = self.start_time;
+
+            self.walk_timeline(&self.events[thread], |phase, name, _, time| {
+                if !stack.is_empty() {
+                    *stacks.entry(stack.join(";")).or_default() +=
+                        time_between_ns(last_time, time);
+                }
+                last_time = time;
+
+                match phase {
+                    // `;` separates the frames and query keys can contain it, e.g. in array types
+                    Phase::Begin => stack.push(name.replace(';', ",").replace('\n', " ")),
+                    Phase::End => { stack.pop(); }
+                }
+            });
+        }
+
+        let mut folded = String::new();
+        for (stack, time_ns) in stacks {
+            let time_us = time_ns / 1000;
+            if time_us > 0 {
+                writeln!(folded, "{} {}", stack, time_us).unwrap();
+            }
+        }
+
+        fs::write(path, folded)
+    }
 }
@@ -693,6 +693,18 @@ impl IntrinsicCallMethods<'tcx> for Builder<'a, 'll, 'tcx> {
                 return;
             }
 
+            // The compile-time heap only exists during const evaluation; at runtime,
+            // `const_allocate` always fails and the other two do nothing.
+            "const_allocate" => {
+                self.const_null(llret_ty)
+            }
+            "const_deallocate" => {
+                return;
+            }
+            "const_make_global" => {
+                args[0].immediate()
+            }
+
             _ => bug!("unknown intrinsic '{}'", name),
         };
 
@@ -65,8 +65,10 @@ use rustc::middle::allocator::AllocatorKind;
 use rustc::middle::cstore::{EncodedMetadata, MetadataLoader};
 use rustc::session::{Session, CompileIncomplete};
 use rustc::session::config::{OutputFilenames, OutputType, PrintRequest, OptLevel};
+use rustc::session::config::TimePassesFormat;
 use rustc::ty::{self, TyCtxt};
 use rustc::util::time_graph;
+use rustc::util::common::print_llvm_pass_timings_json;
 use rustc::util::profiling::ProfileCategory;
 use rustc_mir::monomorphize;
 use rustc_codegen_ssa::ModuleCodegen;
@@ -159,8 +161,16 @@ impl WriteBackendMethods for LlvmCodegenBackend {
     type TargetMachine = &'static mut llvm::TargetMachine;
     type ThinData = back::lto::ThinData;
     type ThinBuffer = back::lto::ThinBuffer;
-    fn print_pass_timings(&self) {
-            unsafe { llvm::LLVMRustPrintPassTimings(); }
+    fn print_pass_timings(&self, format: TimePassesFormat) {
+        match format {
+            TimePassesFormat::Text => unsafe { llvm::LLVMRustPrintPassTimings(); }
+            TimePassesFormat::Json => {
+                let report = llvm::build_string(|s| unsafe {
+                    llvm::LLVMRustWritePassTimingsJSONToString(s)
+                }).expect("non-UTF8 pass timings from LLVM");
+                print_llvm_pass_timings_json(&report);
+            }
+        }
     }
     fn run_fat_lto(
         cgcx: &CodegenContext<Self>,
@@ -1360,6 +1360,10 @@ extern "C" {
     /// Print the pass timings since static dtors aren't picking them up.
     pub fn LLVMRustPrintPassTimings();
 
+    /// Write the pass timings as a JSON object instead, see
+    /// `print_llvm_pass_timings_json`.
+    pub fn LLVMRustWritePassTimingsJSONToString(s: &RustString);
+
     pub fn LLVMStructCreateNamed(C: &Context, Name: *const c_char) -> &Type;
 
     pub fn LLVMStructSetBody(StructTy: &'a Type,
@@ -8,7 +8,8 @@ use super::symbol_export::ExportedSymbols;
 
 use crate::traits::*;
 use rustc_incremental::{copy_cgu_workproducts_to_incr_comp_cache_dir,
-                        in_incr_comp_dir, in_incr_comp_dir_sess};
+                        in_incr_comp_dir, in_incr_comp_dir_sess,
+                        save_cgus_to_artifact_store, CguDependencies};
 use rustc::dep_graph::{WorkProduct, WorkProductId, WorkProductFileKind};
 use rustc::dep_graph::cgu_reuse_tracker::CguReuseTracker;
 use rustc::middle::cstore::EncodedMetadata;
@@ -18,7 +19,8 @@ use rustc::util::nodemap::FxHashMap;
 use rustc::util::time_graph::{self, TimeGraph, Timeline};
 use rustc::hir::def_id::{CrateNum, LOCAL_CRATE};
 use rustc::ty::TyCtxt;
-use rustc::util::common::{time_depth, set_time_depth, print_time_passes_entry};
+use rustc::util::common::{time_depth, set_time_depth, time_parent, set_time_parent};
+use rustc::util::common::print_time_passes_entry;
 use rustc_fs_util::link_or_copy;
 use rustc_data_structures::svh::Svh;
 use rustc_errors::{Handler, Level, DiagnosticBuilder, FatalError, DiagnosticId};
@@ -466,6 +468,7 @@ pub fn start_async_codegen<B: ExtraBackendMethods>(
         shared_emitter_main,
         future: coordinator_thread,
         output_filenames: tcx.output_filenames(LOCAL_CRATE),
+        cgu_dependencies: FxHashMap::default(),
     }
 }
 
@@ -1192,6 +1195,7 @@ fn start_executing_work<B: ExtraBackendMethods>(
     return thread::spawn(move || {
         // We pretend to be within the top-level LLVM time-passes task here:
         set_time_depth(1);
+        set_time_parent(Some("LLVM passes".to_string()));
 
         let max_workers = ::num_cpus::get();
         let mut worker_id_counter = 0;
@@ -1503,6 +1507,7 @@ fn start_executing_work<B: ExtraBackendMethods>(
             // This is the top-level timing for all of LLVM, set the time-depth
             // to zero.
             set_time_depth(0);
+            set_time_parent(None);
             print_time_passes_entry(cgcx.time_passes,
                                     "LLVM passes",
                                     total_llvm_time);
@@ -1558,9 +1563,11 @@ fn spawn_work<B: ExtraBackendMethods>(
     work: WorkItem<B>
 ) {
     let depth = time_depth();
+    let parent = time_parent();
 
     thread::spawn(move || {
         set_time_depth(depth);
+        set_time_parent(parent);
 
         // Set up a destructor which will fire off a message that we're done as
         // we exit.
@@ -1767,6 +1774,7 @@ pub struct OngoingCodegen<B: ExtraBackendMethods> {
     pub shared_emitter_main: SharedEmitterMain,
     pub future: thread::JoinHandle<Result<CompiledModules, ()>>,
     pub output_filenames: Arc<OutputFilenames>,
+    pub cgu_dependencies: FxHashMap<WorkProductId, CguDependencies>,
 }
 
 impl<B: ExtraBackendMethods> OngoingCodegen<B> {
@@ -1797,6 +1805,7 @@ impl<B: ExtraBackendMethods> OngoingCodegen<B> {
         let work_products =
             copy_all_cgu_workproducts_to_incr_comp_cache_dir(sess,
                                                              &compiled_modules);
+        save_cgus_to_artifact_store(sess, &work_products, &self.cgu_dependencies);
         produce_final_output_artifacts(sess,
                                        &compiled_modules,
                                        &self.output_filenames);
@@ -1804,7 +1813,7 @@ impl<B: ExtraBackendMethods> OngoingCodegen<B> {
         // FIXME: time_llvm_passes support - does this use a global context or
         // something?
         if sess.codegen_units() == 1 && sess.time_llvm_passes() {
-            self.backend.print_pass_timings()
+            self.backend.print_pass_timings(sess.opts.debugging_opts.time_passes_format)
         }
 
         (CodegenResults {
@@ -613,7 +613,7 @@ pub fn codegen_crate<B: ExtraBackendMethods>(
         metadata,
         rx,
         codegen_units.len());
-    let ongoing_codegen = AbortCodegenOnDrop::<B>(Some(ongoing_codegen));
+    let mut ongoing_codegen = AbortCodegenOnDrop::<B>(Some(ongoing_codegen));
 
     // Codegen an allocator shim, if necessary.
     //
@@ -671,7 +671,23 @@ pub fn codegen_crate<B: ExtraBackendMethods>(
         ongoing_codegen.wait_for_signal_to_codegen_item();
         ongoing_codegen.check_for_errors(tcx.sess);
 
-        let cgu_reuse = determine_cgu_reuse(tcx, &cgu);
+        let (cgu_reuse, source) = match determine_cgu_reuse(tcx, &cgu) {
+            // Codegen units that are not in the incremental compilation
+            // directory may still be in the artifact store.
+            CguReuse::No => {
+                let dep_node = cgu.codegen_dep_node(tcx);
+                match ::rustc_incremental::fetch_cgu_from_artifact_store(tcx,
+                                                                         &cgu.name().as_str(),
+                                                                         &dep_node) {
+                    Some(work_product) => (cached_cgu_reuse(tcx), Some(work_product)),
+                    None => (CguReuse::No, None),
+                }
+            }
+            cgu_reuse => {
+                record_cgu_dependencies(tcx, &mut ongoing_codegen, &cgu);
+                (cgu_reuse, Some(cgu.work_product(tcx)))
+            }
+        };
         tcx.sess.cgu_reuse_tracker.set_actual_reuse(&cgu.name().as_str(), cgu_reuse);
 
         match cgu_reuse {
@@ -685,19 +701,20 @@ pub fn codegen_crate<B: ExtraBackendMethods>(
                 let stats = backend.compile_codegen_unit(tcx, *cgu.name());
                 all_stats.extend(stats);
                 total_codegen_time += start_time.elapsed();
+                record_cgu_dependencies(tcx, &mut ongoing_codegen, &cgu);
                 false
             }
             CguReuse::PreLto => {
                 submit_pre_lto_module_to_llvm(&backend, tcx, CachedModuleCodegen {
                     name: cgu.name().to_string(),
-                    source: cgu.work_product(tcx),
+                    source: source.unwrap(),
                 });
                 true
             }
             CguReuse::PostLto => {
                 submit_post_lto_module_to_llvm(&backend, tcx, CachedModuleCodegen {
                     name: cgu.name().to_string(),
-                    source: cgu.work_product(tcx),
+                    source: source.unwrap(),
                 });
                 true
             }
@@ -986,13 +1003,30 @@ fn determine_cgu_reuse<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>,
         cgu.name());
 
     if tcx.dep_graph.try_mark_green(tcx, &dep_node).is_some() {
-        // We can re-use either the pre- or the post-thinlto state
-        if tcx.sess.lto() != Lto::No {
-            CguReuse::PreLto
-        } else {
-            CguReuse::PostLto
-        }
+        cached_cgu_reuse(tcx)
     } else {
         CguReuse::No
     }
 }
+
+/// Remembers what `cgu` was built from, so that it can be added to the
+/// artifact store once its object files exist.
+fn record_cgu_dependencies<'a, 'tcx, B: ExtraBackendMethods>(
+    tcx: TyCtxt<'a, 'tcx, 'tcx>,
+    ongoing_codegen: &mut OngoingCodegen<B>,
+    cgu: &CodegenUnit<'tcx>,
+) {
+    let dep_node = cgu.codegen_dep_node(tcx);
+    if let Some(dependencies) = ::rustc_incremental::record_cgu_dependencies(tcx, &dep_node) {
+        ongoing_codegen.cgu_dependencies.insert(cgu.work_product_id(), dependencies);
+    }
+}
+
+fn cached_cgu_reuse(tcx: TyCtxt<'_, '_, '_>) -> CguReuse {
+    // We can re-use either the pre- or the post-thinlto state
+    if tcx.sess.lto() != Lto::No {
+        CguReuse::PreLto
+    } else {
+        CguReuse::PostLto
+    }
+}
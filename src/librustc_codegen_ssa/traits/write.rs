@@ -3,6 +3,7 @@ use crate::back::write::{CodegenContext, ModuleConfig, FatLTOInput};
 use crate::{CompiledModule, ModuleCodegen};
 
 use rustc::dep_graph::WorkProduct;
+use rustc::session::config::TimePassesFormat;
 use rustc::util::time_graph::Timeline;
 use rustc_errors::{FatalError, Handler};
 
@@ -31,7 +32,7 @@ pub trait WriteBackendMethods: 'static + Sized + Clone {
         cached_modules: Vec<(SerializedModule<Self::ModuleBuffer>, WorkProduct)>,
         timeline: &mut Timeline,
     ) -> Result<(Vec<LtoModuleCodegen<Self>>, Vec<WorkProduct>), FatalError>;
-    fn print_pass_timings(&self);
+    fn print_pass_timings(&self, format: TimePassesFormat);
     unsafe fn optimize(
         cgcx: &CodegenContext<Self>,
         diag_handler: &Handler,
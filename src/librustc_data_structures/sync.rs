@@ -271,6 +271,15 @@ cfg_if! {
             }
         }
 
+        // Keeps the API the same as without `parallel_compiler`, so that code
+        // which clones an `MTLock` doesn't only build in one configuration.
+        impl<T: Clone> Clone for MTLock<T> {
+            #[inline]
+            fn clone(&self) -> Self {
+                MTLock(self.0.clone())
+            }
+        }
+
         use parking_lot::Mutex as InnerLock;
         use parking_lot::RwLock as InnerRwLock;
 
@@ -315,11 +315,20 @@ pub fn compile_input(
 
                 let ongoing_codegen = phase_4_codegen(&*codegen_backend, tcx, rx);
 
+                // Codegen was the last user of the MIR, so no more passes are dumped.
+                if tcx.sess.opts.debugging_opts.dump_mir_html {
+                    let _ = mir::util::finish_mir_html_dumps(tcx.sess);
+                }
+
                 if log_enabled!(::log::Level::Info) {
                     println!("Post-codegen");
                     tcx.print_debug_stats();
                 }
 
+                if tcx.sess.opts.debugging_opts.self_profile_memory {
+                    tcx.sess.profiler(|p| p.record_interner_sizes(tcx.interner_sizes()));
+                }
+
                 if tcx.sess.opts.output_types.contains_key(&OutputType::Mir) {
                     if let Err(e) = mir::transform::dump_mir::emit_mir(tcx, &outputs) {
                         sess.err(&format!("could not emit MIR: {}", e));
@@ -354,6 +363,11 @@ pub fn compile_input(
         sess.save_json_results();
     }
 
+    if sess.opts.debugging_opts.self_profile_trace.is_some() ||
+       sess.opts.debugging_opts.self_profile_folded.is_some() {
+        sess.save_profiler_timeline();
+    }
+
     controller_entry_point!(
         compilation_done,
         sess,
@@ -818,6 +818,20 @@ impl<'a> CompilerCalls<'a> for RustcDefaultCalls {
                     describe_lints(&sess, &ls, false);
                     return None;
                 }
+                if sopts.debugging_opts.incremental_gc {
+                    if sopts.incremental.is_none() {
+                        early_error(sopts.error_format,
+                                    "`-Z incremental-gc` requires `-C incremental=<dir>`");
+                    }
+                    if let Err(err) = rustc_incremental::garbage_collect_incremental_directory(
+                        &sess
+                    ) {
+                        sess.err(&format!("failed to garbage collect incremental compilation \
+                                           directory: {}", err));
+                    }
+                    sess.abort_if_errors();
+                    return None;
+                }
                 rustc_lint::register_builtins(&mut sess.lint_store.borrow_mut(), Some(&sess));
                 let mut cfg = config::build_configuration(&sess, cfg.clone());
                 let codegen_backend = get_codegen_backend(&sess);
@@ -1,6 +1,6 @@
 //! This pass is only used for the UNIT TESTS and DEBUGGING NEEDS
 //! around dependency graph construction. It serves two purposes; it
-//! will dump graphs in graphviz form to disk, and it searches for
+//! will dump graphs in graphviz or JSON form to disk, and it searches for
 //! `#[rustc_if_this_changed]` and `#[rustc_then_this_would_need]`
 //! annotations. These annotations can be used to test whether paths
 //! exist in the graph. These checks run after codegen, so they view the
@@ -45,9 +45,11 @@ use rustc_data_structures::graph::implementation::{
 use rustc::hir;
 use rustc::hir::intravisit::{self, NestedVisitorMap, Visitor};
 use rustc::ich::{ATTR_IF_THIS_CHANGED, ATTR_THEN_THIS_WOULD_NEED};
+use rustc_serialize::json;
 use std::env;
 use std::fs::{self, File};
-use std::io::Write;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
 use syntax::ast;
 use syntax_pos::Span;
 
@@ -57,6 +59,14 @@ pub fn assert_dep_graph<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>) {
             dump_graph(tcx);
         }
 
+        if let Some(ref path) = tcx.sess.opts.debugging_opts.dump_dep_graph_json {
+            if let Err(err) = dump_graph_json(tcx, path) {
+                tcx.sess.err(&format!("could not write dependency graph to `{}`: {}",
+                                      path.display(),
+                                      err));
+            }
+        }
+
         // if the `rustc_attrs` feature is not enabled, then the
         // attributes we are interested in cannot be present anyway, so
         // skip the walk.
@@ -255,6 +265,45 @@ fn dump_graph(tcx: TyCtxt<'_, '_, '_>) {
     }
 }
 
+/// Writes the complete dependency graph of the current session to `path`,
+/// one node per line:
+///
+/// ```text
+/// {"nodes":[
+/// {"kind":"Hir","label":"Hir(foo[0]::bar[0])","fingerprint":"...","edges":[]},
+/// {"kind":"TypeckTables","label":"TypeckTables(foo[0]::bar[0])","fingerprint":"...","edges":[0]}
+/// ]}
+/// ```
+///
+/// The `edges` of a node are the indices of the nodes it read, i.e., the ones
+/// it depends on. Unlike the graphviz output, this stays usable for real
+/// crates; `src/etc/dep-graph-query.py` answers questions about it.
+fn dump_graph_json(tcx: TyCtxt<'_, '_, '_>, path: &Path) -> io::Result<()> {
+    let graph = tcx.dep_graph.serialize();
+    let mut file = BufWriter::new(File::create(path)?);
+
+    write!(file, "{{\"nodes\":[")?;
+    for (index, node) in graph.nodes.iter_enumerated() {
+        if index.index() > 0 {
+            write!(file, ",")?;
+        }
+        write!(file,
+               "\n{{\"kind\":{},\"label\":{},\"fingerprint\":\"{}\",\"edges\":[",
+               json::as_json(&format!("{:?}", node.kind)),
+               json::as_json(&format!("{:?}", node)),
+               graph.fingerprints[index].to_hex())?;
+        for (i, target) in graph.edge_targets_from(index).iter().enumerate() {
+            if i > 0 {
+                write!(file, ",")?;
+            }
+            write!(file, "{}", target.index())?;
+        }
+        write!(file, "]}}")?;
+    }
+    write!(file, "\n]}}\n")?;
+    file.flush()
+}
+
 pub struct GraphvizDepGraph<'q>(FxHashSet<&'q DepNode>,
                                 Vec<(&'q DepNode, &'q DepNode)>);
 
@@ -33,3 +33,8 @@ pub use persist::prepare_session_directory;
 pub use persist::finalize_session_directory;
 pub use persist::delete_workproduct_files;
 pub use persist::garbage_collect_session_directories;
+pub use persist::garbage_collect_incremental_directory;
+pub use persist::CguDependencies;
+pub use persist::fetch_cgu_from_artifact_store;
+pub use persist::record_cgu_dependencies;
+pub use persist::save_cgus_to_artifact_store;
@@ -0,0 +1,313 @@
+//! This module implements the incremental artifact store, which shares the
+//! files of codegen units between compilation sessions that do not use the
+//! same incremental compilation directory, e.g. between several checkouts of
+//! the same project, with `-Z incremental-artifact-store=<dir>`. The store
+//! directory may as well be a local stand-in for a network cache.
+//!
+//! Entries are content-addressed: the key of an entry is a fingerprint of the
+//! `CompileCodegenUnit` dep-node of the codegen unit, of the fingerprints of
+//! all dependencies of that node, and of the commandline arguments and the
+//! compiler version. There is one directory per codegen unit, named after the
+//! hash of its dep-node, which contains one directory per entry:
+//!
+//! ```text
+//! <store>/<dep-node hash>/<key>/entry.bin
+//! <store>/<dep-node hash>/<key>/<codegen unit name>.o
+//! ```
+//!
+//! `entry.bin` lists the dependencies of the codegen unit together with the
+//! fingerprints they had when it was compiled. If a codegen unit cannot be
+//! re-used from the incremental compilation directory, the entries of its
+//! directory are checked with `DepGraph::try_mark_green_from_dependencies`,
+//! which succeeds if all dependencies have the same fingerprints in the
+//! current session. The files of the first entry that matches are then
+//! copied into the session directory, as if they had been there all along.
+//!
+//! Entries are built in a temporary directory and renamed into place once
+//! they are complete, so that concurrent sessions never see partial entries.
+//! Entries are never modified afterwards.
+
+use rustc::dep_graph::{DepNode, WorkProduct, WorkProductId};
+use rustc::ich::Fingerprint;
+use rustc::session::Session;
+use rustc::ty::TyCtxt;
+use rustc_data_structures::base_n;
+use rustc_data_structures::fx::FxHashMap;
+use rustc_data_structures::stable_hasher::StableHasher;
+use rustc_fs_util::link_or_copy;
+use rustc_serialize::Decodable as RustcDecodable;
+use rustc_serialize::Encodable as RustcEncodable;
+use rustc_serialize::opaque::{Decoder, Encoder};
+use std::fs as std_fs;
+use std::hash::Hash;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use rand::{RngCore, thread_rng};
+
+use super::data::*;
+use super::file_format;
+use super::fs::*;
+
+const ENTRY_FILENAME: &str = "entry.bin";
+const TEMP_DIRECTORY_PREFIX: &str = "tmp-";
+
+/// What a codegen unit of the current session was built from. It is recorded
+/// during codegen, while the dep-graph is available, and turned into an entry
+/// of the artifact store once the object files of the codegen unit exist.
+#[derive(Debug)]
+pub struct CguDependencies {
+    dep_node: DepNode,
+    fingerprint: Fingerprint,
+    dependencies: Vec<(DepNode, Fingerprint)>,
+}
+
+/// Records the dependencies of the codegen unit with the given
+/// `CompileCodegenUnit` node. Returns `None` if there is no artifact store or
+/// if the codegen unit could not be recognized by other sessions.
+pub fn record_cgu_dependencies(tcx: TyCtxt<'_, '_, '_>,
+                               dep_node: &DepNode)
+                               -> Option<CguDependencies> {
+    tcx.sess.opts.debugging_opts.incremental_artifact_store.as_ref()?;
+
+    let (fingerprint, dependencies) = tcx.dep_graph.dependency_fingerprints(dep_node)?;
+    Some(CguDependencies {
+        dep_node: *dep_node,
+        fingerprint,
+        dependencies,
+    })
+}
+
+/// Looks for an entry of the artifact store which matches the codegen unit
+/// `cgu_name` in the current session. If there is one, its files are copied
+/// into the session directory, the `CompileCodegenUnit` node is added to the
+/// dep-graph, and the work product of the codegen unit is returned.
+pub fn fetch_cgu_from_artifact_store(tcx: TyCtxt<'_, '_, '_>,
+                                     cgu_name: &str,
+                                     dep_node: &DepNode)
+                                     -> Option<WorkProduct> {
+    let store = tcx.sess.opts.debugging_opts.incremental_artifact_store.as_ref()?;
+    let report_incremental_info = tcx.sess.opts.debugging_opts.incremental_info;
+    debug!("fetch_cgu_from_artifact_store({:?})", cgu_name);
+
+    for entry_directory in entry_directories(&store.join(dep_node.hash.to_hex())) {
+        let entry = match load_entry(report_incremental_info, &entry_directory) {
+            Some(entry) => entry,
+            None => continue,
+        };
+
+        // Entries of other compiler versions or with other commandline
+        // arguments are stored under a different key.
+        let key = entry_key(tcx.sess, dep_node, entry.fingerprint, &entry.dependencies);
+        if entry_directory.file_name().and_then(|name| name.to_str()) != Some(&*key.to_hex()) {
+            continue
+        }
+
+        // The files are copied first, since the codegen unit cannot be
+        // compiled anymore once its node has been added to the dep-graph. If
+        // the node cannot be marked green after all, the copies are removed
+        // again, so that they don't end up in the session directory without
+        // a work product referring to them.
+        if !copy_entry_files(tcx.sess, &entry_directory, &entry.work_product) {
+            remove_entry_files(tcx.sess, &entry.work_product);
+            continue
+        }
+
+        if tcx.dep_graph.try_mark_green_from_dependencies(tcx,
+                                                          dep_node,
+                                                          entry.fingerprint,
+                                                          &entry.dependencies).is_none() {
+            remove_entry_files(tcx.sess, &entry.work_product);
+            continue
+        }
+
+        if report_incremental_info {
+            println!("[incremental] re-using codegen unit `{}` from the artifact store `{}`",
+                     cgu_name,
+                     entry_directory.display());
+        }
+
+        return Some(WorkProduct {
+            cgu_name: cgu_name.to_string(),
+            saved_files: entry.work_product.saved_files,
+        })
+    }
+
+    None
+}
+
+/// Adds the work products of the codegen units whose dependencies were
+/// recorded with `record_cgu_dependencies` to the artifact store. Codegen
+/// units which are already in the store are skipped.
+pub fn save_cgus_to_artifact_store(sess: &Session,
+                                   work_products: &FxHashMap<WorkProductId, WorkProduct>,
+                                   cgu_dependencies: &FxHashMap<WorkProductId, CguDependencies>) {
+    let store = match sess.opts.debugging_opts.incremental_artifact_store {
+        Some(ref store) => store,
+        None => return,
+    };
+    debug!("save_cgus_to_artifact_store()");
+
+    for (id, work_product) in work_products {
+        let cgu_dependencies = match cgu_dependencies.get(id) {
+            Some(cgu_dependencies) => cgu_dependencies,
+            None => continue,
+        };
+
+        if let Err(err) = save_entry(sess, store, work_product, cgu_dependencies) {
+            sess.warn(&format!("failed to add codegen unit `{}` to the incremental \
+                                artifact store `{}`: {}",
+                               work_product.cgu_name,
+                               store.display(),
+                               err));
+        }
+    }
+}
+
+fn save_entry(sess: &Session,
+              store: &Path,
+              work_product: &WorkProduct,
+              cgu_dependencies: &CguDependencies)
+              -> io::Result<()> {
+    let cgu_directory = store.join(cgu_dependencies.dep_node.hash.to_hex());
+    let key = entry_key(sess,
+                        &cgu_dependencies.dep_node,
+                        cgu_dependencies.fingerprint,
+                        &cgu_dependencies.dependencies).to_hex();
+    let entry_directory = cgu_directory.join(&key);
+    if entry_directory.exists() {
+        return Ok(())
+    }
+
+    std_fs::create_dir_all(&cgu_directory)?;
+    let random_number = thread_rng().next_u64();
+    let temp_directory = cgu_directory.join(format!("{}{}-{}",
+                                                    TEMP_DIRECTORY_PREFIX,
+                                                    key,
+                                                    base_n::encode(random_number as u128,
+                                                                   base_n::MAX_BASE)));
+    std_fs::create_dir(&temp_directory)?;
+
+    let result = (|| {
+        for &(_, ref file_name) in &work_product.saved_files {
+            link_or_copy(in_incr_comp_dir_sess(sess, file_name),
+                         temp_directory.join(file_name))?;
+        }
+
+        let entry = SerializedArtifactStoreEntry {
+            fingerprint: cgu_dependencies.fingerprint,
+            dependencies: cgu_dependencies.dependencies.clone(),
+            work_product: work_product.clone(),
+        };
+        let mut encoder = Encoder::new(Vec::new());
+        file_format::write_file_header(&mut encoder);
+        entry.encode(&mut encoder).unwrap();
+        std_fs::write(temp_directory.join(ENTRY_FILENAME), encoder.into_inner())?;
+
+        std_fs::rename(&temp_directory, &entry_directory)
+    })();
+
+    match result {
+        Ok(()) => {
+            debug!("save_entry() - stored `{}` as `{}`",
+                   work_product.cgu_name,
+                   entry_directory.display());
+            Ok(())
+        }
+        // Another session may have stored the same entry in the meantime
+        Err(_) if entry_directory.exists() => {
+            let _ = std_fs::remove_dir_all(&temp_directory);
+            Ok(())
+        }
+        Err(err) => {
+            let _ = std_fs::remove_dir_all(&temp_directory);
+            Err(err)
+        }
+    }
+}
+
+/// Returns the complete entries in the directory of a codegen unit, the most
+/// recently stored ones first.
+fn entry_directories(cgu_directory: &Path) -> Vec<PathBuf> {
+    let dir_entries = match cgu_directory.read_dir() {
+        Ok(dir_entries) => dir_entries,
+        Err(_) => return vec![],
+    };
+
+    let mut entries: Vec<_> = dir_entries.filter_map(|dir_entry| {
+        let dir_entry = dir_entry.ok()?;
+        if dir_entry.file_name().to_string_lossy().starts_with(TEMP_DIRECTORY_PREFIX) {
+            return None
+        }
+        let modified = dir_entry.metadata().and_then(|metadata| metadata.modified()).ok()?;
+        Some((modified, dir_entry.path()))
+    }).collect();
+
+    entries.sort_by_key(|&(modified, _)| modified);
+    entries.into_iter().rev().map(|(_, path)| path).collect()
+}
+
+fn load_entry(report_incremental_info: bool,
+              entry_directory: &Path)
+              -> Option<SerializedArtifactStoreEntry> {
+    let path = entry_directory.join(ENTRY_FILENAME);
+    match file_format::read_file(report_incremental_info, &path) {
+        Ok(Some((data, start_pos))) => {
+            let mut decoder = Decoder::new(&data[..], start_pos);
+            RustcDecodable::decode(&mut decoder).ok()
+        }
+        Ok(None) => None,
+        Err(err) => {
+            debug!("load_entry() - could not read `{}`: {}", path.display(), err);
+            None
+        }
+    }
+}
+
+fn copy_entry_files(sess: &Session, entry_directory: &Path, work_product: &WorkProduct) -> bool {
+    work_product.saved_files.iter().all(|&(_, ref file_name)| {
+        let source = entry_directory.join(file_name);
+        let destination = in_incr_comp_dir_sess(sess, file_name);
+        match link_or_copy(&source, &destination) {
+            Ok(_) => true,
+            Err(err) => {
+                debug!("copy_entry_files() - could not copy `{}` to `{}`: {}",
+                       source.display(),
+                       destination.display(),
+                       err);
+                false
+            }
+        }
+    })
+}
+
+/// Removes the files copied by `copy_entry_files`, or as many of them as
+/// were copied before it failed.
+fn remove_entry_files(sess: &Session, work_product: &WorkProduct) {
+    for &(_, ref file_name) in &work_product.saved_files {
+        let path = in_incr_comp_dir_sess(sess, file_name);
+        match std_fs::remove_file(&path) {
+            Ok(()) => {}
+            Err(ref err) if err.kind() == io::ErrorKind::NotFound => {}
+            Err(err) => {
+                sess.warn(&format!("file-system error deleting outdated file `{}`: {}",
+                                   path.display(),
+                                   err));
+            }
+        }
+    }
+}
+
+fn entry_key(sess: &Session,
+             dep_node: &DepNode,
+             fingerprint: Fingerprint,
+             dependencies: &[(DepNode, Fingerprint)])
+             -> Fingerprint {
+    let mut hasher = StableHasher::<Fingerprint>::new();
+    file_format::rustc_version().hash(&mut hasher);
+    sess.opts.dep_tracking_hash().hash(&mut hasher);
+    dep_node.hash(&mut hasher);
+    fingerprint.hash(&mut hasher);
+    dependencies.hash(&mut hasher);
+    hasher.finish()
+}
@@ -1,6 +1,7 @@
 //! The data that we will serialize and deserialize.
 
-use rustc::dep_graph::{WorkProduct, WorkProductId};
+use rustc::dep_graph::{DepNode, WorkProduct, WorkProductId};
+use rustc::ich::Fingerprint;
 
 #[derive(Debug, RustcEncodable, RustcDecodable)]
 pub struct SerializedWorkProduct {
@@ -10,3 +11,15 @@ pub struct SerializedWorkProduct {
     /// work-product data itself
     pub work_product: WorkProduct,
 }
+
+#[derive(Debug, RustcEncodable, RustcDecodable)]
+pub struct SerializedArtifactStoreEntry {
+    /// fingerprint of the `CompileCodegenUnit` node of the codegen unit
+    pub fingerprint: Fingerprint,
+
+    /// dependencies of that node, with the fingerprints they had
+    pub dependencies: Vec<(DepNode, Fingerprint)>,
+
+    /// the files of the entry, relative to its directory
+    pub work_product: WorkProduct,
+}
@@ -50,6 +50,18 @@
 //! any more and will delete those. It will also delete any finalized session
 //! directories for a given crate except for the most recent one.
 //!
+//! Optionally, the size of the cache can be limited as well. With
+//! `-Z incremental-crate-cache-size`, a crate's finalized session directories
+//! are deleted while the crate directory is larger than the given number of
+//! MiB, even if this means that the next session starts from scratch. With
+//! `-Z incremental-cache-size`, the limit applies to all crate directories in
+//! the incremental compilation directory together, and the crates that were
+//! compiled least recently lose their session directories first. Crates
+//! compiled into the same directory by other compiler processes are collected
+//! as well, using the same locking protocol as described below. Finally,
+//! `-Z incremental-gc` applies all of the above to every crate in the
+//! directory and exits without compiling anything.
+//!
 //! ## Synchronization
 //!
 //! There is some synchronization needed in order for the compiler to be able to
@@ -373,6 +385,7 @@ pub fn finalize_session_directory(sess: &Session, svh: Svh) {
     }
 
     let _ = garbage_collect_session_directories(sess);
+    let _ = enforce_cache_size_limits(sess, Some(&*sess.incr_comp_session_dir()));
 }
 
 pub fn delete_all_session_dir_contents(sess: &Session) -> io::Result<()> {
@@ -643,6 +656,30 @@ pub fn garbage_collect_session_directories(sess: &Session) -> io::Result<()> {
     debug!("garbage_collect_session_directories() - crate directory: {}",
         crate_directory.display());
 
+    garbage_collect_crate_directory(sess, crate_directory)
+}
+
+/// Implements `-Z incremental-gc`: collects the session directories of every
+/// crate in the incremental compilation directory and enforces the cache size
+/// limits, without starting a compilation session.
+pub fn garbage_collect_incremental_directory(sess: &Session) -> io::Result<()> {
+    let incr_dir = sess.opts.incremental.as_ref().unwrap();
+    debug!("garbage_collect_incremental_directory() - {}", incr_dir.display());
+
+    for dir_entry in incr_dir.read_dir()? {
+        let crate_directory = match dir_entry {
+            Ok(dir_entry) => dir_entry.path(),
+            _ => continue,
+        };
+        if crate_directory.is_dir() {
+            garbage_collect_crate_directory(sess, &crate_directory)?;
+        }
+    }
+
+    enforce_cache_size_limits(sess, None)
+}
+
+fn garbage_collect_crate_directory(sess: &Session, crate_directory: &Path) -> io::Result<()> {
     // First do a pass over the crate directory, collecting lock files and
     // session directories
     let mut session_directories = FxHashSet::default();
@@ -852,6 +889,155 @@ pub fn garbage_collect_session_directories(sess: &Session) -> io::Result<()> {
     Ok(())
 }
 
+/// The disk usage of one crate directory, as seen by `enforce_cache_size_limits`.
+#[derive(Debug)]
+struct CrateCache {
+    /// The newest timestamp of the crate's session directories.
+    last_used: SystemTime,
+    size: u64,
+    /// The finalized session directories, with their timestamps and sizes.
+    sessions: Vec<(SystemTime, PathBuf, u64)>,
+}
+
+fn mib_to_bytes(mib: usize) -> u64 {
+    mib as u64 * 1024 * 1024
+}
+
+/// Deletes finalized session directories until the crate directories respect
+/// `-Z incremental-crate-cache-size` and `-Z incremental-cache-size`.
+fn enforce_cache_size_limits(sess: &Session,
+                             current_session: Option<&PathBuf>)
+                             -> io::Result<()> {
+    let crate_limit = sess.opts.debugging_opts.incremental_crate_cache_size.map(mib_to_bytes);
+    let total_limit = sess.opts.debugging_opts.incremental_cache_size.map(mib_to_bytes);
+    if crate_limit.is_none() && total_limit.is_none() {
+        return Ok(())
+    }
+
+    let incr_dir = sess.opts.incremental.as_ref().unwrap();
+    let mut crates = vec![];
+
+    for dir_entry in incr_dir.read_dir()? {
+        let crate_directory = match dir_entry {
+            Ok(dir_entry) => dir_entry.path(),
+            _ => continue,
+        };
+        let session_entries = match crate_directory.read_dir() {
+            Ok(session_entries) => session_entries,
+            _ => continue,
+        };
+
+        let mut cache = CrateCache {
+            last_used: UNIX_EPOCH,
+            size: 0,
+            sessions: vec![],
+        };
+        for session_entry in session_entries {
+            let session_directory = match session_entry {
+                Ok(session_entry) => session_entry.path(),
+                _ => continue,
+            };
+            let directory_name = session_directory.file_name().unwrap().to_string_lossy();
+            let timestamp = match extract_timestamp_from_session_dir(&directory_name) {
+                Ok(timestamp) => timestamp,
+                Err(()) => continue,
+            };
+            let size = directory_size(&session_directory);
+
+            cache.last_used = cache.last_used.max(timestamp);
+            cache.size += size;
+            if is_finalized(&directory_name) {
+                cache.sessions.push((timestamp, session_directory.clone(), size));
+            }
+        }
+        crates.push(cache);
+    }
+
+    for path in caches_to_evict(crates, crate_limit, total_limit, current_session) {
+        let lock_file_path = lock_file_path(&path);
+        let lock = match flock::Lock::new(&lock_file_path,
+                                          false,  // don't wait
+                                          false,  // don't create the lock-file
+                                          true) { // get an exclusive lock
+            Ok(lock) => lock,
+            Err(_) => {
+                debug!("enforce_cache_size_limits() - not collecting `{}`, still in use",
+                       path.display());
+                continue
+            }
+        };
+
+        if sess.opts.debugging_opts.incremental_info {
+            println!("[incremental] deleting session directory `{}` to respect the \
+                      incremental cache size limit", path.display());
+        }
+
+        if let Err(err) = safe_remove_dir_all(&path) {
+            sess.warn(&format!("Failed to delete incremental compilation session \
+                                directory `{}` exceeding the cache size limit: {}",
+                               path.display(),
+                               err));
+        } else {
+            delete_session_dir_lock_file(sess, &lock_file_path);
+            // Remove the crate directory too if it is empty now
+            let _ = std_fs::remove_dir(path.parent().unwrap());
+        }
+
+        mem::drop(lock);
+    }
+
+    Ok(())
+}
+
+/// Selects the session directories to delete so that no crate is larger than
+/// `crate_limit` and all crates together are not larger than `total_limit`.
+/// Crates are considered from the least recently used one, and within a crate
+/// the oldest session directories go first. The current session is only
+/// deleted if it alone exceeds `crate_limit`, never for `total_limit`.
+fn caches_to_evict(mut crates: Vec<CrateCache>,
+                   crate_limit: Option<u64>,
+                   total_limit: Option<u64>,
+                   current_session: Option<&PathBuf>)
+                   -> Vec<PathBuf> {
+    crates.sort_by_key(|cache| cache.last_used);
+
+    let mut total_size: u64 = crates.iter().map(|cache| cache.size).sum();
+    let mut evicted = vec![];
+
+    for mut cache in crates {
+        cache.sessions.sort_by_key(|&(timestamp, ..)| timestamp);
+
+        for (_, path, size) in cache.sessions {
+            let over_crate_limit = crate_limit.map_or(false, |limit| cache.size > limit);
+            let over_total_limit = total_limit.map_or(false, |limit| total_size > limit);
+            let is_current = current_session == Some(&path);
+
+            if over_crate_limit || (over_total_limit && !is_current) {
+                cache.size -= size;
+                total_size -= size;
+                evicted.push(path);
+            }
+        }
+    }
+
+    evicted
+}
+
+fn directory_size(path: &Path) -> u64 {
+    let entries = match path.read_dir() {
+        Ok(entries) => entries,
+        Err(_) => return 0,
+    };
+
+    entries.filter_map(|entry| entry.ok()).map(|entry| {
+        match entry.metadata() {
+            Ok(ref metadata) if metadata.is_dir() => directory_size(&entry.path()),
+            Ok(metadata) => metadata.len(),
+            Err(_) => 0,
+        }
+    }).sum()
+}
+
 fn all_except_most_recent(deletion_candidates: Vec<(SystemTime, PathBuf, Option<flock::Lock>)>)
                           -> FxHashMap<PathBuf, Option<flock::Lock>> {
     let most_recent = deletion_candidates.iter()
@@ -917,6 +1103,40 @@ fn test_all_except_most_recent() {
     );
 }
 
+#[test]
+fn test_caches_to_evict() {
+    fn cache(last_used: u64, sessions: &[(u64, &str, u64)]) -> CrateCache {
+        CrateCache {
+            last_used: UNIX_EPOCH + Duration::new(last_used, 0),
+            size: sessions.iter().map(|&(.., size)| size).sum(),
+            sessions: sessions.iter().map(|&(timestamp, path, size)| {
+                (UNIX_EPOCH + Duration::new(timestamp, 0), PathBuf::from(path), size)
+            }).collect(),
+        }
+    }
+    let crates = || vec![
+        cache(30, &[(30, "a/s-30", 40)]),
+        cache(10, &[(5, "b/s-5", 20), (10, "b/s-10", 20)]),
+        cache(20, &[(20, "c/s-20", 10)]),
+    ];
+    let paths = |paths: &[&str]| paths.iter().map(PathBuf::from).collect::<Vec<_>>();
+
+    assert_eq!(caches_to_evict(crates(), None, None, None), paths(&[]));
+
+    // The oldest session of the least recently used crate goes first
+    assert_eq!(caches_to_evict(crates(), None, Some(80), None), paths(&["b/s-5"]));
+    assert_eq!(caches_to_evict(crates(), None, Some(45), None),
+               paths(&["b/s-5", "b/s-10", "c/s-20"]));
+
+    // The current session is kept for the total limit...
+    assert_eq!(caches_to_evict(crates(), None, Some(0), Some(&PathBuf::from("a/s-30"))),
+               paths(&["b/s-5", "b/s-10", "c/s-20"]));
+
+    // ... but not for the crate limit
+    assert_eq!(caches_to_evict(crates(), Some(30), None, Some(&PathBuf::from("a/s-30"))),
+               paths(&["b/s-5", "a/s-30"]));
+}
+
 #[test]
 fn test_timestamp_serialization() {
     for i in 0 .. 1_000u64 {
@@ -113,7 +113,9 @@ pub fn load_dep_graph(sess: &Session) ->
     // Calling `sess.incr_comp_session_dir()` will panic if `sess.opts.incremental.is_none()`.
     // Fortunately, we just checked that this isn't the case.
     let path = dep_graph_path_from(&sess.incr_comp_session_dir());
-    let report_incremental_info = sess.opts.debugging_opts.incremental_info;
+    // The reasons for not loading the dep-graph are also the roots of `-Z incremental-explain`.
+    let report_incremental_info = sess.opts.debugging_opts.incremental_info ||
+                                  sess.opts.debugging_opts.incremental_explain;
     let expected_hash = sess.opts.dep_tracking_hash();
 
     let mut prev_work_products = FxHashMap::default();
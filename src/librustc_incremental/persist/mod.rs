@@ -2,6 +2,7 @@
 //! into the given directory. At the same time, it also hashes the
 //! various HIR nodes.
 
+mod artifact_store;
 mod data;
 mod dirty_clean;
 mod fs;
@@ -10,8 +11,13 @@ mod save;
 mod work_product;
 mod file_format;
 
+pub use artifact_store::CguDependencies;
+pub use artifact_store::fetch_cgu_from_artifact_store;
+pub use artifact_store::record_cgu_dependencies;
+pub use artifact_store::save_cgus_to_artifact_store;
 pub use fs::finalize_session_directory;
 pub use fs::garbage_collect_session_directories;
+pub use fs::garbage_collect_incremental_directory;
 pub use fs::in_incr_comp_dir;
 pub use fs::in_incr_comp_dir_sess;
 pub use fs::prepare_session_directory;
@@ -23,6 +23,10 @@ pub fn save_dep_graph<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>) {
             return;
         }
 
+        if sess.opts.debugging_opts.incremental_explain {
+            tcx.dep_graph.print_red_cause_report();
+        }
+
         let query_cache_path = query_cache_path(sess);
         let dep_graph_path = dep_graph_path(sess);
 
@@ -293,6 +293,7 @@ impl<'cx, 'gcx, 'tcx> MirBorrowckCtxt<'cx, 'gcx, 'tcx> {
 
         self.explain_why_borrow_contains_point(context, borrow, None)
             .add_explanation_to_diagnostic(self.infcx.tcx, self.mir, &mut err, "");
+        self.add_region_flow_to_diagnostic(context, borrow, &mut err);
         err.buffer(&mut self.errors_buffer);
     }
 
@@ -330,6 +331,7 @@ impl<'cx, 'gcx, 'tcx> MirBorrowckCtxt<'cx, 'gcx, 'tcx> {
 
         self.explain_why_borrow_contains_point(context, borrow, None)
             .add_explanation_to_diagnostic(self.infcx.tcx, self.mir, &mut err, "");
+        self.add_region_flow_to_diagnostic(context, borrow, &mut err);
         err.buffer(&mut self.errors_buffer);
     }
 
@@ -544,6 +546,7 @@ impl<'cx, 'gcx, 'tcx> MirBorrowckCtxt<'cx, 'gcx, 'tcx> {
 
         explanation
             .add_explanation_to_diagnostic(self.infcx.tcx, self.mir, &mut err, first_borrow_desc);
+        self.add_region_flow_to_diagnostic(context, issued_borrow, &mut err);
 
         err.buffer(&mut self.errors_buffer);
     }
@@ -716,7 +719,7 @@ impl<'cx, 'gcx, 'tcx> MirBorrowckCtxt<'cx, 'gcx, 'tcx> {
         let kind_place = kind.filter(|_| place_desc.is_some()).map(|k| (k, place_span.0));
         let explanation = self.explain_why_borrow_contains_point(context, &borrow, kind_place);
 
-        let err = match (place_desc, explanation) {
+        let mut err = match (place_desc, explanation) {
             (Some(_), _) if self.is_place_thread_local(root_place) => {
                 self.report_thread_local_value_does_not_live_long_enough(drop_span, borrow_span)
             }
@@ -789,6 +792,7 @@ impl<'cx, 'gcx, 'tcx> MirBorrowckCtxt<'cx, 'gcx, 'tcx> {
             ),
         };
 
+        self.add_region_flow_to_diagnostic(context, borrow, &mut err);
         err.buffer(&mut self.errors_buffer);
     }
 
@@ -947,6 +951,7 @@ impl<'cx, 'gcx, 'tcx> MirBorrowckCtxt<'cx, 'gcx, 'tcx> {
         }
 
         explanation.add_explanation_to_diagnostic(self.infcx.tcx, self.mir, &mut err, "");
+        self.add_region_flow_to_diagnostic(context, borrow, &mut err);
 
         err.buffer(&mut self.errors_buffer);
     }
@@ -1368,6 +1373,7 @@ impl<'cx, 'gcx, 'tcx> MirBorrowckCtxt<'cx, 'gcx, 'tcx> {
 
         self.explain_why_borrow_contains_point(context, loan, None)
             .add_explanation_to_diagnostic(self.infcx.tcx, self.mir, &mut err, "");
+        self.add_region_flow_to_diagnostic(context, loan, &mut err);
 
         err.buffer(&mut self.errors_buffer);
     }
@@ -57,8 +57,22 @@ impl<'b, 'gcx, 'tcx> Flows<'b, 'gcx, 'tcx> {
         }
     }
 
-    crate fn with_outgoing_borrows(&self, op: impl FnOnce(BitIter<'_, BorrowIndex>)) {
-        self.borrows.with_iter_outgoing(op)
+    /// Calls `op` with the borrows that may still be live when the function returns or the
+    /// generator yields, which invalidates all borrows of local data. `exit` is the point where
+    /// this invalidation happens: the start of the returning terminator, or of the block the
+    /// generator resumes in.
+    crate fn with_borrows_live_across_exit(
+        &self,
+        exit: LocationIndex,
+        op: impl FnOnce(&mut dyn Iterator<Item = BorrowIndex>),
+    ) {
+        if let Some(ref polonius) = self.polonius_output {
+            op(&mut polonius.errors_at(exit).iter().cloned())
+        } else {
+            self.borrows.with_iter_outgoing(|mut borrows: BitIter<'_, BorrowIndex>| {
+                op(&mut borrows)
+            })
+        }
     }
 }
 
@@ -713,7 +713,7 @@ impl<'cx, 'gcx, 'tcx> DataflowResultsConsumer<'cx, 'tcx> for MirBorrowckCtxt<'cx
 
             TerminatorKind::Yield {
                 ref value,
-                resume: _,
+                resume,
                 drop: _,
             } => {
                 self.consume_operand(ContextKind::Yield.new(loc), (value, span), flow_state);
@@ -721,7 +721,8 @@ impl<'cx, 'gcx, 'tcx> DataflowResultsConsumer<'cx, 'tcx> for MirBorrowckCtxt<'cx
                 if self.movable_generator {
                     // Look for any active borrows to locals
                     let borrow_set = self.borrow_set.clone();
-                    flow_state.with_outgoing_borrows(|borrows| {
+                    let resume = self.location_table.start_index(resume.start_location());
+                    flow_state.with_borrows_live_across_exit(resume, |borrows| {
                         for i in borrows {
                             let borrow = &borrow_set[i];
                             self.check_for_local_borrow(borrow, span);
@@ -736,7 +737,8 @@ impl<'cx, 'gcx, 'tcx> DataflowResultsConsumer<'cx, 'tcx> for MirBorrowckCtxt<'cx
                 // StorageDead, but we don't always emit those (notably on unwind paths),
                 // so this "extra check" serves as a kind of backup.
                 let borrow_set = self.borrow_set.clone();
-                flow_state.with_outgoing_borrows(|borrows| {
+                let exit = self.location_table.start_index(loc);
+                flow_state.with_borrows_live_across_exit(exit, |borrows| {
                     for i in borrows {
                         let borrow = &borrow_set[i];
                         let context = ContextKind::StorageDead.new(loc);
@@ -9,10 +9,11 @@ use rustc::mir::{
     CastKind, ConstraintCategory, FakeReadCause, Local, Location, Mir, Operand, Place, Projection,
     ProjectionElem, Rvalue, Statement, StatementKind, TerminatorKind,
 };
+use rustc::session::config::BorrowckExplain;
 use rustc::ty::{self, TyCtxt};
 use rustc_data_structures::fx::FxHashSet;
 use rustc_errors::DiagnosticBuilder;
-use syntax_pos::Span;
+use syntax_pos::{MultiSpan, Span};
 
 mod find_use;
 
@@ -315,6 +316,69 @@ impl<'cx, 'gcx, 'tcx> MirBorrowckCtxt<'cx, 'gcx, 'tcx> {
         }
     }
 
+    /// With `-Z borrowck-explain=graph`, adds a note to `err` that walks through the outlives
+    /// constraints keeping `borrow` alive at `context`: from the borrow itself, through each
+    /// constraint at the span it comes from, to the conflicting access.
+    pub(in crate::borrow_check) fn add_region_flow_to_diagnostic(
+        &self,
+        context: Context,
+        borrow: &BorrowData<'tcx>,
+        err: &mut DiagnosticBuilder<'_>,
+    ) {
+        match self.infcx.tcx.sess.opts.debugging_opts.borrowck_explain {
+            Some(BorrowckExplain::Graph) => {}
+            None => return,
+        }
+
+        let regioncx = &self.nonlexical_regioncx;
+        let (path, live_region) = regioncx.explain_sub_region_live_at(
+            self.mir,
+            borrow.region,
+            context.loc,
+        );
+        debug!("add_region_flow_to_diagnostic: path={:?}", path);
+        let path: Vec<_> = path.into_iter().filter(|&(_, _, span)| !span.is_dummy()).collect();
+
+        let mut regions = vec![borrow.region];
+        for &(constraint, _, _) in &path {
+            regions.push(constraint.sup);
+            regions.push(constraint.sub);
+        }
+        regions.push(live_region);
+        let names =
+            regioncx.name_regions_for_diagnostic(self.infcx, self.mir, self.mir_def_id, &regions);
+
+        let borrow_span = self.retrieve_borrow_spans(borrow).var_or_use();
+        let mut spans = MultiSpan::from_span(borrow_span);
+        spans.push_span_label(
+            borrow_span,
+            format!("1. the borrow has region `{}`", names[&borrow.region]),
+        );
+
+        let mut step = 2;
+        for (constraint, category, span) in path {
+            spans.push_span_label(
+                span,
+                format!(
+                    "{}. {}requires `{}: {}`",
+                    step,
+                    category.description(),
+                    names[&constraint.sup],
+                    names[&constraint.sub],
+                ),
+            );
+            step += 1;
+        }
+
+        let access_span = self.mir.source_info(context.loc).span;
+        spans.push_span_label(
+            access_span,
+            format!("{}. `{}` is live at this access", step, names[&live_region]),
+        );
+
+        err.span_note(spans, "the borrow is still in use here because of these constraints");
+    }
+
     /// true if `borrow_location` can reach `use_location` by going through a loop and
     /// `use_location` is also inside of that loop
     fn is_use_in_later_iteration_of_loop(
@@ -164,31 +164,36 @@ pub(in crate::borrow_check) fn compute_regions<'cx, 'gcx, 'tcx>(
     );
 
     // Dump facts if requested.
-    let polonius_output = all_facts.and_then(|all_facts| {
+    if let Some(all_facts) = &all_facts {
         if infcx.tcx.sess.opts.debugging_opts.nll_facts {
             let def_path = infcx.tcx.hir().def_path(def_id);
             let dir_path =
                 PathBuf::from("nll-facts").join(def_path.to_filename_friendly_no_crate());
             all_facts.write_to_dir(dir_path, location_table).unwrap();
         }
+    }
 
-        if infcx.tcx.sess.opts.debugging_opts.polonius {
-            let algorithm = env::var("POLONIUS_ALGORITHM")
-                .unwrap_or_else(|_| String::from("DatafrogOpt"));
-            let algorithm = Algorithm::from_str(&algorithm).unwrap();
-            debug!("compute_regions: using polonius algorithm {:?}", algorithm);
-            Some(Rc::new(Output::compute(
-                &all_facts,
-                algorithm,
-                false,
-            )))
-        } else {
-            None
-        }
+    // Under `-Z polonius`, its loan errors drive the conflict checks in `Flows`, and its
+    // `outlives` facts decide which relations between universal regions have to hold. The
+    // version of `polonius_engine` we use doesn't compute move and initialization errors, so
+    // those, and the explanations attached to all errors, still come from NLL.
+    let polonius = infcx.tcx.sess.opts.debugging_opts.polonius;
+    let polonius_facts = all_facts.as_ref().filter(|_| polonius);
+    let polonius_output = polonius_facts.map(|all_facts| {
+        let algorithm = env::var("POLONIUS_ALGORITHM")
+            .unwrap_or_else(|_| String::from("DatafrogOpt"));
+        let algorithm = Algorithm::from_str(&algorithm).unwrap();
+        debug!("compute_regions: using polonius algorithm {:?}", algorithm);
+        Rc::new(Output::compute(
+            all_facts,
+            algorithm,
+            false,
+        ))
     });
 
     // Solve the region constraints.
-    let closure_region_requirements = regioncx.solve(infcx, &mir, def_id, errors_buffer);
+    let closure_region_requirements =
+        regioncx.solve(infcx, &mir, def_id, polonius_facts, errors_buffer);
 
     // Dump MIR results into a file, if that is enabled. This let us
     // write unit-tests, as well as helping with debugging.
@@ -10,6 +10,7 @@ use rustc::infer::InferCtxt;
 use rustc::infer::NLLRegionVariableOrigin;
 use rustc::mir::{ConstraintCategory, Location, Mir};
 use rustc::ty::{self, RegionVid};
+use rustc_data_structures::fx::FxHashMap;
 use rustc_data_structures::indexed_vec::IndexVec;
 use rustc_errors::{Diagnostic, DiagnosticBuilder};
 use std::collections::VecDeque;
@@ -85,13 +86,7 @@ impl<'tcx> RegionInferenceContext<'tcx> {
 
         // Classify each of the constraints along the path.
         let mut categorized_path: Vec<(ConstraintCategory, bool, Span)> = path.iter()
-            .map(|constraint| {
-                if constraint.category == ConstraintCategory::ClosureBounds {
-                    self.retrieve_closure_constraint_info(mir, &constraint)
-                } else {
-                    (constraint.category, false, constraint.locations.span(mir))
-                }
-            })
+            .map(|constraint| self.categorize_constraint(mir, constraint))
             .collect();
         debug!(
             "best_blame_constraint: categorized_path={:#?}",
@@ -145,6 +140,20 @@ impl<'tcx> RegionInferenceContext<'tcx> {
         *categorized_path.first().unwrap()
     }
 
+    /// The category of `constraint` and the span to report it at, along with whether it comes
+    /// from the body of a closure.
+    fn categorize_constraint(
+        &self,
+        mir: &Mir<'tcx>,
+        constraint: &OutlivesConstraint,
+    ) -> (ConstraintCategory, bool, Span) {
+        if constraint.category == ConstraintCategory::ClosureBounds {
+            self.retrieve_closure_constraint_info(mir, constraint)
+        } else {
+            (constraint.category, false, constraint.locations.span(mir))
+        }
+    }
+
     /// Walks the graph of constraints (where `'a: 'b` is considered
     /// an edge `'a -> 'b`) to find all paths from `from_region` to
     /// `to_region`. The paths are accumulated into the vector
@@ -661,11 +670,68 @@ impl<'tcx> RegionInferenceContext<'tcx> {
     // Finds some region R such that `fr1: R` and `R` is live at
     // `elem`.
     crate fn find_sub_region_live_at(&self, fr1: RegionVid, elem: Location) -> RegionVid {
-        debug!("find_sub_region_live_at(fr1={:?}, elem={:?})", fr1, elem);
+        self.find_sub_region_path_live_at(fr1, elem).1
+    }
+
+    /// Describes the constraints that make `fr1` outlive a region that is live at `elem`, as
+    /// found by `find_sub_region_live_at`: each constraint along the path, with its category and
+    /// the span to report it at. Also returns the region at the end of the path.
+    crate fn explain_sub_region_live_at(
+        &self,
+        mir: &Mir<'tcx>,
+        fr1: RegionVid,
+        elem: Location,
+    ) -> (Vec<(OutlivesConstraint, ConstraintCategory, Span)>, RegionVid) {
+        let (path, r) = self.find_sub_region_path_live_at(fr1, elem);
+        let path = path.iter()
+            .map(|constraint| {
+                let (category, _, span) = self.categorize_constraint(mir, constraint);
+                (*constraint, category, span)
+            })
+            .collect();
+        (path, r)
+    }
+
+    /// Names each of `regions` for use within a single diagnostic. Universal regions get the name
+    /// `give_region_a_name` gives them, so that they match the rest of the diagnostic; the other
+    /// regions are numbered after them, as `'1`, `'2` and so on, in the order they come in.
+    crate fn name_regions_for_diagnostic(
+        &self,
+        infcx: &InferCtxt<'_, '_, 'tcx>,
+        mir: &Mir<'tcx>,
+        mir_def_id: DefId,
+        regions: &[RegionVid],
+    ) -> FxHashMap<RegionVid, String> {
+        let mut names = FxHashMap::default();
+        let mut counter = 1;
+        for &r in regions {
+            if self.universal_regions.is_universal_region(r) && !names.contains_key(&r) {
+                if let Some(name) = self.give_region_a_name(infcx, mir, mir_def_id, r, &mut counter)
+                {
+                    names.insert(r, name.to_string());
+                }
+            }
+        }
+        for &r in regions {
+            names.entry(r).or_insert_with(|| {
+                let name = format!("'{}", counter);
+                counter += 1;
+                name
+            });
+        }
+        names
+    }
+
+    fn find_sub_region_path_live_at(
+        &self,
+        fr1: RegionVid,
+        elem: Location,
+    ) -> (Vec<OutlivesConstraint>, RegionVid) {
+        debug!("find_sub_region_path_live_at(fr1={:?}, elem={:?})", fr1, elem);
         self.find_constraint_paths_between_regions(fr1, |r| {
             // First look for some `r` such that `fr1: r` and `r` is live at `elem`
             debug!(
-                "find_sub_region_live_at: liveness_constraints for {:?} are {:?}",
+                "find_sub_region_path_live_at: liveness_constraints for {:?} are {:?}",
                 r,
                 self.liveness_constraints.region_value_str(r),
             );
@@ -691,7 +757,6 @@ impl<'tcx> RegionInferenceContext<'tcx> {
                         && self.cannot_name_placeholder(r, fr1)
                 })
             })
-            .map(|(_path, r)| r)
             .unwrap()
     }
 
@@ -1,6 +1,7 @@
 use super::universal_regions::UniversalRegions;
 use crate::borrow_check::nll::constraints::graph::NormalConstraintGraph;
 use crate::borrow_check::nll::constraints::{ConstraintSccIndex, ConstraintSet, OutlivesConstraint};
+use crate::borrow_check::nll::facts::AllFacts;
 use crate::borrow_check::nll::region_infer::values::{
     PlaceholderIndices, RegionElement, ToElementIndex
 };
@@ -396,17 +397,22 @@ impl<'tcx> RegionInferenceContext<'tcx> {
     /// Performs region inference and report errors if we see any
     /// unsatisfiable constraints. If this is a closure, returns the
     /// region requirements to propagate to our creator, if any.
+    ///
+    /// Under `-Z polonius`, `polonius_facts` are the facts given to
+    /// Polonius, and the relations between universal regions are
+    /// checked against them instead of the inferred region values.
     pub(super) fn solve<'gcx>(
         &mut self,
         infcx: &InferCtxt<'_, 'gcx, 'tcx>,
         mir: &Mir<'tcx>,
         mir_def_id: DefId,
+        polonius_facts: Option<&AllFacts>,
         errors_buffer: &mut Vec<Diagnostic>,
     ) -> Option<ClosureRegionRequirements<'gcx>> {
         common::time(
             infcx.tcx.sess,
             &format!("solve_nll_region_constraints({:?})", mir_def_id),
-            || self.solve_inner(infcx, mir, mir_def_id, errors_buffer),
+            || self.solve_inner(infcx, mir, mir_def_id, polonius_facts, errors_buffer),
         )
     }
 
@@ -415,6 +421,7 @@ impl<'tcx> RegionInferenceContext<'tcx> {
         infcx: &InferCtxt<'_, 'gcx, 'tcx>,
         mir: &Mir<'tcx>,
         mir_def_id: DefId,
+        polonius_facts: Option<&AllFacts>,
         errors_buffer: &mut Vec<Diagnostic>,
     ) -> Option<ClosureRegionRequirements<'gcx>> {
         self.propagate_constraints(mir);
@@ -442,6 +449,7 @@ impl<'tcx> RegionInferenceContext<'tcx> {
             infcx,
             mir,
             mir_def_id,
+            polonius_facts,
             outlives_requirements.as_mut(),
             errors_buffer,
         );
@@ -1097,14 +1105,22 @@ impl<'tcx> RegionInferenceContext<'tcx> {
     /// If `propagated_outlives_requirements` is `Some`, then we will
     /// push unsatisfied obligations into there. Otherwise, we'll
     /// report them as errors.
+    ///
+    /// With `polonius_facts`, which universal regions each universal
+    /// region has to outlive is read off the Polonius `outlives`
+    /// facts rather than the region values.
     fn check_universal_regions<'gcx>(
         &self,
         infcx: &InferCtxt<'_, 'gcx, 'tcx>,
         mir: &Mir<'tcx>,
         mir_def_id: DefId,
+        polonius_facts: Option<&AllFacts>,
         mut propagated_outlives_requirements: Option<&mut Vec<ClosureOutlivesRequirement<'gcx>>>,
         errors_buffer: &mut Vec<Diagnostic>,
     ) {
+        let polonius_outlived_by =
+            polonius_facts.map(|facts| self.universal_regions_outlived_by_facts(facts));
+
         for (fr, fr_definition) in self.definitions.iter_enumerated() {
             match fr_definition.origin {
                 NLLRegionVariableOrigin::FreeRegion => {
@@ -1116,6 +1132,7 @@ impl<'tcx> RegionInferenceContext<'tcx> {
                         mir,
                         mir_def_id,
                         fr,
+                        polonius_outlived_by.as_ref(),
                         &mut propagated_outlives_requirements,
                         errors_buffer,
                     );
@@ -1140,17 +1157,38 @@ impl<'tcx> RegionInferenceContext<'tcx> {
     ///
     /// Things that are to be propagated are accumulated into the
     /// `outlives_requirements` vector.
+    ///
+    /// If `polonius_outlived_by` is given, the regions `fr` has to
+    /// outlive are taken from it instead.
     fn check_universal_region<'gcx>(
         &self,
         infcx: &InferCtxt<'_, 'gcx, 'tcx>,
         mir: &Mir<'tcx>,
         mir_def_id: DefId,
         longer_fr: RegionVid,
+        polonius_outlived_by: Option<&FxHashMap<RegionVid, Vec<RegionVid>>>,
         propagated_outlives_requirements: &mut Option<&mut Vec<ClosureOutlivesRequirement<'gcx>>>,
         errors_buffer: &mut Vec<Diagnostic>,
     ) {
         debug!("check_universal_region(fr={:?})", longer_fr);
 
+        if let Some(polonius_outlived_by) = polonius_outlived_by {
+            for &shorter_fr in &polonius_outlived_by[&longer_fr] {
+                if let Some(ErrorReported) = self.check_universal_region_relation(
+                    longer_fr,
+                    shorter_fr,
+                    infcx,
+                    mir,
+                    mir_def_id,
+                    propagated_outlives_requirements,
+                    errors_buffer,
+                ) {
+                    return;
+                }
+            }
+            return;
+        }
+
         let longer_fr_scc = self.constraint_sccs.scc(longer_fr);
 
         // Because this free region must be in the ROOT universe, we
@@ -1201,6 +1239,41 @@ impl<'tcx> RegionInferenceContext<'tcx> {
         }
     }
 
+    /// Finds, for each universal region `fr`, the other universal
+    /// regions `o` such that the Polonius `outlives` facts require
+    /// `fr: o`, i.e., that can be reached from `fr` by following
+    /// them. Where in the CFG each fact holds doesn't matter here:
+    /// universal regions are live at every point.
+    fn universal_regions_outlived_by_facts(
+        &self,
+        facts: &AllFacts,
+    ) -> FxHashMap<RegionVid, Vec<RegionVid>> {
+        let mut successors: FxHashMap<RegionVid, FxHashSet<RegionVid>> = FxHashMap::default();
+        for &(sup, sub, _) in &facts.outlives {
+            successors.entry(sup).or_default().insert(sub);
+        }
+
+        self.universal_regions.universal_regions().map(|fr| {
+            let mut visited = FxHashSet::default();
+            let mut stack = vec![fr];
+            let mut outlived_by = vec![];
+            while let Some(r) = stack.pop() {
+                if !visited.insert(r) {
+                    continue;
+                }
+                if r != fr && self.universal_regions.is_universal_region(r) {
+                    outlived_by.push(r);
+                }
+                if let Some(subs) = successors.get(&r) {
+                    stack.extend(subs.iter().cloned());
+                }
+            }
+            // Report errors in a deterministic order.
+            outlived_by.sort();
+            (fr, outlived_by)
+        }).collect()
+    }
+
     fn check_universal_region_relation(
         &self,
         longer_fr: RegionVid,
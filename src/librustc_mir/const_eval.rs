@@ -11,11 +11,12 @@ use rustc::hir::def::Def;
 use rustc::mir::interpret::{ConstEvalErr, ErrorHandled};
 use rustc::mir;
 use rustc::ty::{self, TyCtxt, query::TyCtxtAt};
-use rustc::ty::layout::{self, LayoutOf, VariantIdx};
+use rustc::ty::layout::{self, LayoutOf, VariantIdx, Align, Size};
 use rustc::ty::subst::Subst;
 use rustc::traits::Reveal;
 use rustc_data_structures::fx::FxHashMap;
 use rustc::util::common::ErrorReported;
+use rustc::lint::builtin::LONG_RUNNING_CONST_EVAL;
 
 use syntax::ast::Mutability;
 use syntax::source_map::{Span, DUMMY_SP};
@@ -28,7 +29,8 @@ use crate::interpret::{self,
     snapshot, RefTracking,
 };
 
-/// Number of steps until the detector even starts doing anything.
+/// Number of steps until the detector even starts doing anything, when the
+/// crate lifts the step limit with `#![const_eval_limit = "0"]`.
 /// Also, a warning is shown to the user when this number is reached.
 const STEPS_UNTIL_DETECTOR_ENABLED: isize = 1_000_000;
 /// The number of steps between loop detector snapshots.
@@ -48,7 +50,7 @@ pub(crate) fn mk_eval_cx<'a, 'mir, 'tcx>(
     param_env: ty::ParamEnv<'tcx>,
 ) -> CompileTimeEvalContext<'a, 'mir, 'tcx> {
     debug!("mk_eval_cx: {:?}", param_env);
-    EvalContext::new(tcx.at(span), param_env, CompileTimeInterpreter::new())
+    EvalContext::new(tcx.at(span), param_env, CompileTimeInterpreter::new(tcx))
 }
 
 pub(crate) fn eval_promoted<'a, 'mir, 'tcx>(
@@ -117,7 +119,7 @@ fn eval_body_and_ecx<'a, 'mir, 'tcx>(
     // and try improving it down the road when more information is available
     let span = tcx.def_span(cid.instance.def_id());
     let span = mir.map(|mir| mir.span).unwrap_or(span);
-    let mut ecx = EvalContext::new(tcx.at(span), param_env, CompileTimeInterpreter::new());
+    let mut ecx = EvalContext::new(tcx.at(span), param_env, CompileTimeInterpreter::new(tcx));
     let r = eval_body_using_ecx(&mut ecx, cid, mir, param_env);
     (r, ecx)
 }
@@ -212,6 +214,14 @@ impl Error for ConstEvalError {
 
 // Extra machine state for CTFE, and the Machine instance
 pub struct CompileTimeInterpreter<'a, 'mir, 'tcx: 'a+'mir> {
+    /// The number of steps the interpreter has taken so far.
+    pub(super) steps: usize,
+
+    /// The maximum number of steps, as set by `#![const_eval_limit]`. If it is
+    /// zero, evaluation may take any number of steps and the loop detector is
+    /// used instead.
+    pub(super) step_limit: usize,
+
     /// When this value is negative, it indicates the number of interpreter
     /// steps *until* the loop detector is enabled. When it is positive, it is
     /// the number of steps after the detector has been enabled modulo the loop
@@ -223,8 +233,10 @@ pub struct CompileTimeInterpreter<'a, 'mir, 'tcx: 'a+'mir> {
 }
 
 impl<'a, 'mir, 'tcx> CompileTimeInterpreter<'a, 'mir, 'tcx> {
-    fn new() -> Self {
+    fn new(tcx: TyCtxt<'_, '_, '_>) -> Self {
         CompileTimeInterpreter {
+            steps: 0,
+            step_limit: *tcx.sess.const_eval_limit.get(),
             loop_detector: Default::default(),
             steps_since_detector_enabled: -STEPS_UNTIL_DETECTOR_ENABLED,
         }
@@ -295,27 +307,37 @@ impl<K: Hash + Eq, V> interpret::AllocMap<K, V> for FxHashMap<K, V> {
 type CompileTimeEvalContext<'a, 'mir, 'tcx> =
     EvalContext<'a, 'mir, 'tcx, CompileTimeInterpreter<'a, 'mir, 'tcx>>;
 
-impl interpret::MayLeak for ! {
+/// The kinds of memory CTFE distinguishes in addition to the ones built into the engine.
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum ConstMemoryKind {
+    /// Transient heap memory obtained from the `const_allocate` intrinsic. It has to be
+    /// deallocated, or made global with `const_make_global`, before the final value may
+    /// point to it.
+    Heap,
+}
+
+impl interpret::MayLeak for ConstMemoryKind {
     #[inline(always)]
     fn may_leak(self) -> bool {
-        // `self` is uninhabited
-        self
+        match self {
+            ConstMemoryKind::Heap => false,
+        }
     }
 }
 
 impl<'a, 'mir, 'tcx> interpret::Machine<'a, 'mir, 'tcx>
     for CompileTimeInterpreter<'a, 'mir, 'tcx>
 {
-    type MemoryKinds = !;
+    type MemoryKinds = ConstMemoryKind;
     type PointerTag = ();
 
     type FrameExtra = ();
     type MemoryExtra = ();
     type AllocExtra = ();
 
-    type MemoryMap = FxHashMap<AllocId, (MemoryKind<!>, Allocation)>;
+    type MemoryMap = FxHashMap<AllocId, (MemoryKind<ConstMemoryKind>, Allocation)>;
 
-    const STATIC_KIND: Option<!> = None; // no copying of statics allowed
+    const STATIC_KIND: Option<ConstMemoryKind> = None; // no copying of statics allowed
 
     #[inline(always)]
     fn enforce_validity(_ecx: &EvalContext<'a, 'mir, 'tcx, Self>) -> bool {
@@ -371,11 +393,43 @@ impl<'a, 'mir, 'tcx> interpret::Machine<'a, 'mir, 'tcx>
         if ecx.emulate_intrinsic(instance, args, dest)? {
             return Ok(());
         }
-        // An intrinsic that we do not support
         let intrinsic_name = &ecx.tcx.item_name(instance.def_id()).as_str()[..];
-        Err(
-            ConstEvalError::NeedsRfc(format!("calling intrinsic `{}`", intrinsic_name)).into()
-        )
+        let heap = MemoryKind::Machine(ConstMemoryKind::Heap);
+        match intrinsic_name {
+            "const_allocate" => {
+                let size = ecx.read_scalar(args[0])?.to_usize(ecx)?;
+                let align = ecx.read_scalar(args[1])?.to_usize(ecx)?;
+                if size == 0 {
+                    return err!(HeapAllocZeroBytes);
+                }
+                let align = match Align::from_bytes(align) {
+                    Ok(align) => align,
+                    Err(_) => return err!(HeapAllocNonPowerOfTwoAlignment(align)),
+                };
+                let ptr = ecx.memory_mut().allocate(Size::from_bytes(size), align, heap);
+                ecx.write_scalar(Scalar::Ptr(ptr), dest)?;
+            }
+            "const_deallocate" => {
+                let ptr = ecx.read_scalar(args[0])?.to_ptr()?;
+                let size = ecx.read_scalar(args[1])?.to_usize(ecx)?;
+                let align = ecx.read_scalar(args[2])?.to_usize(ecx)?;
+                let align = match Align::from_bytes(align) {
+                    Ok(align) => align,
+                    Err(_) => return err!(HeapAllocNonPowerOfTwoAlignment(align)),
+                };
+                ecx.memory_mut().deallocate(ptr, Some((Size::from_bytes(size), align)), heap)?;
+            }
+            "const_make_global" => {
+                let ptr = ecx.read_scalar(args[0])?.to_ptr()?;
+                ecx.memory_mut().intern_heap(ptr, heap)?;
+                ecx.write_scalar(Scalar::Ptr(ptr), dest)?;
+            }
+            // An intrinsic that we do not support
+            _ => return Err(
+                ConstEvalError::NeedsRfc(format!("calling intrinsic `{}`", intrinsic_name)).into()
+            ),
+        }
+        Ok(())
     }
 
     fn ptr_op(
@@ -416,6 +470,21 @@ impl<'a, 'mir, 'tcx> interpret::Machine<'a, 'mir, 'tcx>
     }
 
     fn before_terminator(ecx: &mut EvalContext<'a, 'mir, 'tcx, Self>) -> EvalResult<'tcx> {
+        ecx.machine.steps += 1;
+        if ecx.machine.step_limit != 0 {
+            if ecx.machine.steps > ecx.machine.step_limit {
+                // Point at the terminator we stopped at rather than at the last
+                // statement, so a loop without statements is still blamed.
+                let frame = ecx.frame();
+                let span = frame.mir.basic_blocks()[frame.block].terminator().source_info.span;
+                ecx.tcx.span = span;
+                return err!(StepLimitReached(ecx.machine.step_limit));
+            }
+            return Ok(());
+        }
+
+        // With `#![const_eval_limit = "0"]`, evaluation may take any number of
+        // steps, and only stops once the loop detector sees a repeated state.
         {
             let steps = &mut ecx.machine.steps_since_detector_enabled;
 
@@ -439,6 +508,29 @@ impl<'a, 'mir, 'tcx> interpret::Machine<'a, 'mir, 'tcx>
         )
     }
 
+    fn before_ptr_write(
+        ecx: &EvalContext<'a, 'mir, 'tcx, Self>,
+        dest: PlaceTy<'tcx>,
+    ) -> EvalResult<'tcx> {
+        // With `#![feature(const_heap)]`, the const checker allows writes through raw
+        // pointers so that memory from `const_allocate` can be filled. Any other memory,
+        // e.g. a local behind a shared reference cast to `*mut`, must not be written through
+        // a pointer, including references reborrowed from such a raw pointer. None of the
+        // intrinsics writing through pointer arguments (`copy_nonoverlapping`, `write_bytes`,
+        // `move_val_init`, ...) are supported here, so they cannot get around this either.
+        if dest.layout.is_zst() {
+            return Ok(());
+        }
+        let ptr = dest.to_mem_place().ptr.to_ptr()?;
+        match ecx.memory().alloc_map().get(&ptr.alloc_id) {
+            Some(&(MemoryKind::Machine(ConstMemoryKind::Heap), _)) => Ok(()),
+            _ => err!(MachineError(
+                "writing through a pointer to memory not allocated by `const_allocate`"
+                    .to_string(),
+            )),
+        }
+    }
+
     #[inline(always)]
     fn tag_new_allocation(
         _ecx: &mut EvalContext<'a, 'mir, 'tcx, Self>,
@@ -583,6 +675,30 @@ pub fn const_eval_provider<'a, 'tcx>(
     })
 }
 
+/// Warns about constants whose evaluation took more than half of the steps allowed by
+/// `#![const_eval_limit]`, as small changes to them or to the `const fn`s they call could
+/// then make their evaluation fail.
+fn lint_long_running_const_eval<'a, 'mir, 'tcx>(
+    tcx: TyCtxt<'a, 'tcx, 'tcx>,
+    def_id: DefId,
+    machine: &CompileTimeInterpreter<'a, 'mir, 'tcx>,
+) {
+    if machine.step_limit == 0 || machine.steps <= machine.step_limit / 2 {
+        return;
+    }
+    if let Some(hir_id) = tcx.hir().as_local_hir_id(def_id) {
+        tcx.lint_hir(
+            LONG_RUNNING_CONST_EVAL,
+            hir_id,
+            tcx.def_span(def_id),
+            &format!(
+                "evaluation of this constant came close to the `const_eval_limit` of {} steps",
+                machine.step_limit,
+            ),
+        );
+    }
+}
+
 pub fn const_eval_raw_provider<'a, 'tcx>(
     tcx: TyCtxt<'a, 'tcx, 'tcx>,
     key: ty::ParamEnvAnd<'tcx, GlobalId<'tcx>>,
@@ -636,6 +752,9 @@ pub fn const_eval_raw_provider<'a, 'tcx>(
     };
 
     let (res, ecx) = eval_body_and_ecx(tcx, cid, None, key.param_env);
+    if res.is_ok() && cid.promoted.is_none() {
+        lint_long_running_const_eval(tcx, def_id, &ecx.machine);
+    }
     res.and_then(|place| {
         Ok(RawConst {
             alloc_id: place.to_ptr().expect("we allocated this ptr!").alloc_id,
@@ -0,0 +1,91 @@
+//! Inspecting the results of a dataflow analysis at any location.
+
+use rustc::mir::{BasicBlock, Location, Mir};
+
+use super::{Analysis, Direction, Results};
+
+/// Recovers the state of a dataflow analysis at any `Location` from its results.
+///
+/// "Before" refers to the order in which the analysis applies effects: for a backward analysis,
+/// `seek_before` gives the state right *after* the statement in program order.
+///
+/// Seeking to a later location of the same block, in the direction of the analysis, only applies
+/// the effects in between; any other seek starts over from the entry set of the block.
+pub struct ResultsCursor<'a, 'tcx: 'a, A> where A: Analysis<'tcx> {
+    mir: &'a Mir<'tcx>,
+    results: &'a Results<'tcx, A>,
+    state: A::Domain,
+
+    /// The block `state` belongs to, and how many of its effects have been applied to it.
+    pos: Option<(BasicBlock, usize)>,
+}
+
+impl<'a, 'tcx: 'a, A> ResultsCursor<'a, 'tcx, A> where A: Analysis<'tcx> {
+    pub fn new(mir: &'a Mir<'tcx>, results: &'a Results<'tcx, A>) -> Self {
+        ResultsCursor {
+            mir,
+            results,
+            state: results.analysis.bottom_value(mir),
+            pos: None,
+        }
+    }
+
+    /// The state at the current position of the cursor.
+    pub fn get(&self) -> &A::Domain {
+        &self.state
+    }
+
+    /// Moves the cursor to right before the effect of the statement or terminator at `location`.
+    pub fn seek_before(&mut self, location: Location) {
+        let effects = self.effects_before(location);
+        self.seek(location.block, effects);
+    }
+
+    /// The number of effects applied before the one at `location`.
+    fn effects_before(&self, location: Location) -> usize {
+        let num_statements = self.mir[location.block].statements.len();
+        assert!(location.statement_index <= num_statements);
+
+        if A::Direction::is_forward() {
+            location.statement_index
+        } else {
+            num_statements - location.statement_index
+        }
+    }
+
+    fn seek(&mut self, block: BasicBlock, effects: usize) {
+        let mut applied = match self.pos {
+            Some((pos_block, applied)) if pos_block == block && applied <= effects => applied,
+            _ => {
+                self.state.clone_from(self.results.entry_set_for_block(block));
+                0
+            }
+        };
+
+        while applied < effects {
+            self.apply_effect(block, applied);
+            applied += 1;
+        }
+        self.pos = Some((block, applied));
+    }
+
+    /// Applies the effect with the given index, counted in the order the analysis visits them.
+    fn apply_effect(&mut self, block: BasicBlock, index: usize) {
+        let block_data = &self.mir[block];
+        let num_statements = block_data.statements.len();
+        let statement_index = if A::Direction::is_forward() {
+            index
+        } else {
+            num_statements - index
+        };
+
+        let location = Location { block, statement_index };
+        let analysis = &self.results.analysis;
+        if statement_index == num_statements {
+            analysis.apply_terminator_effect(&mut self.state, block_data.terminator(), location);
+        } else {
+            let statement = &block_data.statements[statement_index];
+            analysis.apply_statement_effect(&mut self.state, statement, location);
+        }
+    }
+}
@@ -0,0 +1,228 @@
+//! The direction in which a dataflow analysis runs through the CFG.
+
+use rustc::mir::{self, BasicBlock, Location, Mir};
+use rustc_data_structures::bit_set::BitSet;
+
+use super::{Analysis, GenKill, GenKillAnalysis};
+
+pub trait Direction {
+    fn is_forward() -> bool;
+
+    /// Applies the effects of all statements and the terminator of `block` to `state`, in the
+    /// order the analysis visits them.
+    fn apply_effects_in_block<'tcx, A>(analysis: &A,
+                                       state: &mut A::Domain,
+                                       block: BasicBlock,
+                                       block_data: &mir::BasicBlockData<'tcx>)
+        where A: Analysis<'tcx>;
+
+    /// Records the effects of all statements and the terminator of `block` in `trans`, in the
+    /// order the analysis visits them.
+    fn gen_kill_effects_in_block<'tcx, A>(analysis: &A,
+                                          trans: &mut impl GenKill<A::Idx>,
+                                          block: BasicBlock,
+                                          block_data: &mir::BasicBlockData<'tcx>)
+        where A: GenKillAnalysis<'tcx>;
+
+    /// Passes `exit_state`, the state after all effects of `block` have been applied, to
+    /// `propagate` for each block it flows into.
+    ///
+    /// Edges leaving a block in `dead_unwinds` towards its unwind target are ignored.
+    fn join_state_into_successors_of<'tcx, A>(analysis: &A,
+                                              mir: &Mir<'tcx>,
+                                              dead_unwinds: Option<&BitSet<BasicBlock>>,
+                                              exit_state: &mut A::Domain,
+                                              block: BasicBlock,
+                                              propagate: impl FnMut(BasicBlock, &A::Domain))
+        where A: Analysis<'tcx>;
+}
+
+/// Dataflow from the start block to the returns. The state on entry to a block is the state
+/// before its first statement.
+pub struct Forward;
+
+impl Direction for Forward {
+    fn is_forward() -> bool {
+        true
+    }
+
+    fn apply_effects_in_block<'tcx, A>(analysis: &A,
+                                       state: &mut A::Domain,
+                                       block: BasicBlock,
+                                       block_data: &mir::BasicBlockData<'tcx>)
+        where A: Analysis<'tcx>
+    {
+        for (statement_index, statement) in block_data.statements.iter().enumerate() {
+            let location = Location { block, statement_index };
+            analysis.apply_statement_effect(state, statement, location);
+        }
+
+        let location = Location { block, statement_index: block_data.statements.len() };
+        analysis.apply_terminator_effect(state, block_data.terminator(), location);
+    }
+
+    fn gen_kill_effects_in_block<'tcx, A>(analysis: &A,
+                                          trans: &mut impl GenKill<A::Idx>,
+                                          block: BasicBlock,
+                                          block_data: &mir::BasicBlockData<'tcx>)
+        where A: GenKillAnalysis<'tcx>
+    {
+        for (statement_index, statement) in block_data.statements.iter().enumerate() {
+            let location = Location { block, statement_index };
+            analysis.statement_effect(trans, statement, location);
+        }
+
+        let location = Location { block, statement_index: block_data.statements.len() };
+        analysis.terminator_effect(trans, block_data.terminator(), location);
+    }
+
+    fn join_state_into_successors_of<'tcx, A>(analysis: &A,
+                                              mir: &Mir<'tcx>,
+                                              dead_unwinds: Option<&BitSet<BasicBlock>>,
+                                              exit_state: &mut A::Domain,
+                                              block: BasicBlock,
+                                              mut propagate: impl FnMut(BasicBlock, &A::Domain))
+        where A: Analysis<'tcx>
+    {
+        use rustc::mir::TerminatorKind::*;
+
+        let unwind_is_dead = dead_unwinds.map_or(false, |dead| dead.contains(block));
+
+        match mir[block].terminator().kind {
+            Return | Resume | Abort | GeneratorDrop | Unreachable => {}
+
+            Goto { target } |
+            Assert { target, cleanup: None, .. } |
+            Yield { resume: target, drop: None, .. } |
+            Drop { target, location: _, unwind: None } |
+            DropAndReplace { target, value: _, location: _, unwind: None } => {
+                propagate(target, exit_state);
+            }
+
+            Yield { resume: target, drop: Some(drop), .. } => {
+                propagate(target, exit_state);
+                propagate(drop, exit_state);
+            }
+
+            Assert { target, cleanup: Some(unwind), .. } |
+            Drop { target, location: _, unwind: Some(unwind) } |
+            DropAndReplace { target, value: _, location: _, unwind: Some(unwind) } => {
+                propagate(target, exit_state);
+                if !unwind_is_dead {
+                    propagate(unwind, exit_state);
+                }
+            }
+
+            SwitchInt { ref targets, .. } => {
+                for &target in targets {
+                    propagate(target, exit_state);
+                }
+            }
+
+            Call { cleanup, ref destination, ref func, ref args, .. } => {
+                if let Some(unwind) = cleanup {
+                    if !unwind_is_dead {
+                        propagate(unwind, exit_state);
+                    }
+                }
+
+                if let Some((ref return_place, target)) = *destination {
+                    // This must be done *last*, so that the effect of the return doesn't leak
+                    // into the unwind edge.
+                    analysis.apply_call_return_effect(exit_state, block, func, args, return_place);
+                    propagate(target, exit_state);
+                }
+            }
+
+            FalseEdges { real_target, ref imaginary_targets } => {
+                propagate(real_target, exit_state);
+                for &target in imaginary_targets {
+                    propagate(target, exit_state);
+                }
+            }
+
+            FalseUnwind { real_target, unwind } => {
+                propagate(real_target, exit_state);
+                if let Some(unwind) = unwind {
+                    if !unwind_is_dead {
+                        propagate(unwind, exit_state);
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Dataflow from the returns to the start block. The state on entry to a block is the state after
+/// its terminator.
+pub struct Backward;
+
+impl Direction for Backward {
+    fn is_forward() -> bool {
+        false
+    }
+
+    fn apply_effects_in_block<'tcx, A>(analysis: &A,
+                                       state: &mut A::Domain,
+                                       block: BasicBlock,
+                                       block_data: &mir::BasicBlockData<'tcx>)
+        where A: Analysis<'tcx>
+    {
+        let location = Location { block, statement_index: block_data.statements.len() };
+        analysis.apply_terminator_effect(state, block_data.terminator(), location);
+
+        for (statement_index, statement) in block_data.statements.iter().enumerate().rev() {
+            let location = Location { block, statement_index };
+            analysis.apply_statement_effect(state, statement, location);
+        }
+    }
+
+    fn gen_kill_effects_in_block<'tcx, A>(analysis: &A,
+                                          trans: &mut impl GenKill<A::Idx>,
+                                          block: BasicBlock,
+                                          block_data: &mir::BasicBlockData<'tcx>)
+        where A: GenKillAnalysis<'tcx>
+    {
+        let location = Location { block, statement_index: block_data.statements.len() };
+        analysis.terminator_effect(trans, block_data.terminator(), location);
+
+        for (statement_index, statement) in block_data.statements.iter().enumerate().rev() {
+            let location = Location { block, statement_index };
+            analysis.statement_effect(trans, statement, location);
+        }
+    }
+
+    fn join_state_into_successors_of<'tcx, A>(analysis: &A,
+                                              mir: &Mir<'tcx>,
+                                              dead_unwinds: Option<&BitSet<BasicBlock>>,
+                                              exit_state: &mut A::Domain,
+                                              block: BasicBlock,
+                                              mut propagate: impl FnMut(BasicBlock, &A::Domain))
+        where A: Analysis<'tcx>
+    {
+        for &pred in mir.predecessors_for(block).iter() {
+            let terminator = mir[pred].terminator();
+            if dead_unwinds.map_or(false, |dead| dead.contains(pred)) &&
+                terminator.unwind() == Some(&Some(block))
+            {
+                continue;
+            }
+
+            match terminator.kind {
+                mir::TerminatorKind::Call {
+                    ref func,
+                    ref args,
+                    destination: Some((ref return_place, target)),
+                    ..
+                } if target == block => {
+                    // The return value is only stored along this edge.
+                    let mut state = exit_state.clone();
+                    analysis.apply_call_return_effect(&mut state, pred, func, args, return_place);
+                    propagate(pred, &state);
+                }
+
+                _ => propagate(pred, exit_state),
+            }
+        }
+    }
+}
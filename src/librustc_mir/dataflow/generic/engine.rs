@@ -0,0 +1,153 @@
+//! The fixpoint iteration of a dataflow analysis.
+
+use rustc::mir::{traversal, BasicBlock, Mir, START_BLOCK};
+use rustc_data_structures::bit_set::BitSet;
+use rustc_data_structures::indexed_vec::IndexVec;
+use rustc_data_structures::work_queue::WorkQueue;
+
+use super::{Analysis, Direction, GenKillAnalysis, GenKillSet, ResultsCursor};
+
+/// The results of a dataflow analysis: the state on entry to each block, in the direction of the
+/// analysis.
+pub struct Results<'tcx, A> where A: Analysis<'tcx> {
+    pub analysis: A,
+    entry_sets: IndexVec<BasicBlock, A::Domain>,
+}
+
+impl<'tcx, A> Results<'tcx, A> where A: Analysis<'tcx> {
+    pub fn entry_set_for_block(&self, block: BasicBlock) -> &A::Domain {
+        &self.entry_sets[block]
+    }
+
+    pub fn into_entry_sets(self) -> IndexVec<BasicBlock, A::Domain> {
+        self.entry_sets
+    }
+
+    /// Returns a cursor to inspect the state at any location in `mir`.
+    pub fn cursor<'a>(&'a self, mir: &'a Mir<'tcx>) -> ResultsCursor<'a, 'tcx, A> {
+        ResultsCursor::new(mir, self)
+    }
+}
+
+/// Computes the fixpoint of a dataflow analysis.
+pub struct Engine<'a, 'tcx: 'a, A> where A: Analysis<'tcx> {
+    mir: &'a Mir<'tcx>,
+    analysis: A,
+    dead_unwinds: Option<&'a BitSet<BasicBlock>>,
+    entry_sets: IndexVec<BasicBlock, A::Domain>,
+
+    /// The effect of each block as a whole, if the analysis allows computing it up front.
+    apply_trans_for_block: Option<Box<dyn Fn(BasicBlock, &mut A::Domain) + 'a>>,
+}
+
+impl<'a, 'tcx: 'a, A> Engine<'a, 'tcx, A> where A: Analysis<'tcx> {
+    /// Creates an engine for an analysis that applies the effects of each statement and
+    /// terminator whenever a block is visited.
+    pub fn new_generic(mir: &'a Mir<'tcx>, analysis: A) -> Self {
+        Self::new(mir, analysis, None)
+    }
+
+    fn new(mir: &'a Mir<'tcx>,
+           analysis: A,
+           apply_trans_for_block: Option<Box<dyn Fn(BasicBlock, &mut A::Domain) + 'a>>)
+           -> Self {
+        let bottom_value = analysis.bottom_value(mir);
+        let mut entry_sets = IndexVec::from_elem(bottom_value, mir.basic_blocks());
+        if A::Direction::is_forward() {
+            analysis.initialize_start_block(mir, &mut entry_sets[START_BLOCK]);
+        }
+
+        Engine {
+            mir,
+            analysis,
+            dead_unwinds: None,
+            entry_sets,
+            apply_trans_for_block,
+        }
+    }
+
+    /// Signals that the unwind edges of the given blocks are never taken.
+    pub fn dead_unwinds(mut self, dead_unwinds: &'a BitSet<BasicBlock>) -> Self {
+        self.dead_unwinds = Some(dead_unwinds);
+        self
+    }
+
+    pub fn iterate_to_fixpoint(self) -> Results<'tcx, A> {
+        let Engine {
+            mir,
+            analysis,
+            dead_unwinds,
+            mut entry_sets,
+            apply_trans_for_block,
+        } = self;
+
+        debug!("iterate_to_fixpoint: running `{}`", A::NAME);
+
+        // Visiting the blocks in (reverse) postorder first means most blocks only need to be
+        // visited once if the CFG is acyclic. Unreachable blocks come last.
+        let mut dirty_queue: WorkQueue<BasicBlock> =
+            WorkQueue::with_none(mir.basic_blocks().len());
+        if A::Direction::is_forward() {
+            for (block, _) in traversal::reverse_postorder(mir) {
+                dirty_queue.insert(block);
+            }
+        } else {
+            for (block, _) in traversal::postorder(mir) {
+                dirty_queue.insert(block);
+            }
+        }
+        for block in mir.basic_blocks().indices() {
+            dirty_queue.insert(block);
+        }
+
+        let mut state = analysis.bottom_value(mir);
+        while let Some(block) = dirty_queue.pop() {
+            let block_data = &mir[block];
+            state.clone_from(&entry_sets[block]);
+
+            match apply_trans_for_block {
+                Some(ref apply_trans) => apply_trans(block, &mut state),
+                None => A::Direction::apply_effects_in_block(&analysis, &mut state, block,
+                                                             block_data),
+            }
+
+            A::Direction::join_state_into_successors_of(
+                &analysis,
+                mir,
+                dead_unwinds,
+                &mut state,
+                block,
+                |target, state| {
+                    if analysis.join(&mut entry_sets[target], state) {
+                        dirty_queue.insert(target);
+                    }
+                },
+            );
+        }
+
+        Results { analysis, entry_sets }
+    }
+}
+
+impl<'a, 'tcx: 'a, A> Engine<'a, 'tcx, A> where A: GenKillAnalysis<'tcx> {
+    /// Creates an engine for a gen/kill analysis, computing the transfer function of each block
+    /// once up front.
+    pub fn new_gen_kill(mir: &'a Mir<'tcx>, analysis: A) -> Self {
+        let identity = GenKillSet::identity(analysis.domain_size(mir));
+        let mut trans_for_block = IndexVec::from_elem(identity, mir.basic_blocks());
+        for (block, block_data) in mir.basic_blocks().iter_enumerated() {
+            <A as GenKillAnalysis<'tcx>>::Direction::gen_kill_effects_in_block(
+                &analysis,
+                &mut trans_for_block[block],
+                block,
+                block_data,
+            );
+        }
+
+        let apply_trans = move |block: BasicBlock, state: &mut BitSet<A::Idx>| {
+            trans_for_block[block].apply(state);
+        };
+
+        Self::new(mir, analysis, Some(Box::new(apply_trans)))
+    }
+}
@@ -0,0 +1,33 @@
+//! The lattices used as the state of a dataflow analysis.
+
+use rustc_data_structures::bit_set::BitSet;
+use rustc_data_structures::indexed_vec::Idx;
+
+/// A set of values with a least upper bound ("join") for each pair of them.
+///
+/// The fixpoint iteration only terminates if the lattice has finite height, i.e., if any value
+/// can only grow a finite number of times.
+pub trait JoinSemiLattice: Eq {
+    /// Replaces `self` with the join of `self` and `other`. Returns `true` if `self` changed.
+    fn join(&mut self, other: &Self) -> bool;
+}
+
+/// `false` is less than `true`.
+impl JoinSemiLattice for bool {
+    fn join(&mut self, other: &Self) -> bool {
+        if *other && !*self {
+            *self = true;
+            true
+        } else {
+            false
+        }
+    }
+}
+
+/// Sets are ordered by inclusion, so the join is the union. This is the lattice of "maybe"
+/// analyses.
+impl<T: Idx> JoinSemiLattice for BitSet<T> {
+    fn join(&mut self, other: &Self) -> bool {
+        self.union(other)
+    }
+}
@@ -0,0 +1,222 @@
+//! A framework for dataflow analyses over MIR.
+//!
+//! `BitDenotation` is limited to forward analyses whose state is a bitset and whose effects are
+//! gen/kill sets. An `Analysis` instead describes how each statement and terminator transforms a
+//! state, which can be any `JoinSemiLattice`, and runs either `Forward` or `Backward` through the
+//! CFG. `Engine` computes the fixpoint, yielding the state on entry to each block (in the
+//! direction of the analysis), and a `ResultsCursor` recovers the state at any `Location` from
+//! that.
+//!
+//! Analyses whose state is a bitset and whose effects can be expressed as gen/kill sets should
+//! implement `GenKillAnalysis` rather than `Analysis`. The effect of each block is then computed
+//! once up front, instead of on every visit of the block during the fixpoint iteration.
+
+use rustc::mir::{self, BasicBlock, Location, Mir};
+use rustc_data_structures::bit_set::{BitSet, HybridBitSet};
+use rustc_data_structures::indexed_vec::Idx;
+
+mod cursor;
+mod direction;
+mod engine;
+mod lattice;
+
+pub use self::cursor::ResultsCursor;
+pub use self::direction::{Backward, Direction, Forward};
+pub use self::engine::{Engine, Results};
+pub use self::lattice::JoinSemiLattice;
+
+/// A dataflow analysis.
+pub trait Analysis<'tcx> {
+    /// The state of the analysis at a given point.
+    type Domain: Clone + JoinSemiLattice;
+
+    /// Whether the analysis runs forward or backward through the CFG.
+    type Direction: Direction;
+
+    /// A name describing the analysis, for debugging output.
+    const NAME: &'static str;
+
+    /// The initial state of each block, before anything is known about it.
+    fn bottom_value(&self, mir: &Mir<'tcx>) -> Self::Domain;
+
+    /// Mutates the state on entry to the start block, to account for what holds on function
+    /// entry (e.g., that the arguments are initialized). Only used by forward analyses.
+    fn initialize_start_block(&self, mir: &Mir<'tcx>, state: &mut Self::Domain);
+
+    /// Joins `other` into `state` where control flow merges, returning whether `state` changed.
+    ///
+    /// Analyses whose join isn't determined by their domain alone can override this.
+    fn join(&self, state: &mut Self::Domain, other: &Self::Domain) -> bool {
+        state.join(other)
+    }
+
+    /// Applies the effect of `statement` to `state`.
+    fn apply_statement_effect(&self,
+                              state: &mut Self::Domain,
+                              statement: &mir::Statement<'tcx>,
+                              location: Location);
+
+    /// Applies the effect of `terminator` to `state`.
+    ///
+    /// For `Call` terminators, the effect of storing the return value doesn't belong here, as it
+    /// only happens on the successful return edge; see `apply_call_return_effect`.
+    fn apply_terminator_effect(&self,
+                               state: &mut Self::Domain,
+                               terminator: &mir::Terminator<'tcx>,
+                               location: Location);
+
+    /// Applies the effect of a successful return from the `Call` terminating `block` to `state`.
+    ///
+    /// This is only applied along the edge to the return block, never along the unwind edge.
+    fn apply_call_return_effect(&self,
+                                _state: &mut Self::Domain,
+                                _block: BasicBlock,
+                                _func: &mir::Operand<'tcx>,
+                                _args: &[mir::Operand<'tcx>],
+                                _return_place: &mir::Place<'tcx>) {}
+}
+
+/// A dataflow analysis whose state is a set and whose effects are gen/kill sets.
+///
+/// The sets are joined by union. Every `GenKillAnalysis` is an `Analysis`.
+pub trait GenKillAnalysis<'tcx> {
+    /// The type of the elements of the set.
+    type Idx: Idx;
+
+    /// Whether the analysis runs forward or backward through the CFG.
+    type Direction: Direction;
+
+    /// A name describing the analysis, for debugging output.
+    const NAME: &'static str;
+
+    /// The number of possible elements of the set.
+    fn domain_size(&self, mir: &Mir<'tcx>) -> usize;
+
+    /// See `Analysis::initialize_start_block`.
+    fn initialize_start_block(&self, mir: &Mir<'tcx>, state: &mut BitSet<Self::Idx>);
+
+    /// Records the effect of `statement` in `trans`.
+    fn statement_effect(&self,
+                        trans: &mut impl GenKill<Self::Idx>,
+                        statement: &mir::Statement<'tcx>,
+                        location: Location);
+
+    /// Records the effect of `terminator` in `trans`.
+    fn terminator_effect(&self,
+                         trans: &mut impl GenKill<Self::Idx>,
+                         terminator: &mir::Terminator<'tcx>,
+                         location: Location);
+
+    /// See `Analysis::apply_call_return_effect`.
+    fn call_return_effect(&self,
+                          _state: &mut BitSet<Self::Idx>,
+                          _block: BasicBlock,
+                          _func: &mir::Operand<'tcx>,
+                          _args: &[mir::Operand<'tcx>],
+                          _return_place: &mir::Place<'tcx>) {}
+}
+
+impl<'tcx, A> Analysis<'tcx> for A where A: GenKillAnalysis<'tcx> {
+    type Domain = BitSet<A::Idx>;
+    type Direction = <A as GenKillAnalysis<'tcx>>::Direction;
+
+    const NAME: &'static str = <A as GenKillAnalysis<'tcx>>::NAME;
+
+    fn bottom_value(&self, mir: &Mir<'tcx>) -> Self::Domain {
+        BitSet::new_empty(self.domain_size(mir))
+    }
+
+    fn initialize_start_block(&self, mir: &Mir<'tcx>, state: &mut Self::Domain) {
+        GenKillAnalysis::initialize_start_block(self, mir, state)
+    }
+
+    fn apply_statement_effect(&self,
+                              state: &mut Self::Domain,
+                              statement: &mir::Statement<'tcx>,
+                              location: Location) {
+        self.statement_effect(state, statement, location)
+    }
+
+    fn apply_terminator_effect(&self,
+                               state: &mut Self::Domain,
+                               terminator: &mir::Terminator<'tcx>,
+                               location: Location) {
+        self.terminator_effect(state, terminator, location)
+    }
+
+    fn apply_call_return_effect(&self,
+                                state: &mut Self::Domain,
+                                block: BasicBlock,
+                                func: &mir::Operand<'tcx>,
+                                args: &[mir::Operand<'tcx>],
+                                return_place: &mir::Place<'tcx>) {
+        self.call_return_effect(state, block, func, args, return_place)
+    }
+}
+
+/// Something effects of a `GenKillAnalysis` can be recorded in. Later calls take precedence over
+/// earlier ones for the same element.
+pub trait GenKill<T> {
+    /// Adds `elem` to the set.
+    fn gen(&mut self, elem: T);
+
+    /// Removes `elem` from the set.
+    fn kill(&mut self, elem: T);
+
+    fn gen_all(&mut self, elems: impl IntoIterator<Item = T>) {
+        for elem in elems {
+            self.gen(elem);
+        }
+    }
+
+    fn kill_all(&mut self, elems: impl IntoIterator<Item = T>) {
+        for elem in elems {
+            self.kill(elem);
+        }
+    }
+}
+
+/// The combined effect of a sequence of statements and terminators, i.e., a transfer function.
+#[derive(Clone, Debug)]
+pub struct GenKillSet<T: Idx> {
+    gen: HybridBitSet<T>,
+    kill: HybridBitSet<T>,
+}
+
+impl<T: Idx> GenKillSet<T> {
+    /// Creates a transfer function without any effect.
+    pub fn identity(domain_size: usize) -> Self {
+        GenKillSet {
+            gen: HybridBitSet::new_empty(domain_size),
+            kill: HybridBitSet::new_empty(domain_size),
+        }
+    }
+
+    /// Applies the transfer function to `state`.
+    pub fn apply(&self, state: &mut BitSet<T>) {
+        state.union(&self.gen);
+        state.subtract(&self.kill);
+    }
+}
+
+impl<T: Idx> GenKill<T> for GenKillSet<T> {
+    fn gen(&mut self, elem: T) {
+        self.gen.insert(elem);
+        self.kill.remove(elem);
+    }
+
+    fn kill(&mut self, elem: T) {
+        self.kill.insert(elem);
+        self.gen.remove(elem);
+    }
+}
+
+impl<T: Idx> GenKill<T> for BitSet<T> {
+    fn gen(&mut self, elem: T) {
+        self.insert(elem);
+    }
+
+    fn kill(&mut self, elem: T) {
+        self.remove(elem);
+    }
+}
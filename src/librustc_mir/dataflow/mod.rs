@@ -2,7 +2,6 @@ use syntax::ast::{self, MetaItem};
 
 use rustc_data_structures::bit_set::{BitSet, BitSetOperator, HybridBitSet};
 use rustc_data_structures::indexed_vec::Idx;
-use rustc_data_structures::work_queue::WorkQueue;
 
 use rustc::ty::{self, TyCtxt};
 use rustc::mir::{self, Mir, BasicBlock, BasicBlockData, Location, Statement, Terminator};
@@ -28,6 +27,7 @@ use self::move_paths::MoveData;
 
 mod at_location;
 pub mod drop_flag_effects;
+pub mod generic;
 mod graphviz;
 mod impls;
 pub mod move_paths;
@@ -167,19 +167,88 @@ impl<'a, 'gcx: 'tcx, 'tcx: 'a, BD> DataflowAnalysis<'a, 'tcx, BD> where BD: BitD
     }
 }
 
-struct PropagationContext<'b, 'a: 'b, 'tcx: 'a, O> where O: 'b + BitDenotation<'tcx>
+/// Runs the fixpoint iteration of a `BitDenotation` on the generic dataflow engine.
+///
+/// The effect of each block has already been summarized in its gen and kill sets by
+/// `build_sets`, so it is applied as a whole at the terminator.
+struct BlockTransfer<'b, 'a: 'b, 'tcx: 'a, BD> where BD: 'b + BitDenotation<'tcx>
 {
-    builder: &'b mut DataflowAnalysis<'a, 'tcx, O>,
+    mir: &'a Mir<'tcx>,
+    flow_state: &'b DataflowState<'tcx, BD>,
+    bottom_value: bool,
+}
+
+impl<'b, 'a: 'b, 'tcx: 'a, BD> generic::Analysis<'tcx> for BlockTransfer<'b, 'a, 'tcx, BD>
+    where BD: BitDenotation<'tcx>
+{
+    type Domain = BitSet<BD::Idx>;
+    type Direction = generic::Forward;
+
+    const NAME: &'static str = "block_transfer";
+
+    fn bottom_value(&self, _mir: &Mir<'tcx>) -> Self::Domain {
+        let bits_per_block = self.flow_state.sets.bits_per_block();
+        if self.bottom_value {
+            BitSet::new_filled(bits_per_block)
+        } else {
+            BitSet::new_empty(bits_per_block)
+        }
+    }
+
+    fn initialize_start_block(&self, _mir: &Mir<'tcx>, state: &mut Self::Domain) {
+        state.overwrite(self.flow_state.sets.on_entry_set_for(mir::START_BLOCK.index()));
+    }
+
+    fn join(&self, state: &mut Self::Domain, other: &Self::Domain) -> bool {
+        self.flow_state.operator.join(state, other)
+    }
+
+    fn apply_statement_effect(&self,
+                              _state: &mut Self::Domain,
+                              _statement: &Statement<'tcx>,
+                              _location: Location) {}
+
+    fn apply_terminator_effect(&self,
+                               state: &mut Self::Domain,
+                               _terminator: &Terminator<'tcx>,
+                               location: Location) {
+        let sets = &self.flow_state.sets;
+        state.union(sets.gen_set_for(location.block.index()));
+        state.subtract(sets.kill_set_for(location.block.index()));
+    }
+
+    fn apply_call_return_effect(&self,
+                                state: &mut Self::Domain,
+                                block: BasicBlock,
+                                _func: &mir::Operand<'tcx>,
+                                _args: &[mir::Operand<'tcx>],
+                                return_place: &mir::Place<'tcx>) {
+        let dest_bb = match self.mir[block].terminator().kind {
+            mir::TerminatorKind::Call { destination: Some((_, dest_bb)), .. } => dest_bb,
+            _ => bug!("call return effect applied to a block without a returning call"),
+        };
+        self.flow_state.operator.propagate_call_return(state, block, dest_bb, return_place);
+    }
 }
 
 impl<'a, 'tcx: 'a, BD> DataflowAnalysis<'a, 'tcx, BD> where BD: BitDenotation<'tcx>
 {
     fn propagate(&mut self) {
-        let mut temp = BitSet::new_empty(self.flow_state.sets.bits_per_block);
-        let mut propcx = PropagationContext {
-            builder: self,
+        let entry_sets = {
+            let analysis = BlockTransfer {
+                mir: self.mir,
+                flow_state: &self.flow_state,
+                bottom_value: self.bottom_value,
+            };
+            generic::Engine::new_generic(self.mir, analysis)
+                .dead_unwinds(self.dead_unwinds)
+                .iterate_to_fixpoint()
+                .into_entry_sets()
         };
-        propcx.walk_cfg(&mut temp);
+
+        for (bb, entry_set) in entry_sets.into_iter_enumerated() {
+            self.flow_state.sets.on_entry_sets[bb.index()] = entry_set;
+        }
     }
 
     fn build_sets(&mut self) {
@@ -222,27 +291,6 @@ impl<'a, 'tcx: 'a, BD> DataflowAnalysis<'a, 'tcx, BD> where BD: BitDenotation<'t
     }
 }
 
-impl<'b, 'a: 'b, 'tcx: 'a, BD> PropagationContext<'b, 'a, 'tcx, BD> where BD: BitDenotation<'tcx>
-{
-    fn walk_cfg(&mut self, in_out: &mut BitSet<BD::Idx>) {
-        let mut dirty_queue: WorkQueue<mir::BasicBlock> =
-            WorkQueue::with_all(self.builder.mir.basic_blocks().len());
-        let mir = self.builder.mir;
-        while let Some(bb) = dirty_queue.pop() {
-            let bb_data = &mir[bb];
-            {
-                let sets = self.builder.flow_state.sets.for_block(bb.index());
-                debug_assert!(in_out.words().len() == sets.on_entry.words().len());
-                in_out.overwrite(sets.on_entry);
-                in_out.union(sets.gen_set);
-                in_out.subtract(sets.kill_set);
-            }
-            self.builder.propagate_bits_into_graph_successors_of(
-                in_out, (bb, bb_data), &mut dirty_queue);
-        }
-    }
-}
-
 fn dataflow_path(context: &str, path: &str) -> PathBuf {
     let mut path = PathBuf::from(path);
     let new_file_name = {
@@ -380,6 +428,8 @@ pub struct DataflowAnalysis<'a, 'tcx: 'a, O> where O: BitDenotation<'tcx>
     flow_state: DataflowState<'tcx, O>,
     dead_unwinds: &'a BitSet<mir::BasicBlock>,
     mir: &'a Mir<'tcx>,
+    /// The initial value of each bit, see `InitialFlow`.
+    bottom_value: bool,
 }
 
 impl<'a, 'tcx: 'a, O> DataflowAnalysis<'a, 'tcx, O> where O: BitDenotation<'tcx>
@@ -709,6 +759,7 @@ impl<'a, 'tcx, D> DataflowAnalysis<'a, 'tcx, D> where D: BitDenotation<'tcx>
         DataflowAnalysis {
             mir,
             dead_unwinds,
+            bottom_value: D::bottom_value(),
             flow_state: DataflowState {
                 sets: AllSets {
                     bits_per_block,
@@ -721,100 +772,3 @@ impl<'a, 'tcx, D> DataflowAnalysis<'a, 'tcx, D> where D: BitDenotation<'tcx>
         }
     }
 }
-
-impl<'a, 'tcx: 'a, D> DataflowAnalysis<'a, 'tcx, D> where D: BitDenotation<'tcx> {
-    /// Propagates the bits of `in_out` into all the successors of `bb`,
-    /// using bitwise operator denoted by `self.operator`.
-    ///
-    /// For most blocks, this is entirely uniform. However, for blocks
-    /// that end with a call terminator, the effect of the call on the
-    /// dataflow state may depend on whether the call returned
-    /// successfully or unwound.
-    ///
-    /// To reflect this, the `propagate_call_return` method of the
-    /// `BitDenotation` mutates `in_out` when propagating `in_out` via
-    /// a call terminator; such mutation is performed *last*, to
-    /// ensure its side-effects do not leak elsewhere (e.g., into
-    /// unwind target).
-    fn propagate_bits_into_graph_successors_of(
-        &mut self,
-        in_out: &mut BitSet<D::Idx>,
-        (bb, bb_data): (mir::BasicBlock, &mir::BasicBlockData<'tcx>),
-        dirty_list: &mut WorkQueue<mir::BasicBlock>)
-    {
-        match bb_data.terminator().kind {
-            mir::TerminatorKind::Return |
-            mir::TerminatorKind::Resume |
-            mir::TerminatorKind::Abort |
-            mir::TerminatorKind::GeneratorDrop |
-            mir::TerminatorKind::Unreachable => {}
-            mir::TerminatorKind::Goto { target } |
-            mir::TerminatorKind::Assert { target, cleanup: None, .. } |
-            mir::TerminatorKind::Yield { resume: target, drop: None, .. } |
-            mir::TerminatorKind::Drop { target, location: _, unwind: None } |
-            mir::TerminatorKind::DropAndReplace {
-                target, value: _, location: _, unwind: None
-            } => {
-                self.propagate_bits_into_entry_set_for(in_out, target, dirty_list);
-            }
-            mir::TerminatorKind::Yield { resume: target, drop: Some(drop), .. } => {
-                self.propagate_bits_into_entry_set_for(in_out, target, dirty_list);
-                self.propagate_bits_into_entry_set_for(in_out, drop, dirty_list);
-            }
-            mir::TerminatorKind::Assert { target, cleanup: Some(unwind), .. } |
-            mir::TerminatorKind::Drop { target, location: _, unwind: Some(unwind) } |
-            mir::TerminatorKind::DropAndReplace {
-                target, value: _, location: _, unwind: Some(unwind)
-            } => {
-                self.propagate_bits_into_entry_set_for(in_out, target, dirty_list);
-                if !self.dead_unwinds.contains(bb) {
-                    self.propagate_bits_into_entry_set_for(in_out, unwind, dirty_list);
-                }
-            }
-            mir::TerminatorKind::SwitchInt { ref targets, .. } => {
-                for target in targets {
-                    self.propagate_bits_into_entry_set_for(in_out, *target, dirty_list);
-                }
-            }
-            mir::TerminatorKind::Call { cleanup, ref destination, .. } => {
-                if let Some(unwind) = cleanup {
-                    if !self.dead_unwinds.contains(bb) {
-                        self.propagate_bits_into_entry_set_for(in_out, unwind, dirty_list);
-                    }
-                }
-                if let Some((ref dest_place, dest_bb)) = *destination {
-                    // N.B.: This must be done *last*, after all other
-                    // propagation, as documented in comment above.
-                    self.flow_state.operator.propagate_call_return(
-                        in_out, bb, dest_bb, dest_place);
-                    self.propagate_bits_into_entry_set_for(in_out, dest_bb, dirty_list);
-                }
-            }
-            mir::TerminatorKind::FalseEdges { real_target, ref imaginary_targets } => {
-                self.propagate_bits_into_entry_set_for(in_out, real_target, dirty_list);
-                for target in imaginary_targets {
-                    self.propagate_bits_into_entry_set_for(in_out, *target, dirty_list);
-                }
-            }
-            mir::TerminatorKind::FalseUnwind { real_target, unwind } => {
-                self.propagate_bits_into_entry_set_for(in_out, real_target, dirty_list);
-                if let Some(unwind) = unwind {
-                    if !self.dead_unwinds.contains(bb) {
-                        self.propagate_bits_into_entry_set_for(in_out, unwind, dirty_list);
-                    }
-                }
-            }
-        }
-    }
-
-    fn propagate_bits_into_entry_set_for(&mut self,
-                                         in_out: &BitSet<D::Idx>,
-                                         bb: mir::BasicBlock,
-                                         dirty_queue: &mut WorkQueue<mir::BasicBlock>) {
-        let entry_set = &mut self.flow_state.sets.for_block(bb.index()).on_entry;
-        let set_changed = self.flow_state.operator.join(entry_set, &in_out);
-        if set_changed {
-            dirty_queue.insert(bb);
-        }
-    }
-}
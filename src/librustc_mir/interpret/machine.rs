@@ -197,6 +197,17 @@ pub trait Machine<'a, 'mir, 'tcx>: Sized {
         Ok(())
     }
 
+    /// Called before an assignment, a `SetDiscriminant` or a call writes to `dest`, if the
+    /// place was reached by dereferencing a pointer, be it a raw pointer or a reference.
+    /// Intrinsics writing to the memory behind a pointer argument must call this as well.
+    #[inline]
+    fn before_ptr_write(
+        _ecx: &EvalContext<'a, 'mir, 'tcx, Self>,
+        _dest: PlaceTy<'tcx, Self::PointerTag>,
+    ) -> EvalResult<'tcx> {
+        Ok(())
+    }
+
     /// Called immediately before a new stack frame got pushed
     fn stack_push(
         ecx: &mut EvalContext<'a, 'mir, 'tcx, Self>,
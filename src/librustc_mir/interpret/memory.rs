@@ -630,7 +630,9 @@ where
         // remove allocation
         let (kind, mut alloc) = self.alloc_map.remove(&alloc_id).unwrap();
         match kind {
-            MemoryKind::Machine(_) => bug!("Static cannot refer to machine memory"),
+            // Machine memory (for CTFE, transient heap allocations) must not outlive the
+            // evaluation unless it was explicitly made global, see `intern_heap`.
+            MemoryKind::Machine(_) => return err!(HeapAllocLeaked),
             MemoryKind::Stack | MemoryKind::Vtable => {},
         }
         // ensure llvm knows not to put this into immutable memory
@@ -657,6 +659,46 @@ where
         }
         Ok(())
     }
+
+    /// Turns a machine allocation of the given `kind` into immutable global memory while
+    /// the evaluation is still running, so that the final value may point to it.
+    /// Everything the allocation points to must already be global.
+    pub fn intern_heap(
+        &mut self,
+        ptr: Pointer,
+        kind: MemoryKind<M::MemoryKinds>,
+    ) -> EvalResult<'tcx> {
+        trace!("intern_heap {:?}", ptr);
+        if ptr.offset.bytes() != 0 {
+            return err!(MachineError(
+                "only a pointer to the start of an allocation can be made global".to_string(),
+            ));
+        }
+        let (alloc_kind, mut alloc) = match self.alloc_map.remove(&ptr.alloc_id) {
+            Some(alloc) => alloc,
+            None => return err!(MachineError(
+                "only a live heap allocation can be made global".to_string(),
+            )),
+        };
+        if alloc_kind != kind {
+            return err!(MachineError(
+                format!("{:?} memory cannot be made global", alloc_kind),
+            ));
+        }
+        for &(_, inner) in alloc.relocations.values() {
+            if self.alloc_map.contains_key(&inner) {
+                return err!(HeapAllocLeaked);
+            } else if self.dead_alloc_map.contains_key(&inner) {
+                return err!(ValidationFailure(
+                    "encountered dangling pointer in global heap memory".into(),
+                ));
+            }
+        }
+        alloc.mutability = Mutability::Immutable;
+        let alloc = self.tcx.intern_const_alloc(alloc);
+        self.tcx.alloc_map.lock().set_alloc_id_memory(ptr.alloc_id, alloc);
+        Ok(())
+    }
 }
 
 /// Reading and writing.
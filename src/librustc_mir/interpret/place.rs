@@ -655,6 +655,26 @@ where
         Ok(place)
     }
 
+    /// Computes the destination of a write, and lets the machine check writes through
+    /// pointers.
+    pub fn eval_place_for_write(
+        &mut self,
+        mir_place: &mir::Place<'tcx>
+    ) -> EvalResult<'tcx, PlaceTy<'tcx, M::PointerTag>> {
+        let place = self.eval_place(mir_place)?;
+
+        let mut derefs_ptr = false;
+        let mut base = mir_place;
+        while let mir::Place::Projection(ref proj) = *base {
+            derefs_ptr |= proj.elem == mir::ProjectionElem::Deref;
+            base = &proj.base;
+        }
+        if derefs_ptr {
+            M::before_ptr_write(self, place)?;
+        }
+        Ok(place)
+    }
+
     /// Write a scalar to a place
     pub fn write_scalar(
         &mut self,
@@ -88,7 +88,7 @@ impl<'a, 'mir, 'tcx, M: Machine<'a, 'mir, 'tcx>> EvalContext<'a, 'mir, 'tcx, M>
                 ref place,
                 variant_index,
             } => {
-                let dest = self.eval_place(place)?;
+                let dest = self.eval_place_for_write(place)?;
                 self.write_discriminant_index(variant_index, dest)?;
             }
 
@@ -137,7 +137,7 @@ impl<'a, 'mir, 'tcx, M: Machine<'a, 'mir, 'tcx>> EvalContext<'a, 'mir, 'tcx, M>
         rvalue: &mir::Rvalue<'tcx>,
         place: &mir::Place<'tcx>,
     ) -> EvalResult<'tcx> {
-        let dest = self.eval_place(place)?;
+        let dest = self.eval_place_for_write(place)?;
 
         use rustc::mir::Rvalue::*;
         match *rvalue {
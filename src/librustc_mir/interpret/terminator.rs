@@ -70,7 +70,7 @@ impl<'a, 'mir, 'tcx, M: Machine<'a, 'mir, 'tcx>> EvalContext<'a, 'mir, 'tcx, M>
                 ..
             } => {
                 let (dest, ret) = match *destination {
-                    Some((ref lv, target)) => (Some(self.eval_place(lv)?), Some(target)),
+                    Some((ref lv, target)) => (Some(self.eval_place_for_write(lv)?), Some(target)),
                     None => (None, None),
                 };
 
@@ -199,12 +199,14 @@ impl<'a, 'mir, 'tcx> ConstPropagator<'a, 'mir, 'tcx> {
                     | UnterminatedCString(_)
                     | HeapAllocZeroBytes
                     | HeapAllocNonPowerOfTwoAlignment(_)
+                    | HeapAllocLeaked
                     | Unreachable
                     | ReadFromReturnPointer
                     | GeneratorResumedAfterReturn
                     | GeneratorResumedAfterPanic
                     | ReferencedConstant
                     | InfiniteLoop
+                    | StepLimitReached(_)
                     => {
                         // FIXME: report UB here
                     },
@@ -0,0 +1,340 @@
+//! Destination propagation.
+//!
+//! This pass looks for moves and copies between locals,
+//!
+//!     DEST = move SRC
+//!
+//! and, when liveness allows it, makes `SRC` and `DEST` the same local. The assignment becomes a
+//! no-op, so the value gets computed straight into its final destination. This matters most for
+//! large aggregates, where every such assignment turns into a `memcpy` that LLVM frequently fails
+//! to remove. The typical case is a value that is built in a temporary and then returned:
+//!
+//!     _1 = const produce() -> bb1
+//!     ...
+//!     _0 = move _1
+//!
+//! becomes
+//!
+//!     _0 = const produce() -> bb1
+//!     ...
+//!     nop
+//!
+//! Two locals can be merged if they don't *conflict*: neither of them may be written while the
+//! other one is live, and neither of them may be written by a statement or terminator that also
+//! reads the other one (so that, e.g., a call doesn't get its return place aliased with one of its
+//! arguments). The copy being eliminated is the only exception to this, since after it both
+//! locals hold the same value. Liveness comes from `util::liveness`, which doesn't track accesses
+//! through references, so locals that are ever borrowed are left alone. Arguments are never
+//! replaced either, since their values come from the caller.
+//!
+//! Merging is done greedily in MIR order, with the conflicts of merged locals being combined, so
+//! chains of moves collapse into a single local. The storage markers of merged locals are removed,
+//! as their live ranges no longer nest properly.
+
+use rustc::mir::{BasicBlock, Local, LocalKind, Location, Mir, Operand, Place, Rvalue};
+use rustc::mir::{Statement, StatementKind, TerminatorKind, RETURN_PLACE};
+use rustc::mir::visit::{MutVisitor, NonMutatingUseContext, PlaceContext, Visitor};
+use rustc::ty::TyCtxt;
+use rustc_data_structures::bit_set::{BitSet, SparseBitMatrix};
+use rustc_data_structures::fx::FxHashMap;
+use rustc_data_structures::indexed_vec::IndexVec;
+use crate::transform::{MirPass, MirSource};
+use crate::util::def_use::DefUseAnalysis;
+use crate::util::liveness::{self, DefUse, IdentityMap, LiveVarSet};
+
+pub struct DestinationPropagation;
+
+impl MirPass for DestinationPropagation {
+    fn run_pass<'a, 'tcx>(&self,
+                          tcx: TyCtxt<'a, 'tcx, 'tcx>,
+                          _source: MirSource<'tcx>,
+                          mir: &mut Mir<'tcx>) {
+        // We only run when the MIR optimization level is > 1.
+        // This avoids a slow pass, and messing up debug info.
+        if tcx.sess.opts.debugging_opts.mir_opt_level <= 1 {
+            return;
+        }
+
+        let candidates = find_candidates(mir);
+        if candidates.is_empty() {
+            return;
+        }
+
+        let mut relevant = BitSet::new_empty(mir.local_decls.len());
+        for &(dest, src) in &candidates {
+            relevant.insert(dest);
+            relevant.insert(src);
+        }
+        let mut conflicts = find_conflicts(mir, &relevant);
+
+        // Merge the locals of each candidate that doesn't conflict, tracking which local each one
+        // got merged into.
+        let mut renames: IndexVec<Local, Local> = mir.local_decls.indices().collect();
+        let mut merged = BitSet::new_empty(mir.local_decls.len());
+        for (dest, src) in candidates {
+            let dest = root(&renames, dest);
+            let src = root(&renames, src);
+            if dest == src || conflicts.contains(dest, src) {
+                continue;
+            }
+            // `src` may itself have become the target of an earlier merge.
+            match mir.local_kind(src) {
+                LocalKind::Var | LocalKind::Temp => {}
+                LocalKind::Arg | LocalKind::ReturnPointer => continue,
+            }
+
+            debug!("dest_prop: merging {:?} into {:?}", src, dest);
+            renames[src] = dest;
+            let src_conflicts: Vec<_> = conflicts.iter(src).collect();
+            for local in src_conflicts {
+                conflicts.insert(dest, local);
+                conflicts.insert(local, dest);
+            }
+            merged.insert(src);
+            merged.insert(dest);
+        }
+
+        if merged.is_empty() {
+            return;
+        }
+        for local in mir.local_decls.indices() {
+            renames[local] = root(&renames, local);
+        }
+
+        RenameLocals { renames: &renames }.visit_mir(mir);
+
+        for data in mir.basic_blocks_mut() {
+            for stmt in &mut data.statements {
+                let remove = match stmt.kind {
+                    StatementKind::StorageLive(local) |
+                    StatementKind::StorageDead(local) => merged.contains(local),
+                    _ => match copy_operands(stmt) {
+                        Some((dest, src)) => dest == src,
+                        None => false,
+                    },
+                };
+                if remove {
+                    stmt.make_nop();
+                }
+            }
+        }
+
+        // The locals that were merged away are now unused and get removed by `SimplifyLocals`.
+    }
+}
+
+fn root(renames: &IndexVec<Local, Local>, mut local: Local) -> Local {
+    while renames[local] != local {
+        local = renames[local];
+    }
+    local
+}
+
+/// If `stmt` is of the form `DEST = SRC`, `DEST = move SRC` with both sides being locals, returns
+/// `(DEST, SRC)`.
+fn copy_operands(stmt: &Statement<'_>) -> Option<(Local, Local)> {
+    if let StatementKind::Assign(Place::Local(dest), box Rvalue::Use(ref operand)) = stmt.kind {
+        match *operand {
+            Operand::Copy(Place::Local(src)) |
+            Operand::Move(Place::Local(src)) => return Some((dest, src)),
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Returns the `(DEST, SRC)` pairs of all assignments that could be eliminated by merging `SRC`
+/// into `DEST`, in MIR order.
+fn find_candidates(mir: &Mir<'_>) -> Vec<(Local, Local)> {
+    let mut borrowed = BorrowedLocals(BitSet::new_empty(mir.local_decls.len()));
+    borrowed.visit_mir(mir);
+    let borrowed = borrowed.0;
+
+    let mut candidates = vec![];
+    for data in mir.basic_blocks() {
+        for stmt in &data.statements {
+            let (dest, src) = match copy_operands(stmt) {
+                Some(operands) => operands,
+                None => continue,
+            };
+            if dest == src || borrowed.contains(dest) || borrowed.contains(src) {
+                continue;
+            }
+            match mir.local_kind(dest) {
+                LocalKind::Var | LocalKind::Temp | LocalKind::ReturnPointer => {}
+                LocalKind::Arg => continue,
+            }
+            match mir.local_kind(src) {
+                LocalKind::Var | LocalKind::Temp => {}
+                LocalKind::Arg | LocalKind::ReturnPointer => continue,
+            }
+            if mir.local_decls[dest].ty != mir.local_decls[src].ty {
+                continue;
+            }
+            candidates.push((dest, src));
+        }
+    }
+    candidates
+}
+
+/// Computes which of the `relevant` locals conflict with each other. The resulting matrix is
+/// symmetric.
+fn find_conflicts<'tcx>(mir: &Mir<'tcx>,
+                        relevant: &BitSet<Local>)
+                        -> SparseBitMatrix<Local, Local> {
+    let mut def_use_analysis = DefUseAnalysis::new(mir);
+    def_use_analysis.analyze(mir);
+
+    let mut liveness = liveness::liveness_of_locals(mir, &IdentityMap::new(mir));
+    add_return_place_liveness(mir, &def_use_analysis, &mut liveness.outs);
+
+    // Only the relevant locals can conflict, so only their accesses are needed. They are grouped
+    // by location here, so that each block can be walked backwards below.
+    let mut accesses: FxHashMap<Location, Vec<(Local, PlaceContext<'tcx>)>> = FxHashMap::default();
+    for local in relevant.iter() {
+        for place_use in &def_use_analysis.local_info(local).defs_and_uses {
+            accesses.entry(place_use.location).or_default().push((local, place_use.context));
+        }
+    }
+
+    let mut conflicts = SparseBitMatrix::new(mir.local_decls.len());
+    let mut live = LiveVarSet::new_empty(mir.local_decls.len());
+    let no_accesses = vec![];
+
+    // Walk each block backwards, so that `live` always holds the relevant locals that are live
+    // right after the location being looked at.
+    for (block, data) in mir.basic_blocks().iter_enumerated() {
+        live.overwrite(&liveness.outs[block]);
+        live.intersect(relevant);
+
+        let location = mir.terminator_loc(block);
+        let mut terminator_accesses = accesses.get(&location).unwrap_or(&no_accesses).clone();
+        if let TerminatorKind::Return = data.terminator().kind {
+            // `return` reads the return place, but that isn't visible in the MIR.
+            if relevant.contains(RETURN_PLACE) {
+                let context = PlaceContext::NonMutatingUse(NonMutatingUseContext::Move);
+                terminator_accesses.push((RETURN_PLACE, context));
+            }
+        }
+        apply_accesses(&terminator_accesses, &mut conflicts, &mut live, None);
+
+        for (statement_index, stmt) in data.statements.iter().enumerate().rev() {
+            let location = Location { block, statement_index };
+            let stmt_accesses = accesses.get(&location).unwrap_or(&no_accesses);
+            apply_accesses(stmt_accesses, &mut conflicts, &mut live, copy_operands(stmt));
+        }
+    }
+
+    conflicts
+}
+
+/// Records the conflicts caused by the `accesses` of a single statement or terminator, and updates
+/// `live` from the locals live after it to those live before it. The assignment `exempt`, if any,
+/// doesn't make its two sides conflict.
+fn apply_accesses(accesses: &[(Local, PlaceContext<'_>)],
+                  conflicts: &mut SparseBitMatrix<Local, Local>,
+                  live: &mut LiveVarSet<Local>,
+                  exempt: Option<(Local, Local)>) {
+    let uses = accesses.iter().filter_map(|&(local, context)| match liveness::categorize(context) {
+        Some(DefUse::Use) | Some(DefUse::Drop) => Some(local),
+        Some(DefUse::Def) | None => None,
+    });
+
+    for &(written, context) in accesses {
+        if !context.is_mutating_use() {
+            continue;
+        }
+        for other in live.iter().chain(uses.clone()) {
+            if other == written || exempt == Some((written, other)) {
+                continue;
+            }
+            conflicts.insert(written, other);
+            conflicts.insert(other, written);
+        }
+    }
+
+    for &(local, context) in accesses {
+        if let Some(DefUse::Def) = liveness::categorize(context) {
+            live.remove(local);
+        }
+    }
+    for local in uses {
+        live.insert(local);
+    }
+}
+
+/// `util::liveness` doesn't consider `return` to read the return place, so the return place is
+/// never live according to it. Compute its liveness from its def-use chain and add it to `outs`.
+/// This still needs a fixpoint over the CFG: the def-use chain only says where the return place
+/// is accessed, not whether a use is reachable from a def without another def in between.
+fn add_return_place_liveness(mir: &Mir<'_>,
+                             def_use_analysis: &DefUseAnalysis<'_>,
+                             outs: &mut IndexVec<BasicBlock, LiveVarSet<Local>>) {
+    // For each block, the index of the first statement accessing the return place, and whether
+    // that access reads it. This alone decides whether the return place is live on entry to a
+    // block that accesses it.
+    let mut first_access: IndexVec<BasicBlock, Option<(usize, bool)>> =
+        IndexVec::from_elem(None, mir.basic_blocks());
+    let mut record = |location: Location, is_use: bool| {
+        let first = &mut first_access[location.block];
+        match *first {
+            Some((index, ref mut was_use)) if index == location.statement_index => {
+                *was_use |= is_use;
+            }
+            Some((index, _)) if index < location.statement_index => {}
+            _ => *first = Some((location.statement_index, is_use)),
+        }
+    };
+
+    for place_use in &def_use_analysis.local_info(RETURN_PLACE).defs_and_uses {
+        match liveness::categorize(place_use.context) {
+            Some(DefUse::Def) => record(place_use.location, false),
+            Some(DefUse::Use) | Some(DefUse::Drop) => record(place_use.location, true),
+            None => {}
+        }
+    }
+    for (block, data) in mir.basic_blocks().iter_enumerated() {
+        if let TerminatorKind::Return = data.terminator().kind {
+            record(mir.terminator_loc(block), true);
+        }
+    }
+
+    let mut changed = true;
+    while changed {
+        changed = false;
+        for (block, first) in first_access.iter_enumerated().rev() {
+            let live = match *first {
+                Some((_, is_use)) => is_use,
+                None => outs[block].contains(RETURN_PLACE),
+            };
+            if live {
+                for &pred in mir.predecessors_for(block).iter() {
+                    changed |= outs[pred].insert(RETURN_PLACE);
+                }
+            }
+        }
+    }
+}
+
+struct BorrowedLocals(BitSet<Local>);
+
+impl<'tcx> Visitor<'tcx> for BorrowedLocals {
+    fn visit_rvalue(&mut self, rvalue: &Rvalue<'tcx>, location: Location) {
+        if let Rvalue::Ref(_, _, ref place) = *rvalue {
+            if let Some(local) = place.base_local() {
+                self.0.insert(local);
+            }
+        }
+        self.super_rvalue(rvalue, location)
+    }
+}
+
+struct RenameLocals<'a> {
+    renames: &'a IndexVec<Local, Local>,
+}
+
+impl<'a, 'tcx> MutVisitor<'tcx> for RenameLocals<'a> {
+    fn visit_local(&mut self, local: &mut Local, _: PlaceContext<'tcx>, _: Location) {
+        *local = self.renames[*local];
+    }
+}
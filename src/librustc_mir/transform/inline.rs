@@ -4,11 +4,15 @@ use rustc::hir::CodegenFnAttrFlags;
 use rustc::hir::def_id::DefId;
 
 use rustc_data_structures::bit_set::BitSet;
+use rustc_data_structures::fx::FxHashSet;
 use rustc_data_structures::indexed_vec::{Idx, IndexVec};
+use rustc_data_structures::sync::Lrc;
 
 use rustc::mir::*;
 use rustc::mir::visit::*;
+use rustc::session::config::OptLevel;
 use rustc::ty::{self, Instance, InstanceDef, ParamEnv, Ty, TyCtxt};
+use rustc::ty::query::Providers;
 use rustc::ty::subst::{Subst,Substs};
 
 use std::collections::VecDeque;
@@ -42,12 +46,58 @@ impl MirPass for Inline {
                           tcx: TyCtxt<'a, 'tcx, 'tcx>,
                           source: MirSource<'tcx>,
                           mir: &mut Mir<'tcx>) {
-        if tcx.sess.opts.debugging_opts.mir_opt_level >= 2 {
+        if inline_enabled(tcx) {
             Inliner { tcx, source }.run_pass(mir);
         }
     }
 }
 
+/// Inlining is done when optimizing, and at `-Z mir-opt-level=2` and above, unless overridden by
+/// `-Z inline-mir`.
+pub(crate) fn inline_enabled(tcx: TyCtxt<'_, '_, '_>) -> bool {
+    let opts = &tcx.sess.opts;
+    if let Some(enabled) = opts.debugging_opts.inline_mir {
+        return enabled;
+    }
+    match opts.debugging_opts.mir_opt_level {
+        0 => false,
+        1 => opts.optimize != OptLevel::No,
+        _ => true,
+    }
+}
+
+pub(crate) fn provide(providers: &mut Providers<'_>) {
+    *providers = Providers {
+        mir_inliner_callees,
+        ..*providers
+    };
+}
+
+fn mir_inliner_callees<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>,
+                                 def_id: DefId)
+                                 -> Lrc<Vec<(DefId, &'tcx Substs<'tcx>)>> {
+    let mir = tcx.mir_validated(def_id).borrow();
+    Lrc::new(direct_callees(tcx, &mir))
+}
+
+/// The functions called by `mir`, with regions erased from their substitutions.
+fn direct_callees<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>,
+                            mir: &Mir<'tcx>)
+                            -> Vec<(DefId, &'tcx Substs<'tcx>)> {
+    let mut callees = vec![];
+    for bb_data in mir.basic_blocks() {
+        if let TerminatorKind::Call { ref func, .. } = bb_data.terminator().kind {
+            if let ty::FnDef(def_id, substs) = func.ty(mir, tcx).sty {
+                let callee = (def_id, tcx.erase_regions(&substs));
+                if !callees.contains(&callee) {
+                    callees.push(callee);
+                }
+            }
+        }
+    }
+    callees
+}
+
 struct Inliner<'a, 'tcx: 'a> {
     tcx: TyCtxt<'a, 'tcx, 'tcx>,
     source: MirSource<'tcx>,
@@ -55,18 +105,18 @@ struct Inliner<'a, 'tcx: 'a> {
 
 impl<'a, 'tcx> Inliner<'a, 'tcx> {
     fn run_pass(&self, caller_mir: &mut Mir<'tcx>) {
-        // Keep a queue of callsites to try inlining on. We take
-        // advantage of the fact that queries detect cycles here to
-        // allow us to try and fetch the fully optimized MIR of a
-        // call; if it succeeds, we can inline it and we know that
-        // they do not call us.  Otherwise, we just don't try to
-        // inline.
+        // Keep a queue of callsites to try inlining on. We inline the
+        // fully optimized MIR of the callee, which is only possible if
+        // the callee can't call us, as its optimized MIR could then
+        // depend on ours; see `may_call_caller`.
         //
         // We use a queue so that we inline "broadly" before we inline
         // in depth. It is unclear if this is the best heuristic,
         // really, but that's true of all the heuristics in this
         // file. =)
 
+        // Each callsite comes with the functions inlined to get to it, so that we don't keep
+        // inlining mutually recursive functions into each other.
         let mut callsites = VecDeque::new();
 
         let param_env = self.tcx.param_env(self.source.def_id());
@@ -79,7 +129,7 @@ impl<'a, 'tcx> Inliner<'a, 'tcx> {
                                                                     bb_data,
                                                                     caller_mir,
                                                                     param_env) {
-                    callsites.push_back(callsite);
+                    callsites.push_back((callsite, vec![]));
                 }
             }
         } else {
@@ -91,32 +141,23 @@ impl<'a, 'tcx> Inliner<'a, 'tcx> {
 
         loop {
             local_change = false;
-            while let Some(callsite) = callsites.pop_front() {
+            while let Some((callsite, history)) = callsites.pop_front() {
                 debug!("checking whether to inline callsite {:?}", callsite);
                 if !self.tcx.is_mir_available(callsite.callee) {
                     debug!("checking whether to inline callsite {:?} - MIR unavailable", callsite);
                     continue;
                 }
 
-                let self_node_id = self.tcx.hir().as_local_node_id(self.source.def_id()).unwrap();
-                let callee_node_id = self.tcx.hir().as_local_node_id(callsite.callee);
-
-                let callee_mir = if let Some(callee_node_id) = callee_node_id {
-                    // Avoid a cycle here by only using `optimized_mir` only if we have
-                    // a lower node id than the callee. This ensures that the callee will
-                    // not inline us. This trick only works without incremental compilation.
-                    // So don't do it if that is enabled.
-                    if !self.tcx.dep_graph.is_fully_enabled()
-                        && self_node_id.as_u32() < callee_node_id.as_u32() {
-                        self.tcx.optimized_mir(callsite.callee)
-                    } else {
-                        continue;
-                    }
-                } else {
-                    // This cannot result in a cycle since the callee MIR is from another crate
-                    // and is already optimized.
-                    self.tcx.optimized_mir(callsite.callee)
-                };
+                // The optimized MIR of a local callee may itself be computed by inlining into
+                // it, so it is only safe to request if the callee can't end up calling us.
+                // MIR from other crates is already optimized, so it can't depend on ours.
+                if callsite.callee.is_local() &&
+                    self.may_call_caller(callsite.callee, callsite.substs, param_env)
+                {
+                    debug!("checking whether to inline callsite {:?} - may call caller", callsite);
+                    continue;
+                }
+                let callee_mir = self.tcx.optimized_mir(callsite.callee);
 
                 let callee_mir = if self.consider_optimizing(callsite, callee_mir) {
                     self.tcx.subst_and_normalize_erasing_regions(
@@ -142,9 +183,13 @@ impl<'a, 'tcx> Inliner<'a, 'tcx> {
                                                                              bb_data,
                                                                              caller_mir,
                                                                              param_env) {
-                        // Don't inline the same function multiple times.
-                        if callsite.callee != new_callsite.callee {
-                            callsites.push_back(new_callsite);
+                        // Don't inline a function into its own body, even indirectly.
+                        if new_callsite.callee != callsite.callee &&
+                            !history.contains(&new_callsite.callee)
+                        {
+                            let mut new_history = history.clone();
+                            new_history.push(callsite.callee);
+                            callsites.push_back((new_callsite, new_history));
                         }
                     }
                 }
@@ -166,6 +211,57 @@ impl<'a, 'tcx> Inliner<'a, 'tcx> {
         }
     }
 
+    /// Returns whether calling `callee` with `substs` may lead back to the function being
+    /// optimized, following the calls in the MIR of each function reached. Calls through function
+    /// pointers and trait objects aren't followed, as they are never inlined.
+    ///
+    /// Gives up, assuming the worst, once the chain of calls exceeds the recursion limit.
+    fn may_call_caller(&self,
+                       callee: DefId,
+                       substs: &'tcx Substs<'tcx>,
+                       param_env: ParamEnv<'tcx>)
+                       -> bool {
+        let tcx = self.tcx;
+        let caller = self.source.def_id();
+        let recursion_limit = *tcx.sess.recursion_limit.get();
+
+        let mut visited = FxHashSet::default();
+        let mut stack = vec![(callee, substs, 0)];
+        while let Some((def_id, substs, depth)) = stack.pop() {
+            if def_id == caller {
+                return true;
+            }
+            if depth > recursion_limit {
+                debug!("may_call_caller({:?}): recursion limit reached", callee);
+                return true;
+            }
+            if !visited.insert((def_id, substs)) || !tcx.is_mir_available(def_id) {
+                continue;
+            }
+
+            let callees = if def_id.is_local() {
+                tcx.mir_inliner_callees(def_id)
+            } else if substs.types().next().is_some() {
+                Lrc::new(direct_callees(tcx, tcx.optimized_mir(def_id)))
+            } else {
+                // Code from other crates can only call into this crate through its type
+                // parameters.
+                continue;
+            };
+
+            for &(callee_def_id, callee_substs) in callees.iter() {
+                let callee_substs =
+                    tcx.subst_and_normalize_erasing_regions(substs, param_env, &callee_substs);
+                match Instance::resolve(tcx, param_env, callee_def_id, callee_substs) {
+                    Some(Instance { def: InstanceDef::Virtual(..), .. }) | None => {}
+                    Some(instance) => stack.push((instance.def_id(), instance.substs, depth + 1)),
+                }
+            }
+        }
+
+        false
+    }
+
     fn get_valid_function_call(&self,
                                bb: BasicBlock,
                                bb_data: &BasicBlockData<'tcx>,
@@ -242,6 +338,20 @@ impl<'a, 'tcx> Inliner<'a, 'tcx> {
 
         let codegen_fn_attrs = tcx.codegen_fn_attrs(callsite.callee);
 
+        if codegen_fn_attrs.flags.contains(CodegenFnAttrFlags::NAKED) {
+            debug!("    #[naked] present - not inlining");
+            return false;
+        }
+
+        // The callee may rely on target features the caller isn't compiled with.
+        let caller_attrs = tcx.codegen_fn_attrs(self.source.def_id());
+        if !codegen_fn_attrs.target_features.iter()
+            .all(|feature| caller_attrs.target_features.contains(feature))
+        {
+            debug!("    callee has extra target features - not inlining");
+            return false;
+        }
+
         let hinted = match codegen_fn_attrs.inline {
             // Just treat inline(always) as a hint for now,
             // there are cases that prevent inlining that we
@@ -265,11 +375,20 @@ impl<'a, 'tcx> Inliner<'a, 'tcx> {
             }
         }
 
-        let mut threshold = if hinted {
-            HINT_THRESHOLD
+        let opts = &tcx.sess.opts;
+        let threshold_override = if hinted {
+            opts.debugging_opts.inline_mir_hint_threshold
         } else {
-            DEFAULT_THRESHOLD
+            opts.debugging_opts.inline_mir_threshold
         };
+        let mut threshold = threshold_override.unwrap_or_else(|| {
+            let threshold = if hinted { HINT_THRESHOLD } else { DEFAULT_THRESHOLD };
+            // Be more conservative when optimizing for size or only lightly.
+            match opts.optimize {
+                OptLevel::Less | OptLevel::Size | OptLevel::SizeMin => threshold / 2,
+                OptLevel::No | OptLevel::Default | OptLevel::Aggressive => threshold,
+            }
+        });
 
         // Significantly lower the threshold for inlining cold functions
         if codegen_fn_attrs.flags.contains(CodegenFnAttrFlags::COLD) {
@@ -300,10 +419,13 @@ impl<'a, 'tcx> Inliner<'a, 'tcx> {
             let blk = &callee_mir.basic_blocks()[bb];
 
             for stmt in &blk.statements {
-                // Don't count StorageLive/StorageDead in the inlining cost.
+                // Don't count statements that generate no code in the inlining cost.
                 match stmt.kind {
                     StatementKind::StorageLive(_) |
                     StatementKind::StorageDead(_) |
+                    StatementKind::Retag(..) |
+                    StatementKind::FakeRead(..) |
+                    StatementKind::AscribeUserType(..) |
                     StatementKind::Nop => {}
                     _ => cost += INSTR_COST
                 }
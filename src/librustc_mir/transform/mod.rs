@@ -32,6 +32,8 @@ pub mod dump_mir;
 pub mod deaggregator;
 pub mod instcombine;
 pub mod copy_prop;
+pub mod dest_prop;
+pub mod sroa;
 pub mod const_prop;
 pub mod generator;
 pub mod inline;
@@ -41,6 +43,7 @@ pub mod uniform_array_move_out;
 pub(crate) fn provide(providers: &mut Providers<'_>) {
     self::qualify_consts::provide(providers);
     self::check_unsafety::provide(providers);
+    self::inline::provide(providers);
     *providers = Providers {
         mir_keys,
         mir_built,
@@ -239,6 +242,12 @@ fn optimized_mir<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>, def_id: DefId) -> &'tcx
         tcx.ensure().borrowck(def_id);
     }
 
+    // The inliner uses this to look for cycles through us while optimizing other functions,
+    // so compute it before the MIR it reads is gone.
+    if inline::inline_enabled(tcx) {
+        let _ = tcx.mir_inliner_callees(def_id);
+    }
+
     let mut mir = tcx.mir_validated(def_id).steal();
     run_passes(tcx, &mut mir, InstanceDef::Item(def_id), MirPhase::Optimized, &[
         // Remove all things only needed by analysis
@@ -284,7 +293,9 @@ fn optimized_mir<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>, def_id: DefId) -> &'tcx
         &const_prop::ConstProp,
         &simplify_branches::SimplifyBranches::new("after-const-prop"),
         &deaggregator::Deaggregator,
+        &sroa::ScalarReplacementOfAggregates,
         &copy_prop::CopyPropagation,
+        &dest_prop::DestinationPropagation,
         &remove_noop_landing_pads::RemoveNoopLandingPads,
         &simplify::SimplifyCfg::new("final"),
         &simplify::SimplifyLocals,
@@ -78,6 +78,14 @@ impl<T> PerQualif<T> {
     }
 }
 
+impl PerQualif<BitSet<Local>> {
+    fn union(&mut self, other: &Self) {
+        for (this, other) in self.0.iter_mut().zip(other.0.iter()) {
+            this.union(other);
+        }
+    }
+}
+
 impl PerQualif<bool> {
     fn encode_to_bits(self) -> u8 {
         self.0.iter().enumerate().fold(0, |bits, (i, &qualif)| {
@@ -816,59 +824,171 @@ impl<'a, 'tcx> Checker<'a, 'tcx> {
         }
     }
 
-    /// Check a whole const, static initializer or const fn.
-    fn check_const(&mut self) -> (u8, Lrc<BitSet<Local>>) {
-        debug!("const-checking {} {:?}", self.mode, self.def_id);
-
+    /// Checks all blocks of a const, static initializer or const fn that may
+    /// branch (`#![feature(const_if_match)]`) or loop (`#![feature(const_loop)]`),
+    /// instead of the single path through the body `check_const` allows otherwise.
+    fn check_const_control_flow(&mut self) {
         let mir = self.mir;
+        let features = self.tcx.features();
+
+        // Cleanup blocks only run while unwinding, which const evaluation
+        // never does.
+        let blocks: Vec<_> = traversal::reverse_postorder(mir)
+            .map(|(bb, _)| bb)
+            .filter(|&bb| !mir[bb].is_cleanup)
+            .collect();
+        let mut rpo_index = IndexVec::from_elem(usize::MAX, mir.basic_blocks());
+        for (i, &bb) in blocks.iter().enumerate() {
+            rpo_index[bb] = i;
+        }
+        let has_loops = blocks.iter().any(|&bb| {
+            mir[bb].terminator().successors().any(|&target| rpo_index[target] <= rpo_index[bb])
+        });
 
-        let mut seen_blocks = BitSet::new_empty(mir.basic_blocks().len());
-        let mut bb = START_BLOCK;
-        loop {
-            seen_blocks.insert(bb.index());
-
-            self.visit_basic_block_data(bb, &mir[bb]);
-
-            let target = match mir[bb].terminator().kind {
-                TerminatorKind::Goto { target } |
-                TerminatorKind::Drop { target, .. } |
-                TerminatorKind::Assert { target, .. } |
-                TerminatorKind::Call { destination: Some((_, target)), .. } => {
-                    Some(target)
+        for &bb in &blocks {
+            let terminator = mir[bb].terminator();
+            let span = terminator.source_info.span;
+            match terminator.kind {
+                TerminatorKind::SwitchInt { .. } |
+                TerminatorKind::FalseEdges { .. } if !features.const_if_match => {
+                    emit_feature_err(
+                        &self.tcx.sess.parse_sess, "const_if_match",
+                        span, GateIssue::Language,
+                        &format!("`if`, `match`, `&&` and `||` in {}s are unstable", self.mode),
+                    );
                 }
-
-                // Non-terminating calls cannot produce any value.
-                TerminatorKind::Call { destination: None, .. } => {
-                    break;
+                TerminatorKind::FalseUnwind { .. } if !features.const_loop => {
+                    emit_feature_err(
+                        &self.tcx.sess.parse_sess, "const_loop",
+                        span, GateIssue::Language,
+                        &format!("loops in {}s are unstable", self.mode),
+                    );
                 }
-
-                TerminatorKind::SwitchInt {..} |
                 TerminatorKind::DropAndReplace { .. } |
-                TerminatorKind::Resume |
                 TerminatorKind::Abort |
                 TerminatorKind::GeneratorDrop |
-                TerminatorKind::Yield { .. } |
-                TerminatorKind::Unreachable |
-                TerminatorKind::FalseEdges { .. } |
-                TerminatorKind::FalseUnwind { .. } => None,
+                TerminatorKind::Yield { .. } => {
+                    self.span = span;
+                    self.not_const();
+                }
+                _ => {}
+            }
+        }
 
-                TerminatorKind::Return => {
-                    break;
+        // Each block starts with the union of the qualifications its
+        // predecessors end with, so a move which clears `NeedsDrop` on one
+        // path does not hide a value that still needs dropping on another.
+        // Without loops, one visit in reverse postorder sees every predecessor
+        // of a block before the block itself. With loops, visit the body again
+        // until the states at the end of the blocks stop changing; they only
+        // grow, because each block only adds and removes the same bits every
+        // time. The diagnostics that the repeated visits emit again are
+        // deduplicated by the `Handler`.
+        let predecessors = mir.predecessors();
+        let entry = self.cx.per_local.clone();
+        let mut block_exits: IndexVec<BasicBlock, Option<PerQualif<BitSet<Local>>>> =
+            IndexVec::from_elem(None, mir.basic_blocks());
+        loop {
+            let mut changed = false;
+            self.promotion_candidates.clear();
+            for &bb in &blocks {
+                let mut state = if bb == START_BLOCK {
+                    entry.clone()
+                } else {
+                    PerQualif::new(BitSet::new_empty(mir.local_decls.len()))
+                };
+                for &pred in &predecessors[bb] {
+                    if let Some(ref exit) = block_exits[pred] {
+                        state.union(exit);
+                    }
                 }
-            };
+                self.cx.per_local = state;
 
-            match target {
-                // No loops allowed.
-                Some(target) if !seen_blocks.contains(target.index()) => {
-                    bb = target;
+                self.visit_basic_block_data(bb, &mir[bb]);
+
+                let exit_changed = match block_exits[bb] {
+                    Some(ref exit) => exit.0 != self.cx.per_local.0,
+                    None => true,
+                };
+                if exit_changed {
+                    block_exits[bb] = Some(self.cx.per_local.clone());
+                    changed = true;
                 }
-                _ => {
-                    self.not_const();
-                    break;
+            }
+            if !has_loops || !changed {
+                break;
+            }
+        }
+
+        // The qualifications of the return place are those of all the paths
+        // which return.
+        let mut returned = PerQualif::new(BitSet::new_empty(mir.local_decls.len()));
+        for &bb in &blocks {
+            if let TerminatorKind::Return = mir[bb].terminator().kind {
+                if let Some(ref exit) = block_exits[bb] {
+                    returned.union(exit);
                 }
             }
         }
+        self.cx.per_local = returned;
+    }
+
+    /// Check a whole const, static initializer or const fn.
+    fn check_const(&mut self) -> (u8, Lrc<BitSet<Local>>) {
+        debug!("const-checking {} {:?}", self.mode, self.def_id);
+
+        let mir = self.mir;
+
+        if self.tcx.features().const_if_match || self.tcx.features().const_loop {
+            self.check_const_control_flow();
+        } else {
+            let mut seen_blocks = BitSet::new_empty(mir.basic_blocks().len());
+            let mut bb = START_BLOCK;
+            loop {
+                seen_blocks.insert(bb.index());
+
+                self.visit_basic_block_data(bb, &mir[bb]);
+
+                let target = match mir[bb].terminator().kind {
+                    TerminatorKind::Goto { target } |
+                    TerminatorKind::Drop { target, .. } |
+                    TerminatorKind::Assert { target, .. } |
+                    TerminatorKind::Call { destination: Some((_, target)), .. } => {
+                        Some(target)
+                    }
 
+                    // Non-terminating calls cannot produce any value.
+                    TerminatorKind::Call { destination: None, .. } => {
+                        break;
+                    }
+
+                    TerminatorKind::SwitchInt {..} |
+                    TerminatorKind::DropAndReplace { .. } |
+                    TerminatorKind::Resume |
+                    TerminatorKind::Abort |
+                    TerminatorKind::GeneratorDrop |
+                    TerminatorKind::Yield { .. } |
+                    TerminatorKind::Unreachable |
+                    TerminatorKind::FalseEdges { .. } |
+                    TerminatorKind::FalseUnwind { .. } => None,
+
+                    TerminatorKind::Return => {
+                        break;
+                    }
+                };
+
+                match target {
+                    // No loops allowed.
+                    Some(target) if !seen_blocks.contains(target.index()) => {
+                        bb = target;
+                    }
+                    _ => {
+                        self.not_const();
+                        break;
+                    }
+                }
+            }
+        }
 
         // Collect all the temps we need to promote.
         let mut promoted_temps = BitSet::new_empty(self.temp_promotion_state.len());
@@ -965,11 +1085,18 @@ impl<'a, 'tcx> Visitor<'tcx> for Checker<'a, 'tcx> {
             Place::Projection(ref proj) => {
                 match proj.elem {
                     ProjectionElem::Deref => {
+                        let base_ty = proj.base.ty(self.mir, self.tcx).to_ty(self.tcx);
                         if context.is_mutating_use() {
-                            // `not_const` errors out in const contexts
-                            self.not_const()
+                            // Writing through a raw pointer is how memory from
+                            // `const_allocate` gets filled; the interpreter rejects
+                            // such writes to any other memory.
+                            let is_heap_write = self.tcx.features().const_heap &&
+                                base_ty.is_unsafe_ptr();
+                            if !is_heap_write {
+                                // `not_const` errors out in const contexts
+                                self.not_const()
+                            }
                         }
-                        let base_ty = proj.base.ty(self.mir, self.tcx).to_ty(self.tcx);
                         match self.mode {
                             Mode::Fn => {},
                             _ => {
@@ -1018,7 +1145,9 @@ impl<'a, 'tcx> Visitor<'tcx> for Checker<'a, 'tcx> {
                     }
 
                     ProjectionElem::Downcast(..) => {
-                        self.not_const()
+                        if !self.tcx.features().const_if_match {
+                            self.not_const()
+                        }
                     }
                 }
             }
@@ -1208,6 +1337,16 @@ impl<'a, 'tcx> Visitor<'tcx> for Checker<'a, 'tcx> {
                                     is_shuffle = true;
                                 }
 
+                                "const_allocate" | "const_deallocate" | "const_make_global" => {
+                                    if self.mode != Mode::Fn && !self.tcx.features().const_heap {
+                                        emit_feature_err(
+                                            &self.tcx.sess.parse_sess, "const_heap",
+                                            self.span, GateIssue::Language,
+                                            &format!("heap allocations in {}s are unstable",
+                                                     self.mode));
+                                    }
+                                }
+
                                 // no need to check feature gates, intrinsics are only callable
                                 // from the libstd or with forever unstable feature gates
                                 _ => {}
@@ -262,6 +262,9 @@ fn check_place(
                 | ProjectionElem::Deref | ProjectionElem::Field(..) | ProjectionElem::Index(_) => {
                     check_place(tcx, mir, &proj.base, span)
                 }
+                | ProjectionElem::Downcast(..) if tcx.features().const_if_match => {
+                    check_place(tcx, mir, &proj.base, span)
+                }
                 | ProjectionElem::Downcast(..) => {
                     Err((span, "`match` or `if let` in `const fn` is unstable".into()))
                 }
@@ -289,10 +292,17 @@ fn check_terminator(
             check_operand(tcx, mir, value, span)
         },
 
+        TerminatorKind::FalseEdges { .. } if tcx.features().const_if_match => Ok(()),
+        TerminatorKind::SwitchInt { discr, .. } if tcx.features().const_if_match => {
+            check_operand(tcx, mir, discr, span)
+        }
         TerminatorKind::FalseEdges { .. } | TerminatorKind::SwitchInt { .. } => Err((
             span,
             "`if`, `match`, `&&` and `||` are not stable in const fn".into(),
         )),
+        // Exhaustive `match`es end in an `Unreachable` fallback block, which
+        // the interpreter reports as an error should it ever get there.
+        TerminatorKind::Unreachable if tcx.features().const_if_match => Ok(()),
         | TerminatorKind::Abort | TerminatorKind::Unreachable => {
             Err((span, "const fn with unreachable code is not stable".into()))
         }
@@ -352,6 +362,7 @@ fn check_terminator(
             cleanup: _,
         } => check_operand(tcx, mir, cond, span),
 
+        TerminatorKind::FalseUnwind { .. } if tcx.features().const_loop => Ok(()),
         TerminatorKind::FalseUnwind { .. } => {
             Err((span, "loops are not allowed in const fn".into()))
         },
@@ -386,6 +397,11 @@ fn is_intrinsic_whitelisted(tcx: TyCtxt<'a, 'tcx, 'tcx>, def_id: DefId) -> bool
         | "bswap" // ~> .swap_bytes
         | "bitreverse" // ~> .reverse_bits
         => true,
+        // Only reachable through `core::intrinsics`, and still behind a feature gate:
+        | "const_allocate"
+        | "const_deallocate"
+        | "const_make_global"
+        => tcx.features().const_heap,
         _ => false,
     }
 }
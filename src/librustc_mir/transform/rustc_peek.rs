@@ -3,7 +3,7 @@ use syntax::ast;
 use syntax_pos::Span;
 
 use rustc::ty::{self, TyCtxt};
-use rustc::mir::{self, Mir, Location};
+use rustc::mir::{self, Local, Mir, Location};
 use rustc_data_structures::bit_set::BitSet;
 use crate::transform::{MirPass, MirSource};
 
@@ -16,7 +16,9 @@ use crate::dataflow::{
 };
 use crate::dataflow::move_paths::{MovePathIndex, LookupResult};
 use crate::dataflow::move_paths::{HasMoveData, MoveData};
+use crate::dataflow::generic::{Analysis, Engine, Results};
 use crate::dataflow;
+use crate::util::liveness::{IdentityMap, LiveLocals};
 
 use crate::dataflow::has_rustc_mir_with;
 
@@ -61,6 +63,11 @@ impl MirPass for SanityCheck {
         if has_rustc_mir_with(&attributes, "rustc_peek_definite_init").is_some() {
             sanity_check_via_rustc_peek(tcx, mir, id, &attributes, &flow_def_inits);
         }
+        if has_rustc_mir_with(&attributes, "rustc_peek_liveness").is_some() {
+            let map = IdentityMap::new(mir);
+            let results = Engine::new_gen_kill(mir, LiveLocals::new(&map)).iterate_to_fixpoint();
+            sanity_check_liveness_via_rustc_peek(tcx, mir, &results);
+        }
         if has_rustc_mir_with(&attributes, "stop_after_dataflow").is_some() {
             tcx.sess.fatal("stop_after_dataflow ended compilation");
         }
@@ -213,6 +220,63 @@ fn each_block<'a, 'tcx, O>(tcx: TyCtxt<'a, 'tcx, 'tcx>,
                                       form `&expr`"));
 }
 
+/// Like `sanity_check_via_rustc_peek`, but for the liveness of locals, which is computed by a
+/// backward analysis of the generic dataflow framework. A call `rustc_peek(&x)` checks whether
+/// `x` is live right after it is borrowed, not counting the borrow itself.
+fn sanity_check_liveness_via_rustc_peek<'a, 'tcx, A>(tcx: TyCtxt<'a, 'tcx, 'tcx>,
+                                                     mir: &Mir<'tcx>,
+                                                     results: &Results<'tcx, A>)
+    where A: Analysis<'tcx, Domain = BitSet<Local>>
+{
+    let mut cursor = results.cursor(mir);
+
+    for (bb, data) in mir.basic_blocks().iter_enumerated() {
+        let (args, span) = match is_rustc_peek(tcx, &data.terminator) {
+            Some(args_and_span) => args_and_span,
+            None => continue,
+        };
+        assert!(args.len() == 1);
+        let peek_arg_place = match args[0] {
+            mir::Operand::Copy(ref place @ mir::Place::Local(_)) |
+            mir::Operand::Move(ref place @ mir::Place::Local(_)) => place,
+            _ => {
+                tcx.sess.diagnostic().span_err(
+                    span, "dataflow::sanity_check cannot feed a non-temp to rustc_peek.");
+                continue;
+            }
+        };
+
+        let borrow = data.statements.iter().enumerate().rev().find_map(|(j, stmt)| {
+            match stmt.kind {
+                mir::StatementKind::Assign(ref place, ref rvalue) if place == peek_arg_place => {
+                    Some((j, rvalue))
+                }
+                _ => None,
+            }
+        });
+
+        match borrow.map(|(j, rvalue)| (j, &**rvalue)) {
+            Some((j, &mir::Rvalue::Ref(_, mir::BorrowKind::Shared, mir::Place::Local(local)))) => {
+                // The analysis runs backward, so the state before the effect of the borrow is
+                // the state right after it in program order.
+                cursor.seek_before(Location { block: bb, statement_index: j });
+                let bit_state = cursor.get().contains(local);
+                debug!("rustc_peek(&{:?}) bit_state: {}", local, bit_state);
+                if !bit_state {
+                    tcx.sess.span_err(span, "rustc_peek: bit not set");
+                }
+            }
+            Some((_, &mir::Rvalue::Ref(_, mir::BorrowKind::Shared, _))) => {
+                tcx.sess.span_err(span, "rustc_peek: argument untracked");
+            }
+            _ => {
+                tcx.sess.span_err(span, "rustc_peek: argument expression \
+                                         must be immediate borrow of form `&expr`");
+            }
+        }
+    }
+}
+
 fn is_rustc_peek<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>,
                            terminator: &'a Option<mir::Terminator<'tcx>>)
                            -> Option<(&'a [mir::Operand<'tcx>], Span)> {
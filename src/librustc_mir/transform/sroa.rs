@@ -0,0 +1,194 @@
+//! Scalar replacement of aggregates.
+//!
+//! This pass splits locals of tuple and struct type into one local per field, as long as the
+//! aggregate itself is never used as a whole. After the deaggregator has run, a pair that is only
+//! built and taken apart again looks like:
+//!
+//!     (_2.0: u32) = _1
+//!     (_2.1: u32) = _3
+//!     ...
+//!     _4 = Add(move (_2.0: u32), move (_2.1: u32))
+//!
+//! and is turned into:
+//!
+//!     _5 = _1
+//!     _6 = _3
+//!     ...
+//!     _4 = Add(move _5, move _6)
+//!
+//! which later passes (copy propagation, and LLVM's own SROA) handle much better, since they no
+//! longer have to see through the aggregate.
+//!
+//! A local is only split if every mention of it, other than `StorageLive` and `StorageDead`, is a
+//! field projection applied directly to it. Any use of the whole local (a move, a copy, a borrow,
+//! a drop, ...) keeps the local as it is, and so does any other projection. So does borrowing a
+//! field, even a nested one: unsafe code may use the reference to reach the other fields, which
+//! would no longer be next to it once split. Arguments and the return place are never split,
+//! since their layout is fixed by the calling convention.
+
+use rustc::mir::{Field, Local, LocalDecl, LocalKind, Location, Mir, Place, ProjectionElem};
+use rustc::mir::{Statement, StatementKind};
+use rustc::mir::visit::{MutVisitor, NonUseContext, PlaceContext, Visitor};
+use rustc::ty::{self, Ty, TyCtxt};
+use rustc_data_structures::fx::FxHashMap;
+use rustc_data_structures::indexed_vec::IndexVec;
+use crate::transform::{MirPass, MirSource};
+
+pub struct ScalarReplacementOfAggregates;
+
+impl MirPass for ScalarReplacementOfAggregates {
+    fn run_pass<'a, 'tcx>(&self,
+                          tcx: TyCtxt<'a, 'tcx, 'tcx>,
+                          _source: MirSource<'tcx>,
+                          mir: &mut Mir<'tcx>) {
+        // We only run when the MIR optimization level is > 1.
+        // Split user variables can no longer be inspected in a debugger.
+        if tcx.sess.opts.debugging_opts.mir_opt_level <= 1 {
+            return;
+        }
+
+        let candidates = find_candidates(mir);
+        if candidates.iter().all(|fields| fields.is_none()) {
+            return;
+        }
+
+        // Give each field that is actually used a local of its own. Fields that are never
+        // mentioned don't need one.
+        let mut replacements: IndexVec<Local, Option<FxHashMap<Field, Local>>> =
+            IndexVec::from_elem(None, &mir.local_decls);
+        for (local, fields) in candidates.into_iter_enumerated() {
+            let fields = match fields {
+                Some(fields) => fields,
+                None => continue,
+            };
+            let span = mir.local_decls[local].source_info.span;
+            let mut new_locals = FxHashMap::default();
+            // Sort the fields so the numbering of the new locals is deterministic.
+            let mut fields: Vec<_> = fields.into_iter().collect();
+            fields.sort_by_key(|&(field, _)| field);
+            for (field, ty) in fields {
+                let new_local = mir.local_decls.push(LocalDecl::new_temp(ty, span));
+                debug!("sroa: replacing {:?}.{:?} with {:?}", local, field, new_local);
+                new_locals.insert(field, new_local);
+            }
+            replacements[local] = Some(new_locals);
+        }
+
+        ReplaceFields { replacements: &replacements }.visit_mir(mir);
+
+        // The storage of the aggregate becomes the storage of each of its fields.
+        for data in mir.basic_blocks_mut() {
+            data.expand_statements(|stmt| {
+                let (local, is_live) = match stmt.kind {
+                    StatementKind::StorageLive(local) => (local, true),
+                    StatementKind::StorageDead(local) => (local, false),
+                    _ => return None,
+                };
+                let new_locals = replacements[local].as_ref()?;
+                let source_info = stmt.source_info;
+                let mut new_locals: Vec<_> = new_locals.values().cloned().collect();
+                new_locals.sort();
+                Some(new_locals.into_iter().map(move |new_local| Statement {
+                    source_info,
+                    kind: if is_live {
+                        StatementKind::StorageLive(new_local)
+                    } else {
+                        StatementKind::StorageDead(new_local)
+                    },
+                }).collect::<Vec<_>>().into_iter())
+            });
+        }
+
+        // The original locals are now unused and get removed by `SimplifyLocals`.
+    }
+}
+
+/// Returns, for each local that can be split, the fields it is accessed through along with their
+/// types.
+fn find_candidates<'tcx>(mir: &Mir<'tcx>) -> IndexVec<Local, Option<FxHashMap<Field, Ty<'tcx>>>> {
+    let mut candidates: IndexVec<Local, _> = mir.local_decls.iter_enumerated().map(|(local, decl)| {
+        match mir.local_kind(local) {
+            LocalKind::Var | LocalKind::Temp => {}
+            LocalKind::Arg | LocalKind::ReturnPointer => return None,
+        }
+        match decl.ty.sty {
+            ty::Tuple(..) => Some(FxHashMap::default()),
+            ty::Adt(adt_def, _) if adt_def.is_struct() && !adt_def.repr.simd() => {
+                Some(FxHashMap::default())
+            }
+            _ => None,
+        }
+    }).collect();
+
+    FindCandidates { candidates: &mut candidates }.visit_mir(mir);
+    candidates
+}
+
+struct FindCandidates<'a, 'tcx: 'a> {
+    candidates: &'a mut IndexVec<Local, Option<FxHashMap<Field, Ty<'tcx>>>>,
+}
+
+impl<'a, 'tcx> Visitor<'tcx> for FindCandidates<'a, 'tcx> {
+    fn visit_place(&mut self,
+                   place: &Place<'tcx>,
+                   context: PlaceContext<'tcx>,
+                   location: Location) {
+        if context.is_borrow() {
+            // Keep the whole aggregate if any part of it is borrowed.
+            if let Some(local) = place.base_local() {
+                self.candidates[local] = None;
+            }
+        }
+        if let Place::Projection(ref proj) = *place {
+            if let (&Place::Local(local), &ProjectionElem::Field(field, ty)) =
+                (&proj.base, &proj.elem)
+            {
+                // A field can become a local of its own, unless it is borrowed.
+                if let Some(ref mut fields) = self.candidates[local] {
+                    fields.insert(field, ty);
+                }
+                return;
+            }
+        }
+        self.super_place(place, context, location);
+    }
+
+    fn visit_local(&mut self,
+                   &local: &Local,
+                   context: PlaceContext<'tcx>,
+                   _location: Location) {
+        match context {
+            PlaceContext::NonUse(NonUseContext::StorageLive) |
+            PlaceContext::NonUse(NonUseContext::StorageDead) => {}
+            _ => {
+                // The aggregate is used as a whole, or through something other than a field.
+                self.candidates[local] = None;
+            }
+        }
+    }
+}
+
+struct ReplaceFields<'a> {
+    replacements: &'a IndexVec<Local, Option<FxHashMap<Field, Local>>>,
+}
+
+impl<'a, 'tcx> MutVisitor<'tcx> for ReplaceFields<'a> {
+    fn visit_place(&mut self,
+                   place: &mut Place<'tcx>,
+                   context: PlaceContext<'tcx>,
+                   location: Location) {
+        let new_local = match *place {
+            Place::Projection(ref proj) => match (&proj.base, &proj.elem) {
+                (&Place::Local(local), &ProjectionElem::Field(field, _)) => {
+                    self.replacements[local].as_ref().map(|fields| fields[&field])
+                }
+                _ => None,
+            },
+            _ => None,
+        };
+        match new_local {
+            Some(new_local) => *place = Place::Local(new_local),
+            None => self.super_place(place, context, location),
+        }
+    }
+}
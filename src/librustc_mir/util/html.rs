@@ -0,0 +1,543 @@
+//! Self-contained HTML output for `-Z dump-mir-html`.
+//!
+//! Every MIR body selected by `-Z dump-mir` gets a single `.html` file in the dump
+//! directory, with one section for each pass it was dumped at. The page shows two of
+//! these passes side by side, either as they are or as a line diff, together with
+//! their control flow graphs. Hovering over a local or a basic block highlights all of
+//! its occurrences, and hovering over a statement highlights the source it was lowered
+//! from. Styles and scripts are inlined, so the file can be viewed without network
+//! access.
+
+use rustc::mir::*;
+use rustc::ty::{item_path, TyCtxt};
+use rustc_data_structures::indexed_vec::{Idx, IndexVec};
+use std::collections::VecDeque;
+use std::fmt::{Display, Write as _};
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use rustc::session::Session;
+use super::pretty::{dump_file_stem, write_mir_fn, PassWhere};
+use crate::transform::MirSource;
+
+/// Written into the textual MIR on a line of its own in front of the lines that
+/// belong to a statement or terminator (followed by its location), and after them.
+const LOCATION_MARKER: char = '\u{1}';
+
+/// Adds the MIR of `source` as it is after the given pass to the HTML file of
+/// `source`. The sections are appended to a `.part` file, which
+/// `finish_mir_html_dumps` turns into the complete page.
+pub fn write_mir_fn_html<'a, 'gcx, 'tcx>(
+    tcx: TyCtxt<'a, 'gcx, 'tcx>,
+    pass_num: Option<&dyn Display>,
+    pass_name: &str,
+    disambiguator: &dyn Display,
+    source: MirSource<'tcx>,
+    mir: &Mir<'tcx>,
+) -> io::Result<()> {
+    let title = match pass_num {
+        Some(pass_num) => format!("{} {} ({})", pass_num, pass_name, disambiguator),
+        None => format!("{} ({})", pass_name, disambiguator),
+    };
+    let section = pass_section(tcx, &title, source, mir)?;
+
+    let mut path = PathBuf::from(&tcx.sess.opts.debugging_opts.dump_mir_dir);
+    path.push(format!("{}.html", dump_file_stem(tcx, source)));
+
+    let part_path = part_path(&path);
+    let mut file = if tcx.sess.mir_html_dumps.lock().insert(path.clone()) {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let mut file = io::BufWriter::new(fs::File::create(&part_path)?);
+        write_document_head(tcx, source, mir, &mut file)?;
+        file
+    } else {
+        io::BufWriter::new(fs::OpenOptions::new().append(true).open(&part_path)?)
+    };
+    writeln!(file, "{}", section)?;
+    file.flush()
+}
+
+/// Completes the files `write_mir_fn_html` has started, once no more passes are
+/// dumped, so that every `.html` file is written exactly once.
+pub fn finish_mir_html_dumps(sess: &Session) -> io::Result<()> {
+    let paths: Vec<_> = sess.mir_html_dumps.lock().drain().collect();
+    for path in paths {
+        let part_path = part_path(&path);
+        let mut file = io::BufWriter::new(fs::OpenOptions::new().append(true).open(&part_path)?);
+        write_document_tail(&mut file)?;
+        file.flush()?;
+        drop(file);
+        fs::rename(&part_path, &path)?;
+    }
+    Ok(())
+}
+
+fn part_path(path: &Path) -> PathBuf {
+    let mut part_path = path.as_os_str().to_owned();
+    part_path.push(".part");
+    PathBuf::from(part_path)
+}
+
+/// Writes everything in front of the sections of the passes.
+fn write_document_head<'a, 'gcx, 'tcx>(
+    tcx: TyCtxt<'a, 'gcx, 'tcx>,
+    source: MirSource<'tcx>,
+    mir: &Mir<'tcx>,
+    w: &mut dyn Write,
+) -> io::Result<()> {
+    let node_path = item_path::with_forced_impl_filename_line(|| {
+        // see notes on #41697 in `pretty.rs`
+        tcx.item_path_str(source.def_id())
+    });
+    let title = match source.promoted {
+        Some(promoted) => format!("{}::{:?}", node_path, promoted),
+        None => node_path,
+    };
+
+    writeln!(w, "<!DOCTYPE html>")?;
+    writeln!(w, "<html>")?;
+    writeln!(w, "<head>")?;
+    writeln!(w, "<meta charset=\"utf-8\">")?;
+    writeln!(w, "<title>MIR for {}</title>", escape(&title))?;
+    writeln!(w, "<style>{}</style>", STYLE)?;
+    writeln!(w, "</head>")?;
+    writeln!(w, "<body>")?;
+    writeln!(w, "<h1>MIR for <code>{}</code></h1>", escape(&title))?;
+    writeln!(w, "<div id=\"controls\">")?;
+    writeln!(w, "<select id=\"left\"></select>")?;
+    writeln!(w, "<select id=\"right\"></select>")?;
+    writeln!(w, "<label><input type=\"checkbox\" id=\"diff\"> show changes</label>")?;
+    writeln!(w, "</div>")?;
+    writeln!(w, "<div id=\"panes\">")?;
+    writeln!(w, "<div class=\"pane\" id=\"left-pane\"></div>")?;
+    writeln!(w, "<div class=\"pane\" id=\"right-pane\"></div>")?;
+    writeln!(w, "</div>")?;
+
+    writeln!(w, "<h2>Source</h2>")?;
+    write!(w, "<pre id=\"source\">")?;
+    let source_map = tcx.sess.source_map();
+    let lo = source_map.lookup_char_pos(mir.span.lo());
+    let hi = source_map.lookup_char_pos(mir.span.hi());
+    for line in lo.line..=hi.line {
+        if let Some(text) = lo.file.get_line(line - 1) {
+            write!(
+                w,
+                "<span class=\"src\" data-line=\"{0}\">\
+                 <span class=\"num\">{0:>5}</span> {1}</span>",
+                line,
+                escape(&text),
+            )?;
+        }
+    }
+    writeln!(w, "</pre>")?;
+
+    writeln!(w, "<div id=\"passes\" hidden>")
+}
+
+/// Writes everything after the sections of the passes.
+fn write_document_tail(w: &mut dyn Write) -> io::Result<()> {
+    writeln!(w, "</div>")?;
+
+    // Referenced by the edges of all graphs.
+    writeln!(w, "<svg width=\"0\" height=\"0\"><defs>\
+                 <marker id=\"arrow\" viewBox=\"0 0 10 10\" refX=\"10\" refY=\"5\" \
+                 markerWidth=\"6\" markerHeight=\"6\" orient=\"auto\">\
+                 <path d=\"M 0 0 L 10 5 L 0 10 z\"/></marker></defs></svg>")?;
+    writeln!(w, "<script>{}</script>", SCRIPT)?;
+    writeln!(w, "</body>")?;
+    writeln!(w, "</html>")
+}
+
+/// Renders the MIR and the CFG of `mir` as a `<section>` of the document.
+fn pass_section<'a, 'gcx, 'tcx>(
+    tcx: TyCtxt<'a, 'gcx, 'tcx>,
+    title: &str,
+    source: MirSource<'tcx>,
+    mir: &Mir<'tcx>,
+) -> io::Result<String> {
+    let mut text = Vec::new();
+    write_mir_fn(tcx, source, mir, &mut |pass_where, w: &mut dyn Write| {
+        match pass_where {
+            PassWhere::BeforeLocation(location) => writeln!(
+                w,
+                "{}{}:{}",
+                LOCATION_MARKER,
+                location.block.index(),
+                location.statement_index,
+            ),
+            PassWhere::AfterLocation(_) => writeln!(w, "{}", LOCATION_MARKER),
+            _ => Ok(()),
+        }
+    }, &mut text)?;
+    let text = String::from_utf8_lossy(&text);
+
+    let source_map = tcx.sess.source_map();
+    let fn_file = source_map.lookup_char_pos(mir.span.lo()).file;
+
+    let mut html = String::new();
+    let _ = write!(html, "<section data-title=\"{}\"><pre class=\"mir\">", escape(title));
+    // The source lines of the statement or terminator being printed.
+    let mut lines = None;
+    for line in text.lines() {
+        if line.starts_with(LOCATION_MARKER) {
+            let location = &line[LOCATION_MARKER.len_utf8()..];
+            lines = parse_location(location).and_then(|location| {
+                let span = mir.source_info(location).span;
+                let lo = source_map.lookup_char_pos(span.lo());
+                let hi = source_map.lookup_char_pos(span.hi());
+                if lo.file.name == fn_file.name {
+                    Some((lo.line, hi.line))
+                } else {
+                    None
+                }
+            });
+            continue;
+        }
+
+        html.push_str("<span class=\"line\"");
+        if let Some((lo, hi)) = lines {
+            let _ = write!(html, " data-lo=\"{}\" data-hi=\"{}\"", lo, hi);
+        }
+        if let Some(block) = block_header(line) {
+            let _ = write!(html, " data-block=\"{}\"", block);
+        }
+        html.push('>');
+        html.push_str(&mark_up(line));
+        // Lines are displayed as blocks, so they need no newline between them.
+        html.push_str("</span>");
+    }
+    html.push_str("</pre>");
+    html.push_str(&cfg_svg(mir));
+    html.push_str("</section>");
+    Ok(html)
+}
+
+/// Parses the `<block>:<statement>` written after a `LOCATION_MARKER`.
+fn parse_location(location: &str) -> Option<Location> {
+    let mut parts = location.split(':');
+    let block = parts.next()?.parse().ok()?;
+    let statement_index = parts.next()?.parse().ok()?;
+    Some(Location { block: BasicBlock::new(block), statement_index })
+}
+
+/// Returns the name of the basic block if `line` is the one that opens it, as
+/// in `bb1: {` or `bb1 (cleanup): {`.
+fn block_header(line: &str) -> Option<&str> {
+    let line = line.trim_start();
+    let len = match token_at(line) {
+        Some(("block", len)) => len,
+        _ => return None,
+    };
+    let rest = &line[len..];
+    let rest = if rest.starts_with(" (cleanup)") { &rest[" (cleanup)".len()..] } else { rest };
+    if rest.starts_with(": {") {
+        Some(&line[..len])
+    } else {
+        None
+    }
+}
+
+/// Escapes `line` for HTML, and wraps the locals (`_1`) and basic blocks (`bb1`)
+/// in it into elements the script can highlight.
+fn mark_up(line: &str) -> String {
+    let mut html = String::with_capacity(line.len());
+    let mut rest = line;
+    let mut previous = None;
+    while let Some(c) = rest.chars().next() {
+        let starts_word = previous.map_or(true, |p: char| !p.is_alphanumeric() && p != '_');
+        if let (true, Some((class, len))) = (starts_word, token_at(rest)) {
+            let token = &rest[..len];
+            let _ = write!(html, "<span class=\"{0}\" data-name=\"{1}\">{1}</span>", class, token);
+            previous = token.chars().last();
+            rest = &rest[len..];
+            continue;
+        }
+        match c {
+            '&' => html.push_str("&amp;"),
+            '<' => html.push_str("&lt;"),
+            '>' => html.push_str("&gt;"),
+            '"' => html.push_str("&quot;"),
+            c => html.push(c),
+        }
+        previous = Some(c);
+        rest = &rest[c.len_utf8()..];
+    }
+    html
+}
+
+/// Returns the kind and length of the local or basic block name `s` starts with.
+fn token_at(s: &str) -> Option<(&'static str, usize)> {
+    let (class, prefix) = if s.starts_with('_') {
+        ("local", 1)
+    } else if s.starts_with("bb") {
+        ("block", 2)
+    } else {
+        return None;
+    };
+    let digits = s[prefix..].bytes().take_while(|b| b.is_ascii_digit()).count();
+    if digits == 0 {
+        return None;
+    }
+    let len = prefix + digits;
+    match s[len..].chars().next() {
+        Some(c) if c.is_alphanumeric() || c == '_' => None,
+        _ => Some((class, len)),
+    }
+}
+
+fn escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Draws the control flow graph of `mir` as an SVG. Blocks are put in rows by
+/// their distance from the start block, and edges that do not go down a row
+/// are drawn as dashed curves.
+fn cfg_svg(mir: &Mir<'_>) -> String {
+    const WIDTH: usize = 80;
+    const HEIGHT: usize = 26;
+    const H_GAP: usize = 24;
+    const V_GAP: usize = 34;
+
+    let blocks = mir.basic_blocks();
+    let mut depth = IndexVec::from_elem(None, blocks);
+    let mut queue = VecDeque::new();
+    depth[START_BLOCK] = Some(0);
+    queue.push_back(START_BLOCK);
+    while let Some(block) = queue.pop_front() {
+        let next = depth[block].unwrap() + 1;
+        for &successor in blocks[block].terminator().successors() {
+            if depth[successor].is_none() {
+                depth[successor] = Some(next);
+                queue.push_back(successor);
+            }
+        }
+    }
+
+    // Unreachable blocks go in a row of their own at the bottom.
+    let unreachable_row = depth.iter().filter_map(|&d| d).max().unwrap_or(0) + 1;
+    let mut rows = vec![vec![]; unreachable_row + 1];
+    for (block, &d) in depth.iter_enumerated() {
+        rows[d.unwrap_or(unreachable_row)].push(block);
+    }
+    rows.retain(|row| !row.is_empty());
+
+    let mut position = IndexVec::from_elem((0, 0), blocks);
+    for (y, row) in rows.iter().enumerate() {
+        for (x, &block) in row.iter().enumerate() {
+            position[block] = (H_GAP + x * (WIDTH + H_GAP), V_GAP / 2 + y * (HEIGHT + V_GAP));
+        }
+    }
+    let width = rows.iter().map(|row| row.len()).max().unwrap_or(0) * (WIDTH + H_GAP) + H_GAP;
+    let height = rows.len() * (HEIGHT + V_GAP);
+
+    let mut svg = String::new();
+    let _ = write!(svg, "<svg class=\"cfg\" width=\"{}\" height=\"{}\">", width, height);
+    for (block, data) in blocks.iter_enumerated() {
+        let (x, y) = position[block];
+        for &successor in data.terminator().successors() {
+            let (to_x, to_y) = position[successor];
+            let (x1, y1) = (x + WIDTH / 2, y + HEIGHT);
+            let (x2, y2) = (to_x + WIDTH / 2, to_y);
+            if to_y > y {
+                let _ = write!(svg, "<line class=\"edge\" x1=\"{}\" y1=\"{}\" x2=\"{}\" y2=\"{}\" \
+                                     marker-end=\"url(#arrow)\"/>", x1, y1, x2, y2);
+            } else {
+                let _ = write!(svg, "<path class=\"edge back\" d=\"M {} {} C {} {} {} {} {} {}\" \
+                                     marker-end=\"url(#arrow)\"/>",
+                               x1, y1,
+                               x1 + WIDTH, y1 + V_GAP,
+                               x2 + WIDTH, y2 - V_GAP,
+                               x2, y2);
+            }
+        }
+    }
+    for (block, data) in blocks.iter_enumerated() {
+        let (x, y) = position[block];
+        let name = format!("{:?}", block);
+        let class = if data.is_cleanup { "node cleanup" } else { "node" };
+        let _ = write!(
+            svg,
+            "<g class=\"{}\" data-name=\"{}\"><title>{}</title>\
+             <rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" rx=\"4\"/>\
+             <text x=\"{}\" y=\"{}\">{}</text></g>",
+            class,
+            name,
+            escape(&format!("{:?}", data.terminator().kind)),
+            x, y, WIDTH, HEIGHT,
+            x + WIDTH / 2, y + HEIGHT / 2 + 4, name,
+        );
+    }
+    svg.push_str("</svg>");
+    svg
+}
+
+const STYLE: &str = r#"
+body { font-family: sans-serif; margin: 1em; }
+pre { font-size: 12px; line-height: 1.35; }
+#controls { margin-bottom: 0.5em; }
+#controls select { max-width: 45%; }
+#panes { display: flex; }
+.pane { flex: 1; min-width: 0; margin-right: 1em; }
+.pane h2 { font-size: 1em; }
+.pane pre.mir { max-height: 60vh; overflow: auto; border: 1px solid #ccc; padding: 0.5em; }
+.pane svg.cfg { display: block; max-width: 100%; overflow: auto; }
+.line { display: block; }
+.line.ins { background: #dfd; }
+.line.del { background: #fdd; text-decoration: line-through; }
+.local, .block { cursor: pointer; }
+.hl { background: #ff8; }
+#source { border: 1px solid #ccc; padding: 0.5em; }
+#source .src { display: block; }
+#source .num { color: #888; }
+.node rect { fill: #eef; stroke: #446; }
+.node.cleanup rect { fill: #fee; stroke: #644; }
+.node.hl rect { fill: #ff8; }
+.node text { font: 11px monospace; text-anchor: middle; }
+.node { cursor: pointer; }
+.edge { stroke: #446; fill: none; }
+.edge.back { stroke-dasharray: 4 3; }
+"#;
+
+const SCRIPT: &str = r#"
+(function() {
+    var passes = document.querySelectorAll('#passes > section');
+    var left = document.getElementById('left');
+    var right = document.getElementById('right');
+    var diff = document.getElementById('diff');
+    var leftPane = document.getElementById('left-pane');
+    var rightPane = document.getElementById('right-pane');
+
+    for (var i = 0; i < passes.length; i++) {
+        [left, right].forEach(function(select) {
+            var option = document.createElement('option');
+            option.value = i;
+            option.textContent = passes[i].getAttribute('data-title');
+            select.appendChild(option);
+        });
+    }
+    left.value = Math.max(passes.length - 2, 0);
+    right.value = passes.length - 1;
+
+    // Compares lines without the location in their comment, which changes
+    // for every statement that follows an inserted or removed one.
+    function key(line) {
+        return line.textContent.replace(/\/\/ bb\d+\[\d+\]: /, '// ');
+    }
+
+    // Diffs the lines of two passes through their longest common subsequence.
+    function diffLines(before, after) {
+        var a = Array.prototype.map.call(before, key);
+        var b = Array.prototype.map.call(after, key);
+        var n = a.length, m = b.length;
+        if (n * m > 16000000) {
+            return null;
+        }
+        var table = [];
+        for (var i = 0; i <= n; i++) {
+            table.push(new Int32Array(m + 1));
+        }
+        for (i = n - 1; i >= 0; i--) {
+            for (var j = m - 1; j >= 0; j--) {
+                table[i][j] = a[i] === b[j] ?
+                    table[i + 1][j + 1] + 1 :
+                    Math.max(table[i + 1][j], table[i][j + 1]);
+            }
+        }
+        var pre = document.createElement('pre');
+        pre.className = 'mir';
+        i = 0;
+        j = 0;
+        while (i < n || j < m) {
+            var line;
+            if (i < n && j < m && a[i] === b[j]) {
+                line = after[j].cloneNode(true);
+                i++;
+                j++;
+            } else if (i < n && (j === m || table[i + 1][j] >= table[i][j + 1])) {
+                line = before[i].cloneNode(true);
+                line.classList.add('del');
+                i++;
+            } else {
+                line = after[j].cloneNode(true);
+                line.classList.add('ins');
+                j++;
+            }
+            pre.appendChild(line);
+        }
+        return pre;
+    }
+
+    function show(pane, section, previous) {
+        pane.innerHTML = '';
+        var title = document.createElement('h2');
+        title.textContent = section.getAttribute('data-title');
+        pane.appendChild(title);
+        var pre = null;
+        if (previous) {
+            pre = diffLines(previous.querySelectorAll('pre.mir > .line'),
+                            section.querySelectorAll('pre.mir > .line'));
+        }
+        pane.appendChild(pre || section.querySelector('pre.mir').cloneNode(true));
+        pane.appendChild(section.querySelector('svg.cfg').cloneNode(true));
+    }
+
+    function update() {
+        if (passes.length === 0) {
+            return;
+        }
+        var before = passes[left.value];
+        var after = passes[right.value];
+        show(leftPane, before, null);
+        show(rightPane, after, diff.checked ? before : null);
+    }
+
+    function mark(target, on) {
+        if (!target.closest) {
+            return;
+        }
+        var named = target.closest('[data-name]');
+        if (named) {
+            var name = named.getAttribute('data-name');
+            document.querySelectorAll('#panes [data-name="' + name + '"]').forEach(function(e) {
+                e.classList.toggle('hl', on);
+            });
+        }
+        var line = target.closest('.line[data-lo]');
+        if (line) {
+            var lo = +line.getAttribute('data-lo');
+            var hi = +line.getAttribute('data-hi');
+            document.querySelectorAll('#source > .src').forEach(function(e) {
+                var n = +e.getAttribute('data-line');
+                e.classList.toggle('hl', on && lo <= n && n <= hi);
+            });
+        }
+    }
+
+    document.addEventListener('mouseover', function(event) { mark(event.target, true); });
+    document.addEventListener('mouseout', function(event) { mark(event.target, false); });
+
+    // Clicking on a block in a graph scrolls to it in the MIR next to the graph.
+    document.addEventListener('click', function(event) {
+        var node = event.target.closest && event.target.closest('.node');
+        var pane = node && node.closest('.pane');
+        if (!pane) {
+            return;
+        }
+        var selector = '.line[data-block="' + node.getAttribute('data-name') + '"]';
+        var header = pane.querySelector(selector);
+        if (header) {
+            header.scrollIntoView({ block: 'center' });
+        }
+    });
+
+    left.addEventListener('change', update);
+    right.addEventListener('change', update);
+    diff.addEventListener('change', update);
+    update();
+})();
+"#;
@@ -32,10 +32,10 @@ use rustc::mir::*;
 use rustc::ty::{item_path, TyCtxt};
 use rustc_data_structures::bit_set::BitSet;
 use rustc_data_structures::indexed_vec::{Idx, IndexVec};
-use rustc_data_structures::work_queue::WorkQueue;
 use std::fs;
 use std::io::{self, Write};
 use std::path::{Path, PathBuf};
+use crate::dataflow::generic::{Backward, Engine, GenKill, GenKillAnalysis};
 use crate::transform::MirSource;
 use crate::util::pretty::{dump_enabled, write_basic_block, write_mir_intro};
 
@@ -99,47 +99,59 @@ pub fn liveness_of_locals<'tcx, V: Idx>(
     mir: &Mir<'tcx>,
     map: &impl LiveVariableMap<LiveVar = V>,
 ) -> LivenessResult<V> {
-    let num_live_vars = map.num_variables();
+    // The state on entry to each block of a backward analysis is the state
+    // on exit from it in program order.
+    let outs = Engine::new_gen_kill(mir, LiveLocals::new(map))
+        .iterate_to_fixpoint()
+        .into_entry_sets();
 
-    let def_use: IndexVec<_, DefsUses<V>> = mir
-        .basic_blocks()
-        .iter()
-        .map(|b| block(map, b, num_live_vars))
-        .collect();
+    LivenessResult { outs }
+}
+
+/// The backward dataflow analysis computing the live variables.
+pub struct LiveLocals<'lv, M: 'lv> {
+    map: &'lv M,
+}
 
-    let mut outs: IndexVec<_, LiveVarSet<V>> = mir
-        .basic_blocks()
-        .indices()
-        .map(|_| LiveVarSet::new_empty(num_live_vars))
-        .collect();
+impl<'lv, M> LiveLocals<'lv, M> {
+    pub fn new(map: &'lv M) -> Self {
+        LiveLocals { map }
+    }
+}
 
-    let mut bits = LiveVarSet::new_empty(num_live_vars);
+impl<'tcx, 'lv, V, M> GenKillAnalysis<'tcx> for LiveLocals<'lv, M>
+where
+    V: Idx,
+    M: LiveVariableMap<LiveVar = V>,
+{
+    type Idx = V;
+    type Direction = Backward;
 
-    // queue of things that need to be re-processed, and a set containing
-    // the things currently in the queue
-    let mut dirty_queue: WorkQueue<BasicBlock> = WorkQueue::with_all(mir.basic_blocks().len());
+    const NAME: &'static str = "liveness";
 
-    let predecessors = mir.predecessors();
+    fn domain_size(&self, _mir: &Mir<'tcx>) -> usize {
+        self.map.num_variables()
+    }
 
-    while let Some(bb) = dirty_queue.pop() {
-        // bits = use ∪ (bits - def)
-        bits.overwrite(&outs[bb]);
-        def_use[bb].apply(&mut bits);
+    fn initialize_start_block(&self, _mir: &Mir<'tcx>, _state: &mut LiveVarSet<V>) {
+        // Not used by backward analyses.
+    }
 
-        // `bits` now contains the live variables on entry. Therefore,
-        // add `bits` to the `out` set for each predecessor; if those
-        // bits were not already present, then enqueue the predecessor
-        // as dirty.
-        //
-        // (note that `union` returns true if the `self` set changed)
-        for &pred_bb in &predecessors[bb] {
-            if outs[pred_bb].union(&bits) {
-                dirty_queue.insert(pred_bb);
-            }
-        }
+    fn statement_effect(&self,
+                        trans: &mut impl GenKill<V>,
+                        statement: &Statement<'tcx>,
+                        location: Location) {
+        DefsUsesVisitor { map: self.map, trans }
+            .visit_statement(location.block, statement, location);
     }
 
-    LivenessResult { outs }
+    fn terminator_effect(&self,
+                         trans: &mut impl GenKill<V>,
+                         terminator: &Terminator<'tcx>,
+                         location: Location) {
+        DefsUsesVisitor { map: self.map, trans }
+            .visit_terminator(location.block, terminator, location);
+    }
 }
 
 #[derive(Eq, PartialEq, Clone)]
@@ -211,100 +223,32 @@ pub fn categorize<'tcx>(context: PlaceContext<'tcx>) -> Option<DefUse> {
     }
 }
 
-struct DefsUsesVisitor<'lv, V, M>
-where
-    V: Idx,
-    M: LiveVariableMap<LiveVar = V> + 'lv,
-{
+/// Records the defs and uses of a single statement or terminator. A use of
+/// a variable makes it live before the statement, while a def kills it.
+/// Within a statement, whichever comes later in visiting order wins; e.g.,
+/// `X = X + 1` uses `X`.
+struct DefsUsesVisitor<'lv, 't, M: 'lv, T: 't> {
     map: &'lv M,
-    defs_uses: DefsUses<V>,
-}
-
-#[derive(Eq, PartialEq, Clone)]
-struct DefsUses<V: Idx> {
-    defs: LiveVarSet<V>,
-    uses: LiveVarSet<V>,
-}
-
-impl<V: Idx> DefsUses<V> {
-    fn apply(&self, bits: &mut LiveVarSet<V>) -> bool {
-        bits.subtract(&self.defs) | bits.union(&self.uses)
-    }
-
-    fn add_def(&mut self, index: V) {
-        // If it was used already in the block, remove that use
-        // now that we found a definition.
-        //
-        // Example:
-        //
-        //     // Defs = {X}, Uses = {}
-        //     X = 5
-        //     // Defs = {}, Uses = {X}
-        //     use(X)
-        self.uses.remove(index);
-        self.defs.insert(index);
-    }
-
-    fn add_use(&mut self, index: V) {
-        // Inverse of above.
-        //
-        // Example:
-        //
-        //     // Defs = {}, Uses = {X}
-        //     use(X)
-        //     // Defs = {X}, Uses = {}
-        //     X = 5
-        //     // Defs = {}, Uses = {X}
-        //     use(X)
-        self.defs.remove(index);
-        self.uses.insert(index);
-    }
+    trans: &'t mut T,
 }
 
-impl<'tcx, 'lv, V, M> Visitor<'tcx> for DefsUsesVisitor<'lv, V, M>
+impl<'tcx, 'lv, 't, V, M, T> Visitor<'tcx> for DefsUsesVisitor<'lv, 't, M, T>
 where
     V: Idx,
     M: LiveVariableMap<LiveVar = V>,
+    T: GenKill<V>,
 {
     fn visit_local(&mut self, &local: &Local, context: PlaceContext<'tcx>, _: Location) {
         if let Some(v_index) = self.map.from_local(local) {
             match categorize(context) {
-                Some(DefUse::Def) => self.defs_uses.add_def(v_index),
-                Some(DefUse::Use) | Some(DefUse::Drop) => self.defs_uses.add_use(v_index),
+                Some(DefUse::Def) => self.trans.kill(v_index),
+                Some(DefUse::Use) | Some(DefUse::Drop) => self.trans.gen(v_index),
                 _ => (),
             }
         }
     }
 }
 
-fn block<'tcx, V: Idx>(
-    map: &impl LiveVariableMap<LiveVar = V>,
-    b: &BasicBlockData<'tcx>,
-    locals: usize,
-) -> DefsUses<V> {
-    let mut visitor = DefsUsesVisitor {
-        map,
-        defs_uses: DefsUses {
-            defs: LiveVarSet::new_empty(locals),
-            uses: LiveVarSet::new_empty(locals),
-        },
-    };
-
-    let dummy_location = Location {
-        block: BasicBlock::new(0),
-        statement_index: 0,
-    };
-
-    // Visit the various parts of the basic block in reverse. If we go
-    // forward, the logic in `add_def` and `add_use` would be wrong.
-    visitor.visit_terminator(BasicBlock::new(0), b.terminator(), dummy_location);
-    for statement in b.statements.iter().rev() {
-        visitor.visit_statement(BasicBlock::new(0), statement, dummy_location);
-    }
-
-    visitor.defs_uses
-}
-
 pub fn dump_mir<'a, 'tcx, V: Idx>(
     tcx: TyCtxt<'a, 'tcx, 'tcx>,
     pass_name: &str,
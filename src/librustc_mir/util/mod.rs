@@ -9,6 +9,7 @@ pub mod patch;
 
 mod alignment;
 mod graphviz;
+mod html;
 pub(crate) mod pretty;
 pub mod liveness;
 pub mod collect_writes;
@@ -17,6 +18,7 @@ pub use self::alignment::is_disaligned;
 pub use self::pretty::{dump_enabled, dump_mir, write_mir_pretty, PassWhere};
 pub use self::graphviz::{write_mir_graphviz};
 pub use self::graphviz::write_node_label as write_graphviz_node_label;
+pub use self::html::finish_mir_html_dumps;
 
 /// If possible, suggest replacing `ref` with `ref mut`.
 pub fn suggest_ref_mut<'cx, 'gcx, 'tcx>(
@@ -11,6 +11,7 @@ use std::fs;
 use std::io::{self, Write};
 use std::path::{Path, PathBuf};
 use super::graphviz::write_mir_fn_graphviz;
+use super::html::write_mir_fn_html;
 use crate::transform::MirSource;
 
 const INDENT: &str = "    ";
@@ -153,6 +154,11 @@ fn dump_matched_mir_node<'a, 'gcx, 'tcx, F>(
             write_mir_fn_graphviz(tcx, source.def_id(), mir, &mut file)?;
         };
     }
+
+    if tcx.sess.opts.debugging_opts.dump_mir_html {
+        let _: io::Result<()> =
+            write_mir_fn_html(tcx, pass_num, pass_name, disambiguator, source, mir);
+    }
 }
 
 /// Returns the path to the filename where we should dump a given MIR.
@@ -166,11 +172,6 @@ fn dump_path(
     disambiguator: &dyn Display,
     source: MirSource<'tcx>,
 ) -> PathBuf {
-    let promotion_id = match source.promoted {
-        Some(id) => format!("-{:?}", id),
-        None => String::new(),
-    };
-
     let pass_num = if tcx.sess.opts.debugging_opts.dump_mir_exclude_pass_number {
         String::new()
     } else {
@@ -183,6 +184,28 @@ fn dump_path(
     let mut file_path = PathBuf::new();
     file_path.push(Path::new(&tcx.sess.opts.debugging_opts.dump_mir_dir));
 
+    let file_name = format!(
+        "{}{}.{}.{}.{}",
+        dump_file_stem(tcx, source),
+        pass_num,
+        pass_name,
+        disambiguator,
+        extension,
+    );
+
+    file_path.push(&file_name);
+
+    file_path
+}
+
+/// Returns the part of the dump file names that identifies the MIR body,
+/// `rustc.<item>` followed by the shim type and the promoted index, if any.
+pub(crate) fn dump_file_stem(tcx: TyCtxt<'_, '_, '_>, source: MirSource<'tcx>) -> String {
+    let promotion_id = match source.promoted {
+        Some(id) => format!("-{:?}", id),
+        None => String::new(),
+    };
+
     let item_name = tcx
         .def_path(source.def_id())
         .to_filename_friendly_no_crate();
@@ -205,20 +228,7 @@ fn dump_path(
         _ => String::new(),
     };
 
-    let file_name = format!(
-        "rustc.{}{}{}{}.{}.{}.{}",
-        item_name,
-        shim_disambiguator,
-        promotion_id,
-        pass_num,
-        pass_name,
-        disambiguator,
-        extension,
-    );
-
-    file_path.push(&file_name);
-
-    file_path
+    format!("rustc.{}{}{}", item_name, shim_disambiguator, promotion_id)
 }
 
 /// Attempts to open a file where we should dump a given MIR or other
@@ -444,7 +444,7 @@ impl AbiAndPrefAlign {
 }
 
 /// Integers, also used for enum discriminants.
-#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug, RustcEncodable, RustcDecodable)]
 pub enum Integer {
     I8,
     I16,
@@ -561,7 +561,7 @@ impl FloatTy {
 }
 
 /// Fundamental unit of memory access and layout.
-#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug, RustcEncodable, RustcDecodable)]
 pub enum Primitive {
     /// The `bool` is the signedness of the `Integer` type.
     ///
@@ -614,7 +614,7 @@ impl<'a, 'tcx> Primitive {
 }
 
 /// Information about one scalar component of a Rust type.
-#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+#[derive(Clone, PartialEq, Eq, Hash, Debug, RustcEncodable, RustcDecodable)]
 pub struct Scalar {
     pub value: Primitive,
 
@@ -663,7 +663,7 @@ impl Scalar {
 }
 
 /// Describes how the fields of a type are located in memory.
-#[derive(PartialEq, Eq, Hash, Debug)]
+#[derive(PartialEq, Eq, Hash, Debug, RustcEncodable, RustcDecodable)]
 pub enum FieldPlacement {
     /// All fields start at no offset. The `usize` is the field count.
     ///
@@ -770,7 +770,7 @@ impl FieldPlacement {
 
 /// Describes how values of the type are passed by target ABIs,
 /// in terms of categories of C types there are ABI rules for.
-#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+#[derive(Clone, PartialEq, Eq, Hash, Debug, RustcEncodable, RustcDecodable)]
 pub enum Abi {
     Uninhabited,
     Scalar(Scalar),
@@ -821,7 +821,7 @@ newtype_index! {
     pub struct VariantIdx { .. }
 }
 
-#[derive(PartialEq, Eq, Hash, Debug)]
+#[derive(PartialEq, Eq, Hash, Debug, RustcEncodable, RustcDecodable)]
 pub enum Variants {
     /// Single enum variants, structs/tuples, unions, and all non-ADTs.
     Single {
@@ -853,7 +853,7 @@ pub enum Variants {
     }
 }
 
-#[derive(PartialEq, Eq, Hash, Debug)]
+#[derive(PartialEq, Eq, Hash, Debug, RustcEncodable, RustcDecodable)]
 pub struct LayoutDetails {
     pub variants: Variants,
     pub fields: FieldPlacement,
@@ -889,6 +889,9 @@ pub struct TyLayout<'a, Ty> {
     pub details: &'a LayoutDetails
 }
 
+// Layouts are interned, so decoding them needs a type context.
+impl<'a> ::rustc_serialize::UseSpecializedDecodable for &'a LayoutDetails {}
+
 impl<'a, Ty> Deref for TyLayout<'a, Ty> {
     type Target = &'a LayoutDetails;
     fn deref(&self) -> &&'a LayoutDetails {
@@ -7,6 +7,7 @@ use std::mem;
 use std::fmt::{self, Write};
 use std::ops;
 
+use rustc::util::nodemap::FxHashSet;
 use syntax::symbol::Symbol;
 use syntax::ast::{MetaItem, MetaItemKind, NestedMetaItem, NestedMetaItemKind, LitKind};
 use syntax::parse::ParseSess;
@@ -111,6 +112,45 @@ impl Cfg {
         }
     }
 
+    /// Removes every condition on one of the `hidden` configuration names, returning `None` if
+    /// nothing is left.
+    ///
+    /// Used for configurations collected from plain `#[cfg]` attributes, so that e.g.
+    /// `#[cfg(not(test))]` doesn't end up as a portability note. A `not(..)` mentioning a hidden
+    /// name is removed as a whole: simplifying inside it would invert the condition, e.g.
+    /// `not(all(test, unix))` must not become `not(unix)`.
+    pub fn without_hidden(&self, hidden: &FxHashSet<Symbol>) -> Option<Cfg> {
+        match *self {
+            Cfg::False | Cfg::True => Some(self.clone()),
+            Cfg::Cfg(name, _) => if hidden.contains(&name) { None } else { Some(self.clone()) },
+            Cfg::Not(ref child) => {
+                if child.mentions_any(hidden) { None } else { Some(self.clone()) }
+            }
+            Cfg::All(ref sub_cfgs) => {
+                let mut sub_cfgs = sub_cfgs.iter().filter_map(|c| c.without_hidden(hidden));
+                let first = sub_cfgs.next()?;
+                Some(sub_cfgs.fold(first, |x, y| x & y))
+            }
+            Cfg::Any(ref sub_cfgs) => {
+                let mut sub_cfgs = sub_cfgs.iter().filter_map(|c| c.without_hidden(hidden));
+                let first = sub_cfgs.next()?;
+                Some(sub_cfgs.fold(first, |x, y| x | y))
+            }
+        }
+    }
+
+    /// Whether any condition in the configuration is on one of the given names.
+    fn mentions_any(&self, names: &FxHashSet<Symbol>) -> bool {
+        match *self {
+            Cfg::False | Cfg::True => false,
+            Cfg::Cfg(name, _) => names.contains(&name),
+            Cfg::Not(ref child) => child.mentions_any(names),
+            Cfg::All(ref sub_cfgs) | Cfg::Any(ref sub_cfgs) => {
+                sub_cfgs.iter().any(|c| c.mentions_any(names))
+            }
+        }
+    }
+
     /// Whether the configuration consists of just `Cfg` or `Not`.
     fn is_simple(&self) -> bool {
         match *self {
@@ -180,7 +220,7 @@ impl Cfg {
 
     fn should_use_with_in_description(&self) -> bool {
         match *self {
-            Cfg::Cfg(ref name, _) if name == &"target_feature" => true,
+            Cfg::Cfg(ref name, _) if name == &"target_feature" || name == &"feature" => true,
             _ => false,
         }
     }
@@ -204,16 +244,30 @@ impl ops::BitAndAssign for Cfg {
             (&mut Cfg::False, _) | (_, Cfg::True) => {},
             (s, Cfg::False) => *s = Cfg::False,
             (s @ &mut Cfg::True, b) => *s = b,
-            (&mut Cfg::All(ref mut a), Cfg::All(ref mut b)) => a.append(b),
-            (&mut Cfg::All(ref mut a), ref mut b) => a.push(mem::replace(b, Cfg::True)),
+            (&mut Cfg::All(ref mut a), Cfg::All(ref mut b)) => {
+                for c in b.drain(..) {
+                    if !a.contains(&c) {
+                        a.push(c);
+                    }
+                }
+            },
+            (&mut Cfg::All(ref mut a), ref mut b) => {
+                if !a.contains(b) {
+                    a.push(mem::replace(b, Cfg::True));
+                }
+            },
             (s, Cfg::All(mut a)) => {
                 let b = mem::replace(s, Cfg::True);
-                a.push(b);
+                if !a.contains(&b) {
+                    a.push(b);
+                }
                 *s = Cfg::All(a);
             },
             (s, b) => {
-                let a = mem::replace(s, Cfg::True);
-                *s = Cfg::All(vec![a, b]);
+                if *s != b {
+                    let a = mem::replace(s, Cfg::True);
+                    *s = Cfg::All(vec![a, b]);
+                }
             },
         }
     }
@@ -233,16 +287,30 @@ impl ops::BitOrAssign for Cfg {
             (&mut Cfg::True, _) | (_, Cfg::False) => {},
             (s, Cfg::True) => *s = Cfg::True,
             (s @ &mut Cfg::False, b) => *s = b,
-            (&mut Cfg::Any(ref mut a), Cfg::Any(ref mut b)) => a.append(b),
-            (&mut Cfg::Any(ref mut a), ref mut b) => a.push(mem::replace(b, Cfg::True)),
+            (&mut Cfg::Any(ref mut a), Cfg::Any(ref mut b)) => {
+                for c in b.drain(..) {
+                    if !a.contains(&c) {
+                        a.push(c);
+                    }
+                }
+            },
+            (&mut Cfg::Any(ref mut a), ref mut b) => {
+                if !a.contains(b) {
+                    a.push(mem::replace(b, Cfg::True));
+                }
+            },
             (s, Cfg::Any(mut a)) => {
                 let b = mem::replace(s, Cfg::True);
-                a.push(b);
+                if !a.contains(&b) {
+                    a.push(b);
+                }
                 *s = Cfg::Any(a);
             },
             (s, b) => {
-                let a = mem::replace(s, Cfg::True);
-                *s = Cfg::Any(vec![a, b]);
+                if *s != b {
+                    let a = mem::replace(s, Cfg::True);
+                    *s = Cfg::Any(vec![a, b]);
+                }
             },
         }
     }
@@ -390,6 +458,13 @@ impl<'a> fmt::Display for Html<'a> {
                         } else {
                             return write!(fmt, "target feature <code>{}</code>", feat);
                         },
+                    ("feature", Some(feat)) =>
+                        if self.1 {
+                            return write!(fmt, "<code>{}</code>", Escape(&*feat.as_str()));
+                        } else {
+                            return write!(fmt, "crate feature <code>{}</code>",
+                                          Escape(&*feat.as_str()));
+                        },
                     _ => "",
                 };
                 if !human_readable.is_empty() {
@@ -408,6 +483,7 @@ impl<'a> fmt::Display for Html<'a> {
 mod test {
     use super::Cfg;
 
+    use rustc::util::nodemap::FxHashSet;
     use syntax::symbol::Symbol;
     use syntax::ast::*;
     use syntax::source_map::dummy_spanned;
@@ -525,6 +601,17 @@ mod test {
                 word_cfg("a") & word_cfg("b") & word_cfg("c"),
                 Cfg::All(vec![word_cfg("a"), word_cfg("b"), word_cfg("c")])
             );
+
+            // Repeated conditions are only kept once.
+            assert_eq!(word_cfg("a") & word_cfg("a"), word_cfg("a"));
+            assert_eq!(
+                word_cfg("a") & word_cfg("b") & word_cfg("a"),
+                Cfg::All(vec![word_cfg("a"), word_cfg("b")])
+            );
+            assert_eq!(
+                word_cfg("b") & Cfg::All(vec![word_cfg("a"), word_cfg("b")]),
+                Cfg::All(vec![word_cfg("a"), word_cfg("b")])
+            );
         })
     }
 
@@ -577,6 +664,13 @@ mod test {
                 word_cfg("a") | word_cfg("b") | word_cfg("c"),
                 Cfg::Any(vec![word_cfg("a"), word_cfg("b"), word_cfg("c")])
             );
+
+            // Repeated conditions are only kept once.
+            assert_eq!(word_cfg("a") | word_cfg("a"), word_cfg("a"));
+            assert_eq!(
+                Cfg::Any(vec![word_cfg("a"), word_cfg("b")]) | Cfg::Any(vec![word_cfg("b")]),
+                Cfg::Any(vec![word_cfg("a"), word_cfg("b")])
+            );
         })
     }
 
@@ -819,6 +913,51 @@ mod test {
                 "This is supported on <strong>x86-64 and target feature \
                 <code>sse2</code></strong> only."
             );
+            assert_eq!(
+                name_value_cfg("feature", "serde").render_long_html(),
+                "This is supported with <strong>crate feature <code>serde</code></strong> only."
+            );
+            assert_eq!(
+                name_value_cfg("feature", "serde").render_short_html(),
+                "<code>serde</code>"
+            );
+        })
+    }
+
+    #[test]
+    fn test_without_hidden() {
+        with_globals(|| {
+            let hidden: FxHashSet<Symbol> = vec![Symbol::intern("test")].into_iter().collect();
+
+            assert_eq!(word_cfg("test").without_hidden(&hidden), None);
+            assert_eq!((!word_cfg("test")).without_hidden(&hidden), None);
+            assert_eq!(word_cfg("unix").without_hidden(&hidden), Some(word_cfg("unix")));
+            assert_eq!(
+                (word_cfg("unix") & !word_cfg("test")).without_hidden(&hidden),
+                Some(word_cfg("unix"))
+            );
+            assert_eq!(
+                (word_cfg("unix") | word_cfg("test") | word_cfg("windows"))
+                    .without_hidden(&hidden),
+                Some(word_cfg("unix") | word_cfg("windows"))
+            );
+            assert_eq!(
+                (word_cfg("test") | name_value_cfg("test", "x")).without_hidden(&hidden),
+                None
+            );
+            assert_eq!(
+                (!(word_cfg("test") & word_cfg("unix"))).without_hidden(&hidden),
+                None
+            );
+            assert_eq!(
+                (word_cfg("windows") & !(word_cfg("test") | word_cfg("unix")))
+                    .without_hidden(&hidden),
+                Some(word_cfg("windows"))
+            );
+            assert_eq!(
+                (!(word_cfg("unix") & word_cfg("windows"))).without_hidden(&hidden),
+                Some(!(word_cfg("unix") & word_cfg("windows")))
+            );
         })
     }
 }
@@ -85,6 +85,9 @@ pub struct Options {
     /// Whether to display warnings during doc generation or while gathering doctests. By default,
     /// all non-rustdoc-specific lints are allowed when generating docs.
     pub display_warnings: bool,
+    /// The kind of documentation to generate. Only HTML output supports the options in
+    /// `render_options` beyond `output`.
+    pub output_format: OutputFormat,
 
     // Options that alter generated documentation pages
 
@@ -128,12 +131,24 @@ impl fmt::Debug for Options {
             .field("default_passes", &self.default_passes)
             .field("manual_passes", &self.manual_passes)
             .field("display_warnings", &self.display_warnings)
+            .field("output_format", &self.output_format)
             .field("crate_version", &self.crate_version)
             .field("render_options", &self.render_options)
             .finish()
     }
 }
 
+/// The kinds of documentation rustdoc can write, selected with `--output-format`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OutputFormat {
+    /// The regular HTML documentation.
+    Html,
+    /// One roff man page per item, in section 3 of a man directory.
+    Man,
+    /// One plain-text page per item, for reading in a terminal pager.
+    Text,
+}
+
 /// Configuration options for the HTML page-creation process.
 #[derive(Clone, Debug)]
 pub struct RenderOptions {
@@ -194,6 +209,9 @@ pub struct RenderOptions {
     pub generate_search_filter: bool,
     /// Option (disabled by default) to generate files used by RLS and some other tools.
     pub generate_redirect_pages: bool,
+    /// Whether to generate the per-crate full-text index of the documentation, in addition to
+    /// the regular search index.
+    pub enable_fulltext_search: bool,
 }
 
 impl Options {
@@ -389,13 +407,22 @@ impl Options {
             }
         }
 
-        match matches.opt_str("w").as_ref().map(|s| &**s) {
-            Some("html") | None => {}
+        let output_format = match matches.opt_str("w").as_ref().map(|s| &**s) {
+            Some("html") | None => OutputFormat::Html,
+            Some(s @ "man") | Some(s @ "text") => {
+                if !nightly_options::is_unstable_enabled(matches) {
+                    diag.struct_err(&format!("the `-Z unstable-options` flag must also be \
+                                              passed to enable the `{}` output format", s))
+                        .emit();
+                    return Err(1);
+                }
+                if s == "man" { OutputFormat::Man } else { OutputFormat::Text }
+            }
             Some(s) => {
                 diag.struct_err(&format!("unknown output format: {}", s)).emit();
                 return Err(1);
             }
-        }
+        };
 
         let index_page = matches.opt_str("index-page").map(|s| PathBuf::from(&s));
         if let Some(ref index_page) = index_page {
@@ -439,6 +466,7 @@ impl Options {
         let generate_search_filter = !matches.opt_present("disable-per-crate-search");
         let persist_doctests = matches.opt_str("persist-doctests").map(PathBuf::from);
         let generate_redirect_pages = matches.opt_present("generate-redirect-pages");
+        let enable_fulltext_search = matches.opt_present("enable-fulltext-search");
 
         let (lint_opts, describe_lints, lint_cap) = get_cmd_lint_options(matches, error_format);
 
@@ -463,6 +491,7 @@ impl Options {
             default_passes,
             manual_passes,
             display_warnings,
+            output_format,
             crate_version,
             persist_doctests,
             render_options: RenderOptions {
@@ -484,6 +513,7 @@ impl Options {
                 markdown_playground_url,
                 generate_search_filter,
                 generate_redirect_pages,
+                enable_fulltext_search,
             }
         })
     }
@@ -499,7 +529,6 @@ impl Options {
 fn check_deprecated_options(matches: &getopts::Matches, diag: &errors::Handler) {
     let deprecated_flags = [
        "input-format",
-       "output-format",
        "no-defaults",
        "passes",
     ];
@@ -0,0 +1,243 @@
+//! Full-text search index over documentation bodies.
+//!
+//! The regular search index (see `build_index` in `render.rs`) only knows about item names,
+//! paths and signature types. When `--enable-fulltext-search` is passed, rustdoc additionally
+//! tokenizes and stems the plain text of every indexed item's documentation and writes an
+//! inverted index for each crate to `fulltext-index/<crate>.js`. The file assigns into the global
+//! `fullTextIndex` object so it can be loaded through a `<script>` tag, which keeps search
+//! working when the docs are browsed from `file://`.
+//!
+//! Documents are identified by their position in the crate's entry of the regular search index,
+//! so the full-text index itself doesn't need to repeat any paths or names.
+//!
+//! The serialized form of a crate's index is an object with two arrays of the same length:
+//!
+//! * `t`: the sorted list of stemmed terms;
+//! * `p`: for every term, its postings list as a string of comma-separated entries. Each entry
+//!   is the distance to the previous document id in base 36, optionally followed by `:` and the
+//!   number of occurrences of the term in that document when it is larger than one.
+//!
+//! The tokenizer and stemmer below are mirrored by `fullTextTokenize` and `fullTextStem` in
+//! `static/main.js`. Any change to one must be applied to the other. Both are checked against
+//! the cases in `fulltext-tokenize.json`: here by `test_tokenize_cases`, and in JavaScript by
+//! `src/tools/rustdoc-js/tester.js`.
+
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+use serialize::json::as_json;
+
+/// Words which are so frequent in documentation that indexing them would only bloat the
+/// postings lists.
+const STOP_WORDS: &[&str] = &[
+    "a", "an", "and", "are", "as", "at", "be", "by", "for", "from", "if", "in", "is", "it",
+    "its", "of", "on", "or", "that", "the", "this", "to", "was", "will", "with",
+];
+
+/// Terms longer than this, in UTF-16 code units as counted by JavaScript, are most likely
+/// hashes, URLs or other identifiers nobody will search for by hand.
+const MAX_TERM_LEN: usize = 32;
+
+/// Whether `c` is part of a word once the text is lowercased: ASCII letters and digits, and
+/// everything from U+00C0 on. This is coarser than `char::is_alphanumeric`, but it is what the
+/// search page can check without Unicode-aware regular expressions.
+fn is_word_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c >= '\u{c0}'
+}
+
+/// Splits `text` into lowercase words and stems them.
+///
+/// Underscores and `::` separate words, so that `into_iter` and `slice::iter` yield
+/// `into`/`iter` and `slice`/`iter` respectively.
+pub fn tokenize(text: &str) -> Vec<String> {
+    text.to_lowercase()
+        .split(|c: char| !is_word_char(c))
+        .filter(|w| !w.is_empty() && w.encode_utf16().count() <= MAX_TERM_LEN)
+        .filter(|w| !STOP_WORDS.contains(w))
+        .map(stem)
+        .collect()
+}
+
+fn is_vowel(c: u8) -> bool {
+    match c {
+        b'a' | b'e' | b'i' | b'o' | b'u' | b'y' => true,
+        _ => false,
+    }
+}
+
+/// Applies a light suffix-stripping stemmer to a lowercase word.
+///
+/// This is a heavily simplified version of the first steps of the Porter stemmer: it only
+/// handles plurals, `-ed`/`-ing`/`-ly` and a trailing `-e`/`-y`, which covers most of the
+/// inflections found in API documentation. Non-ASCII words are returned unchanged.
+pub fn stem(word: &str) -> String {
+    if !word.is_ascii() || word.len() <= 3 {
+        return word.to_owned();
+    }
+    let mut w = word.as_bytes().to_vec();
+
+    // Plurals: "sses" -> "ss", "ies" -> "i", "s" -> "".
+    if w.ends_with(b"sses") {
+        w.truncate(w.len() - 2);
+    } else if w.ends_with(b"ies") {
+        w.truncate(w.len() - 2);
+    } else if w.ends_with(b"s") && !w.ends_with(b"ss") && !w.ends_with(b"us") {
+        w.truncate(w.len() - 1);
+    }
+
+    // Verb and adverb endings, only when a vowel remains in the stem.
+    for suffix in &[&b"ing"[..], b"ed", b"ly"] {
+        if w.ends_with(suffix) && w.len() - suffix.len() >= 3 &&
+           w[..w.len() - suffix.len()].iter().any(|&c| is_vowel(c)) {
+            w.truncate(w.len() - suffix.len());
+            // "running" -> "runn" -> "run"
+            let n = w.len();
+            if w[n - 1] == w[n - 2] && !is_vowel(w[n - 1]) &&
+               !b"lsz".contains(&w[n - 1]) {
+                w.truncate(n - 1);
+            }
+            break;
+        }
+    }
+
+    // "iterate" and "iterat(ed)" should meet, as should "copy" and "copi(es)".
+    let n = w.len();
+    if n > 4 && w[n - 1] == b'e' {
+        w.truncate(n - 1);
+    } else if n > 3 && w[n - 1] == b'y' {
+        w[n - 1] = b'i';
+    }
+
+    String::from_utf8(w).unwrap()
+}
+
+/// An inverted index from stemmed terms to the documents that contain them.
+#[derive(Default)]
+pub struct FullTextIndex {
+    /// Maps each term to a list of `(document id, number of occurrences)`, sorted by id.
+    postings: BTreeMap<String, Vec<(usize, u32)>>,
+}
+
+impl FullTextIndex {
+    pub fn new() -> FullTextIndex {
+        FullTextIndex::default()
+    }
+
+    /// Indexes the text of document `id`. Documents must be added in increasing `id` order.
+    pub fn add_document(&mut self, id: usize, text: &str) {
+        for term in tokenize(text) {
+            let list = self.postings.entry(term).or_insert_with(Vec::new);
+            match list.last_mut() {
+                Some(&mut (last, ref mut count)) if last == id => *count += 1,
+                _ => {
+                    debug_assert!(list.last().map_or(true, |&(last, _)| last < id));
+                    list.push((id, 1));
+                }
+            }
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.postings.is_empty()
+    }
+
+    /// Serializes the index as the JavaScript statement loaded by the search page.
+    pub fn to_js(&self, krate: &str) -> String {
+        let mut terms = Vec::with_capacity(self.postings.len());
+        let mut postings = Vec::with_capacity(self.postings.len());
+        for (term, list) in &self.postings {
+            terms.push(format!("{}", as_json(term)));
+            postings.push(format!("\"{}\"", encode_postings(list)));
+        }
+        format!("fullTextIndex[{}] = {{\"t\":[{}],\"p\":[{}]}};",
+                as_json(&krate),
+                terms.join(","),
+                postings.join(","))
+    }
+}
+
+fn encode_postings(list: &[(usize, u32)]) -> String {
+    let mut out = String::new();
+    let mut prev = 0;
+    for (i, &(id, count)) in list.iter().enumerate() {
+        if i > 0 {
+            out.push(',');
+        }
+        push_base36(&mut out, id - prev);
+        if count > 1 {
+            write!(out, ":{}", count).unwrap();
+        }
+        prev = id;
+    }
+    out
+}
+
+fn push_base36(out: &mut String, mut n: usize) {
+    let mut buf = Vec::new();
+    loop {
+        buf.push(std::char::from_digit((n % 36) as u32, 36).unwrap());
+        n /= 36;
+        if n == 0 {
+            break;
+        }
+    }
+    out.extend(buf.iter().rev());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{stem, tokenize, FullTextIndex};
+    use serialize::json::Json;
+
+    #[test]
+    fn test_stem() {
+        fn t(word: &str, expect: &str) {
+            assert_eq!(stem(word), expect, "original: {}", word);
+        }
+
+        t("iterators", "iterator");
+        t("iterate", "iterat");
+        t("iterated", "iterat");
+        t("iterating", "iterat");
+        t("copies", "copi");
+        t("copy", "copi");
+        t("running", "run");
+        t("classes", "class");
+        t("status", "status");
+        t("calls", "call");
+        t("safely", "safe");
+        t("vec", "vec");
+        t("größe", "größe");
+    }
+
+    #[test]
+    fn test_tokenize() {
+        assert_eq!(tokenize("Returns the `Vec::into_iter` of this slice."),
+                   ["return", "vec", "into", "iter", "slic"]);
+        assert_eq!(tokenize(""), Vec::<String>::new());
+    }
+
+    #[test]
+    fn test_tokenize_cases() {
+        let cases = Json::from_str(include_str!("fulltext-tokenize.json")).unwrap();
+        for case in cases.as_array().unwrap() {
+            let case = case.as_array().unwrap();
+            let input = case[0].as_string().unwrap();
+            let expect: Vec<_> = case[1].as_array().unwrap().iter()
+                .map(|term| term.as_string().unwrap())
+                .collect();
+            assert_eq!(tokenize(input), expect, "original: {}", input);
+        }
+    }
+
+    #[test]
+    fn test_serialize() {
+        let mut index = FullTextIndex::new();
+        index.add_document(0, "Allocates memory.");
+        index.add_document(1, "Frees memory, memory and more memory.");
+        index.add_document(40, "Allocates nothing.");
+        assert_eq!(index.to_js("foo"),
+                   "fullTextIndex[\"foo\"] = {\"t\":[\"allocat\",\"free\",\"memori\",\"more\",\
+                    \"noth\"],\"p\":[\"0,14\",\"1\",\"0,1:3\",\"1\",\"14\"]};");
+    }
+}
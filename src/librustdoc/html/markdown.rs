@@ -607,7 +607,13 @@ pub struct LangString {
     pub compile_fail: bool,
     pub error_codes: Vec<String>,
     pub allow_fail: bool,
-    pub edition: Option<Edition>
+    pub edition: Option<Edition>,
+    /// Target triple fragments from `ignore-<target>` attributes.
+    pub ignore_targets: Vec<String>,
+    /// Target triple fragments from `only-<platform>` attributes.
+    pub only_targets: Vec<String>,
+    /// Names from `requires-feature="..."` attributes.
+    pub requires_features: Vec<String>,
 }
 
 impl LangString {
@@ -623,9 +629,54 @@ impl LangString {
             error_codes: Vec::new(),
             allow_fail: false,
             edition: None,
+            ignore_targets: Vec::new(),
+            only_targets: Vec::new(),
+            requires_features: Vec::new(),
         }
     }
 
+    /// Returns whether the code block should be skipped when running doctests for `target`,
+    /// given the `--cfg` specs the doctests are built with.
+    ///
+    /// A target matches an `ignore-`/`only-` attribute when the attribute's value is a substring
+    /// of the target triple, so `only-windows` and `ignore-x86_64-unknown-linux-gnu` both work.
+    /// A `requires-feature="foo"` attribute is satisfied by either `--cfg foo` or
+    /// `--cfg 'feature="foo"'`.
+    pub fn is_ignored_for(&self, target: &str, cfgs: &[String]) -> bool {
+        if self.ignore || self.ignore_targets.iter().any(|t| target.contains(&t[..])) {
+            return true;
+        }
+        if !self.only_targets.is_empty() &&
+           !self.only_targets.iter().any(|t| target.contains(&t[..])) {
+            return true;
+        }
+        let cfgs: Vec<String> = cfgs.iter()
+            .map(|cfg| cfg.chars().filter(|c| !c.is_whitespace()).collect())
+            .collect();
+        self.requires_features.iter().any(|feature| {
+            let as_feature = format!("feature=\"{}\"", feature);
+            !cfgs.iter().any(|cfg| *cfg == *feature || *cfg == as_feature)
+        })
+    }
+
+    /// Splits a code block's info string into attributes. Separators within double quotes
+    /// don't split, so that `requires-feature="foo"` stays a single token.
+    fn tokens(string: &str) -> Vec<&str> {
+        let mut tokens = Vec::new();
+        let mut start = 0;
+        let mut in_quotes = false;
+        for (i, c) in string.char_indices() {
+            if c == '"' {
+                in_quotes = !in_quotes;
+            } else if !in_quotes && !(c == '_' || c == '-' || c == '=' || c.is_alphanumeric()) {
+                tokens.push(&string[start..i]);
+                start = i + c.len_utf8();
+            }
+        }
+        tokens.push(&string[start..]);
+        tokens
+    }
+
     fn parse(string: &str, allow_error_code_check: ErrorCodes) -> LangString {
         let allow_error_code_check = allow_error_code_check.as_bool();
         let mut seen_rust_tags = false;
@@ -633,11 +684,7 @@ impl LangString {
         let mut data = LangString::all_false();
 
         data.original = string.to_owned();
-        let tokens = string.split(|c: char|
-            !(c == '_' || c == '-' || c.is_alphanumeric())
-        );
-
-        for token in tokens {
+        for token in LangString::tokens(string) {
             match token.trim() {
                 "" => {},
                 "should_panic" => {
@@ -662,6 +709,23 @@ impl LangString {
                     // is needed for edition support
                     data.edition = x[7..].parse::<Edition>().ok();
                 }
+                x if allow_error_code_check && x.starts_with("ignore-") && x.len() > 7 => {
+                    data.ignore_targets.push(x[7..].to_owned());
+                    seen_rust_tags = !seen_other_tags || seen_rust_tags;
+                }
+                x if allow_error_code_check && x.starts_with("only-") && x.len() > 5 => {
+                    data.only_targets.push(x[5..].to_owned());
+                    seen_rust_tags = !seen_other_tags || seen_rust_tags;
+                }
+                x if allow_error_code_check && x.starts_with("requires-feature=") => {
+                    let feature = x[17..].trim_matches('"');
+                    if !feature.is_empty() {
+                        data.requires_features.push(feature.to_owned());
+                        seen_rust_tags = !seen_other_tags || seen_rust_tags;
+                    } else {
+                        seen_other_tags = true;
+                    }
+                }
                 x if allow_error_code_check && x.starts_with("E") && x.len() == 5 => {
                     if x[1..].parse::<u32>().is_ok() {
                         data.error_codes.push(x.to_owned());
@@ -863,6 +927,82 @@ pub fn plain_summary_line_full(md: &str, limit_length: bool) -> String {
     }
 }
 
+/// Renders the whole of `md` as plain text, for use by the full-text search index.
+///
+/// Unlike `plain_summary_line`, every block of the documentation is kept. Code blocks are
+/// dropped since examples would otherwise dominate the index, but inline code spans are kept.
+pub fn plain_text(md: &str) -> String {
+    let mut opts = Options::empty();
+    opts.insert(OPTION_ENABLE_TABLES);
+    opts.insert(OPTION_ENABLE_FOOTNOTES);
+
+    let mut s = String::with_capacity(md.len());
+    let mut in_code_block = false;
+    for event in Parser::new_ext(md, opts) {
+        match event {
+            Event::Start(Tag::CodeBlock(_)) => in_code_block = true,
+            Event::End(Tag::CodeBlock(_)) => in_code_block = false,
+            Event::Text(ref t) if !in_code_block => s.push_str(t),
+            Event::SoftBreak | Event::HardBreak => s.push(' '),
+            Event::End(Tag::Paragraph) | Event::End(Tag::Header(_)) |
+            Event::End(Tag::Item) | Event::End(Tag::TableCell) => s.push('\n'),
+            _ => {}
+        }
+    }
+    s
+}
+
+/// Feeds the Markdown events of `md` to `f`, for renderers of output formats other than HTML.
+///
+/// The events go through the same steps as in `Markdown`, except for the HTML-specific ones:
+/// links are replaced with their resolved destinations, and the hidden `# ` lines of Rust code
+/// blocks are removed.
+crate fn for_each_event<F>(md: &str, links: &[(String, String)], mut f: F)
+    where F: FnMut(Event<'_>)
+{
+    let mut opts = Options::empty();
+    opts.insert(OPTION_ENABLE_TABLES);
+    opts.insert(OPTION_ENABLE_FOOTNOTES);
+
+    let replacer = |_: &str, s: &str| {
+        if let Some(&(_, ref replace)) = links.into_iter().find(|link| &*link.0 == s) {
+            Some((replace.clone(), s.to_owned()))
+        } else {
+            None
+        }
+    };
+
+    let p = Parser::new_with_broken_link_callback(md, opts, Some(&replacer));
+    let mut p = LinkReplacer::new(p, links);
+    while let Some(event) = p.next() {
+        let is_rust = match event {
+            Event::Start(Tag::CodeBlock(ref lang)) => {
+                LangString::parse(&lang, ErrorCodes::No).rust
+            }
+            _ => false,
+        };
+        f(event);
+        if !is_rust {
+            continue;
+        }
+
+        let mut origtext = String::new();
+        for event in &mut p {
+            match event {
+                Event::End(Tag::CodeBlock(..)) => {
+                    let lines = origtext.lines().filter_map(|l| map_line(l).for_html());
+                    let text = lines.collect::<Vec<Cow<'_, str>>>().join("\n");
+                    f(Event::Text(text.into()));
+                    f(event);
+                    break;
+                }
+                Event::Text(ref s) => origtext.push_str(s),
+                _ => {}
+            }
+        }
+    }
+}
+
 pub fn markdown_links(md: &str) -> Vec<(String, Option<Range<usize>>)> {
     if md.is_empty() {
         return vec![];
@@ -1087,7 +1227,7 @@ fn test_unique_id() {
 #[cfg(test)]
 mod tests {
     use super::{ErrorCodes, LangString, Markdown, MarkdownHtml, IdMap};
-    use super::plain_summary_line;
+    use super::{plain_summary_line, plain_text};
     use std::cell::RefCell;
     use syntax::edition::Edition;
 
@@ -1108,6 +1248,9 @@ mod tests {
                 original: s.to_owned(),
                 allow_fail,
                 edition,
+                ignore_targets: Vec::new(),
+                only_targets: Vec::new(),
+                requires_features: Vec::new(),
             })
         }
 
@@ -1137,6 +1280,36 @@ mod tests {
         t("edition2018",           false,         false,   false,   true,  false, false, false, v(), Some(Edition::Edition2018));
     }
 
+    #[test]
+    fn test_lang_string_targets() {
+        fn strs(v: &[&str]) -> Vec<String> {
+            v.iter().map(|s| s.to_string()).collect()
+        }
+
+        let parsed = LangString::parse("ignore-windows, ignore-wasm32,only-x86_64",
+                                       ErrorCodes::Yes);
+        assert!(parsed.rust);
+        assert!(!parsed.ignore);
+        assert_eq!(parsed.ignore_targets, strs(&["windows", "wasm32"]));
+        assert_eq!(parsed.only_targets, strs(&["x86_64"]));
+        assert!(!parsed.is_ignored_for("x86_64-unknown-linux-gnu", &[]));
+        assert!(parsed.is_ignored_for("x86_64-pc-windows-msvc", &[]));
+        assert!(parsed.is_ignored_for("i686-unknown-linux-gnu", &[]));
+
+        let parsed = LangString::parse("rust,requires-feature=\"std\"", ErrorCodes::Yes);
+        assert!(parsed.rust);
+        assert_eq!(parsed.requires_features, strs(&["std"]));
+        assert!(parsed.is_ignored_for("x86_64-unknown-linux-gnu", &[]));
+        assert!(!parsed.is_ignored_for("x86_64-unknown-linux-gnu", &strs(&["std"])));
+        assert!(!parsed.is_ignored_for("x86_64-unknown-linux-gnu",
+                                       &strs(&["feature = \"std\""])));
+        assert!(parsed.is_ignored_for("x86_64-unknown-linux-gnu",
+                                      &strs(&["feature=\"alloc\""])));
+
+        // Without nightly these are unknown attributes, so the block isn't Rust at all.
+        assert!(!LangString::parse("only-linux", ErrorCodes::No).rust);
+    }
+
     #[test]
     fn test_header() {
         fn t(input: &str, expect: &str) {
@@ -1196,6 +1369,19 @@ mod tests {
         t("## header", "header");
     }
 
+    #[test]
+    fn test_plain_text() {
+        fn t(input: &str, expect: &str) {
+            let output = plain_text(input);
+            assert_eq!(output, expect, "original: {}", input);
+        }
+
+        t("hello [Rust](https://www.rust-lang.org) :)", "hello Rust :)\n");
+        t("# Panics\n\nWhen `x` is zero.", "Panics\nWhen x is zero.\n");
+        t("Example:\n\n```\nlet x = 1;\n```\n\nDone.", "Example:\nDone.\n");
+        t("* one\n* two", "one\ntwo\n");
+    }
+
     #[test]
     fn test_markdown_html_escape() {
         fn t(input: &str, expect: &str) {
@@ -64,6 +64,7 @@ use crate::html::format::{AsyncSpace, ConstnessSpace};
 use crate::html::format::{GenericBounds, WhereClause, href, AbiSpace};
 use crate::html::format::{VisSpace, Function, UnsafetySpace, MutableSpace};
 use crate::html::format::fmt_impl_for_trait_page;
+use crate::html::fulltext::FullTextIndex;
 use crate::html::item_type::ItemType;
 use crate::html::markdown::{self, Markdown, MarkdownHtml, MarkdownSummaryLine, ErrorCodes, IdMap};
 use crate::html::{highlight, layout, static_files};
@@ -360,6 +361,10 @@ pub struct Cache {
     /// Aliases added through `#[doc(alias = "...")]`. Since a few items can have the same alias,
     /// we need the alias element to have an array of items.
     aliases: FxHashMap<String, Vec<IndexItem>>,
+
+    /// Whether the plain text of each item's documentation should be kept in the search index
+    /// entries, for building the full-text index.
+    generate_fulltext_index: bool,
 }
 
 /// Temporary storage for data obtained during `RustdocVisitor::clean()`.
@@ -410,6 +415,9 @@ struct IndexItem {
     parent: Option<DefId>,
     parent_idx: Option<usize>,
     search_type: Option<IndexItemFunctionType>,
+    /// Plain text of the whole documentation of the item. Only collected when the full-text
+    /// index is enabled, and dropped once it has been added to that index.
+    fulltext: Option<String>,
 }
 
 impl ToJson for IndexItem {
@@ -519,6 +527,7 @@ pub fn run(mut krate: clean::Crate,
         static_root_path,
         generate_search_filter,
         generate_redirect_pages,
+        enable_fulltext_search,
         ..
     } = options;
 
@@ -638,6 +647,7 @@ pub fn run(mut krate: clean::Crate,
         masked_crates: mem::replace(&mut krate.masked_crates, Default::default()),
         typarams: external_typarams,
         aliases: Default::default(),
+        generate_fulltext_index: enable_fulltext_search,
     };
 
     // Cache where all our extern crates are located
@@ -683,6 +693,11 @@ pub fn run(mut krate: clean::Crate,
 
     // Build our search index
     let index = build_index(&krate, &mut cache);
+    let fulltext_index = if cache.generate_fulltext_index {
+        Some(build_fulltext_index(&mut cache))
+    } else {
+        None
+    };
 
     // Freeze the cache now that the index has been built. Put an Arc into TLS
     // for future parallelization opportunities
@@ -690,7 +705,7 @@ pub fn run(mut krate: clean::Crate,
     CACHE_KEY.with(|v| *v.borrow_mut() = cache.clone());
     CURRENT_LOCATION_KEY.with(|s| s.borrow_mut().clear());
 
-    write_shared(&cx, &krate, &*cache, index, &md_opts, diag)?;
+    write_shared(&cx, &krate, &*cache, index, fulltext_index, &md_opts, diag)?;
 
     // And finally render the whole crate's documentation
     cx.krate(krate)
@@ -704,7 +719,8 @@ fn build_index(krate: &clean::Crate, cache: &mut Cache) -> String {
 
     let Cache { ref mut search_index,
                 ref orphan_impl_items,
-                ref mut paths, .. } = *cache;
+                ref mut paths,
+                ref generate_fulltext_index, .. } = *cache;
 
     // Attach all orphan items to the type's definition if the type
     // has since been learned.
@@ -718,6 +734,7 @@ fn build_index(krate: &clean::Crate, cache: &mut Cache) -> String {
                 parent: Some(did),
                 parent_idx: None,
                 search_type: get_index_search_type(&item),
+                fulltext: fulltext_of(item, *generate_fulltext_index),
             });
         }
     }
@@ -759,6 +776,10 @@ fn build_index(krate: &clean::Crate, cache: &mut Cache) -> String {
     crate_data.insert("doc".to_owned(), Json::String(crate_doc));
     crate_data.insert("i".to_owned(), Json::Array(crate_items));
     crate_data.insert("p".to_owned(), Json::Array(crate_paths));
+    if *generate_fulltext_index {
+        // Tells the search page that `fulltext-index/<crate>.js` exists.
+        crate_data.insert("f".to_owned(), Json::U64(1));
+    }
 
     // Collect the index into a string
     format!("searchIndex[{}] = {};",
@@ -766,11 +787,35 @@ fn build_index(krate: &clean::Crate, cache: &mut Cache) -> String {
             Json::Object(crate_data))
 }
 
+/// Builds the full-text index of the crate's documentation. Must be called after `build_index`,
+/// whose order of the search index entries is used to identify documents.
+///
+/// The text of each item is tokenized into the index and then dropped, so that it doesn't stay
+/// in the cache for the rest of the run.
+fn build_fulltext_index(cache: &mut Cache) -> FullTextIndex {
+    let mut index = FullTextIndex::new();
+    for (id, item) in cache.search_index.iter_mut().enumerate() {
+        if let Some(text) = item.fulltext.take() {
+            index.add_document(id, &text);
+        }
+    }
+    index
+}
+
+fn fulltext_of(item: &clean::Item, generate_fulltext_index: bool) -> Option<String> {
+    if generate_fulltext_index {
+        item.doc_value().map(markdown::plain_text)
+    } else {
+        None
+    }
+}
+
 fn write_shared(
     cx: &Context,
     krate: &clean::Crate,
     cache: &Cache,
     search_index: String,
+    fulltext_index: Option<FullTextIndex>,
     options: &RenderOptions,
     diag: &errors::Handler,
 ) -> Result<(), Error> {
@@ -1128,6 +1173,17 @@ themePicker.onblur = handleThemeButtonsBlur;
              &dst);
     try_err!(write!(&mut w, "initSearch(searchIndex);addSearchOptions(searchIndex);"), &dst);
 
+    // Each crate gets its own full-text index file, so that the search page only loads the
+    // (potentially large) indexes of the crates it actually searches.
+    if let Some(fulltext_index) = fulltext_index {
+        let dir = cx.dst.join("fulltext-index");
+        try_err!(fs::create_dir_all(&dir), &dir);
+        let dst = dir.join(&format!("{}.js", krate.name));
+        let mut w = try_err!(File::create(&dst), &dst);
+        try_err!(writeln!(&mut w, "{}", fulltext_index.to_js(&krate.name)), &dst);
+        try_err!(write!(&mut w, "onFullTextIndexLoad({});", as_json(&krate.name)), &dst);
+    }
+
     if options.enable_index_page {
         if let Some(index_page) = options.index_page.clone() {
             let mut md_opts = options.clone();
@@ -1593,6 +1649,7 @@ impl DocFolder for Cache {
                             parent,
                             parent_idx: None,
                             search_type: get_index_search_type(&item),
+                            fulltext: fulltext_of(&item, self.generate_fulltext_index),
                         });
                     }
                 }
@@ -1787,6 +1844,7 @@ impl<'a> Cache {
                                 parent: None,
                                 parent_idx: None,
                                 search_type: get_index_search_type(&item),
+                                fulltext: None,
                             });
             }
         }
@@ -4151,6 +4209,10 @@ fn render_impl(w: &mut fmt::Formatter<'_>, cx: &Context, i: &Impl, link: AssocIt
                    l, "goto source code")?;
         }
         write!(w, "</h3>")?;
+        if let Some(ref cfg) = i.impl_item.attrs.cfg {
+            write!(w, "<div class='stability'><div class='stab portability'>{}</div></div>",
+                   cfg.render_long_html())?;
+        }
         if let Some(ref dox) = cx.shared.maybe_collapsed_doc_value(&i.impl_item) {
             let mut ids = cx.id_map.borrow_mut();
             write!(w, "<div class='docblock'>{}</div>",
@@ -78,6 +78,7 @@ if (!DOMTokenList.prototype.remove) {
     // 0 for "In Names"
     // 1 for "In Parameters"
     // 2 for "In Return Types"
+    // 3 for "In Documentation" (only with the full-text index)
     var currentTab = 0;
 
     var titleBeforeSearch = document.title;
@@ -424,8 +425,69 @@ if (!DOMTokenList.prototype.remove) {
         return s1_len + s2_len;
     }
 
+    // Words which are not indexed, see `STOP_WORDS` in `html/fulltext.rs`.
+    var FULL_TEXT_STOP_WORDS = ["a", "an", "and", "are", "as", "at", "be", "by", "for", "from",
+                                "if", "in", "is", "it", "its", "of", "on", "or", "that", "the",
+                                "this", "to", "was", "will", "with"];
+
+    // This function must stay in sync with `stem` in `html/fulltext.rs`.
+    function fullTextStem(word) {
+        if (!/^[\x00-\x7f]*$/.test(word) || word.length <= 3) {
+            return word;
+        }
+        var w = word;
+        if (w.endsWith("sses") || w.endsWith("ies")) {
+            w = w.slice(0, -2);
+        } else if (w.endsWith("s") && !w.endsWith("ss") && !w.endsWith("us")) {
+            w = w.slice(0, -1);
+        }
+
+        var suffixes = ["ing", "ed", "ly"];
+        var n;
+        for (var i = 0; i < suffixes.length; ++i) {
+            var suffix = suffixes[i];
+            var stemLen = w.length - suffix.length;
+            if (w.endsWith(suffix) && stemLen >= 3 && /[aeiouy]/.test(w.slice(0, stemLen))) {
+                w = w.slice(0, stemLen);
+                n = w.length;
+                if (w[n - 1] === w[n - 2] && "aeiouylsz".indexOf(w[n - 1]) === -1) {
+                    w = w.slice(0, -1);
+                }
+                break;
+            }
+        }
+
+        n = w.length;
+        if (n > 4 && w[n - 1] === "e") {
+            w = w.slice(0, -1);
+        } else if (n > 3 && w[n - 1] === "y") {
+            w = w.slice(0, -1) + "i";
+        }
+        return w;
+    }
+
+    // See `MAX_TERM_LEN` in `html/fulltext.rs`.
+    var FULL_TEXT_MAX_TERM_LEN = 32;
+
+    // This function must stay in sync with `tokenize` in `html/fulltext.rs`. Both are checked
+    // against the cases in `html/fulltext-tokenize.json`.
+    function fullTextTokenize(text) {
+        return text.toLowerCase()
+                   .split(/[^a-z0-9\u00c0-\uffff]+/)
+                   .filter(function(w) {
+                       return w.length > 0 && w.length <= FULL_TEXT_MAX_TERM_LEN &&
+                           FULL_TEXT_STOP_WORDS.indexOf(w) === -1;
+                   })
+                   .map(fullTextStem);
+    }
+
     function initSearch(rawSearchIndex) {
         var currentResults, index, searchIndex;
+        // Position of each crate's own entry in `searchIndex`. The documents of a full-text
+        // index are numbered from the entry following it.
+        var crateOffsets = {};
+        // Crates whose full-text index has been requested, to only load each file once.
+        var fullTextRequested = {};
         var MAX_LEV_DISTANCE = 3;
         var MAX_RESULTS = 200;
         var GENERICS_DATA = 1;
@@ -1230,10 +1292,11 @@ if (!DOMTokenList.prototype.remove) {
                             actives[currentTab][0].getElementsByTagName("a")[0].href;
                     }
                 } else if (e.which === 9) { // tab
+                    var lastTab = document.getElementById("titles").childNodes.length - 1;
                     if (e.shiftKey) {
-                        printTab(currentTab > 0 ? currentTab - 1 : 2);
+                        printTab(currentTab > 0 ? currentTab - 1 : lastTab);
                     } else {
-                        printTab(currentTab > 1 ? 0 : currentTab + 1);
+                        printTab(currentTab < lastTab ? currentTab + 1 : 0);
                     }
                     e.preventDefault();
                 } else if (e.which === 16) { // shift
@@ -1381,15 +1444,21 @@ if (!DOMTokenList.prototype.remove) {
             var ret_others = addTab(results.others, query);
             var ret_in_args = addTab(results.in_args, query, false);
             var ret_returned = addTab(results.returned, query, false);
+            var hasFullText = hasFullTextIndex();
+            var ret_in_docs = hasFullText ? addTab(results.in_docs, query, false) : ["", 0];
+            if (!hasFullText && currentTab === 3) {
+                currentTab = 0;
+            }
 
             var output = "<h1>Results for " + escape(query.query) +
                 (query.type ? " (type: " + escape(query.type) + ")" : "") + "</h1>" +
-                "<div id=\"titles\">" +
+                "<div id=\"titles\"" + (hasFullText ? " class=\"four-tabs\"" : "") + ">" +
                 makeTabHeader(0, "In Names", ret_others[1]) +
                 makeTabHeader(1, "In Parameters", ret_in_args[1]) +
                 makeTabHeader(2, "In Return Types", ret_returned[1]) +
+                (hasFullText ? makeTabHeader(3, "In Documentation", ret_in_docs[1]) : "") +
                 "</div><div id=\"results\">" +
-                ret_others[0] + ret_in_args[0] + ret_returned[0] + "</div>";
+                ret_others[0] + ret_in_args[0] + ret_returned[0] + ret_in_docs[0] + "</div>";
 
             addClass(main, "hidden");
             var search = document.getElementById("search");
@@ -1409,9 +1478,131 @@ if (!DOMTokenList.prototype.remove) {
             elems[0].onclick = function() { printTab(0); };
             elems[1].onclick = function() { printTab(1); };
             elems[2].onclick = function() { printTab(2); };
+            if (hasFullText) {
+                elems[3].onclick = function() { printTab(3); };
+            }
             printTab(currentTab);
         }
 
+        function hasFullTextIndex() {
+            for (var crate in rawSearchIndex) {
+                if (rawSearchIndex.hasOwnProperty(crate) && rawSearchIndex[crate].f) {
+                    return true;
+                }
+            }
+            return false;
+        }
+
+        // Adds a `<script>` tag for the full-text index of each crate that has one. Going
+        // through a script tag rather than an XHR keeps this working from `file://`.
+        function loadFullTextIndexes(filterCrates) {
+            for (var crate in rawSearchIndex) {
+                if (!rawSearchIndex.hasOwnProperty(crate) || !rawSearchIndex[crate].f ||
+                    fullTextRequested[crate] === true ||
+                    (filterCrates !== undefined && crate !== filterCrates)) {
+                    continue;
+                }
+                fullTextRequested[crate] = true;
+                var script = document.createElement("script");
+                script.src = rootPath + "fulltext-index/" + crate + ".js";
+                document.getElementsByTagName("head")[0].appendChild(script);
+            }
+        }
+
+        // Finds the position of `term` in the sorted array `terms`, or -1.
+        function findTerm(terms, term) {
+            var lo = 0;
+            var hi = terms.length;
+            while (lo < hi) {
+                var mid = (lo + hi) >> 1;
+                if (terms[mid] < term) {
+                    lo = mid + 1;
+                } else {
+                    hi = mid;
+                }
+            }
+            return lo < terms.length && terms[lo] === term ? lo : -1;
+        }
+
+        // Decodes a postings list into an object mapping document ids to their term counts.
+        // See the `fulltext` module in rustdoc for the format.
+        function decodePostings(encoded) {
+            var docs = {};
+            var id = 0;
+            var entries = encoded.split(",");
+            for (var i = 0; i < entries.length; ++i) {
+                var parts = entries[i].split(":");
+                id += parseInt(parts[0], 36);
+                docs[id] = parts.length > 1 ? parseInt(parts[1], 10) : 1;
+            }
+            return docs;
+        }
+
+        // Returns the items whose documentation contains every word of the query, ranked by
+        // the sum of the TF-IDF weights of the words.
+        function execFullTextQuery(query, filterCrates) {
+            var terms = fullTextTokenize(query.query);
+            var out = [];
+            if (terms.length === 0) {
+                return out;
+            }
+            for (var crate in window.fullTextIndex) {
+                if (!window.fullTextIndex.hasOwnProperty(crate) ||
+                    !crateOffsets.hasOwnProperty(crate) ||
+                    (filterCrates !== undefined && crate !== filterCrates)) {
+                    continue;
+                }
+                var ftIndex = window.fullTextIndex[crate];
+                var nbDocs = rawSearchIndex[crate].i.length;
+                var scores = null;
+                for (var i = 0; i < terms.length; ++i) {
+                    var pos = findTerm(ftIndex.t, terms[i]);
+                    if (pos === -1) {
+                        scores = false;
+                        break;
+                    }
+                    var docs = decodePostings(ftIndex.p[pos]);
+                    var idf = Math.log(1 + nbDocs / Object.keys(docs).length);
+                    var newScores = {};
+                    for (var doc in docs) {
+                        if (!docs.hasOwnProperty(doc)) {
+                            continue;
+                        }
+                        if (scores === null) {
+                            newScores[doc] = docs[doc] * idf;
+                        } else if (scores.hasOwnProperty(doc)) {
+                            newScores[doc] = scores[doc] + docs[doc] * idf;
+                        }
+                    }
+                    scores = newScores;
+                }
+                if (!scores) {
+                    continue;
+                }
+                for (doc in scores) {
+                    if (!scores.hasOwnProperty(doc)) {
+                        continue;
+                    }
+                    var item = searchIndex[crateOffsets[crate] + 1 + parseInt(doc, 10)];
+                    var obj = {crate: item.crate, ty: item.ty, name: item.name,
+                               path: item.path, desc: item.desc, parent: item.parent,
+                               score: scores[doc]};
+                    var res = buildHrefAndPath(obj);
+                    obj.displayPath = pathSplitter(res[0]);
+                    obj.fullPath = obj.displayPath + obj.name + "|" + obj.ty;
+                    obj.href = res[1];
+                    out.push(obj);
+                }
+            }
+            out.sort(function(a, b) {
+                if (a.score !== b.score) {
+                    return b.score - a.score;
+                }
+                return a.fullPath < b.fullPath ? -1 : (a.fullPath > b.fullPath ? 1 : 0);
+            });
+            return out.slice(0, MAX_RESULTS);
+        }
+
         function execSearch(query, searchWords, filterCrates) {
             function getSmallest(arrays, positions, notDuplicates) {
                 var start = null;
@@ -1470,17 +1661,25 @@ if (!DOMTokenList.prototype.remove) {
                     results.others.push(tmp.others);
                 }
             }
+            var in_docs = [];
+            if (hasFullTextIndex()) {
+                loadFullTextIndexes(filterCrates);
+                in_docs = execFullTextQuery(getQuery(query.raw.replace(/,/g, " ")),
+                                            filterCrates);
+            }
             if (queries.length > 1) {
                 return {
                     "in_args": mergeArrays(results.in_args),
                     "returned": mergeArrays(results.returned),
                     "others": mergeArrays(results.others),
+                    "in_docs": in_docs,
                 };
             } else {
                 return {
                     "in_args": results.in_args[0],
                     "returned": results.returned[0],
                     "others": results.others[0],
+                    "in_docs": in_docs,
                 };
             }
         }
@@ -1538,6 +1737,7 @@ if (!DOMTokenList.prototype.remove) {
                 if (!rawSearchIndex.hasOwnProperty(crate)) { continue; }
 
                 searchWords.push(crate);
+                crateOffsets[crate] = searchIndex.length;
                 searchIndex.push({
                     crate: crate,
                     ty: 1, // == ExternCrate
@@ -1683,6 +1883,14 @@ if (!DOMTokenList.prototype.remove) {
         index = buildIndex(rawSearchIndex);
         startSearch();
 
+        // Called at the end of each `fulltext-index/<crate>.js` file: refresh the results now
+        // that the "In Documentation" tab can be filled.
+        window.onFullTextIndexLoad = function(crate) {
+            if (search_input.value.trim().length !== 0) {
+                search(undefined, true);
+            }
+        };
+
         // Draw a convenient sidebar of known crates if we have a listing
         if (rootPath === "../" || rootPath === "./") {
             var sidebar = document.getElementsByClassName("sidebar-elems")[0];
@@ -1722,6 +1930,7 @@ if (!DOMTokenList.prototype.remove) {
     }
 
     window.initSearch = initSearch;
+    window.fullTextIndex = {};
 
     // delayed sidebar rendering.
     function initSidebarItems(items) {
@@ -2267,7 +2476,7 @@ if (!DOMTokenList.prototype.remove) {
 
     // In the search display, allows to switch between tabs.
     function printTab(nb) {
-        if (nb === 0 || nb === 1 || nb === 2) {
+        if (nb === 0 || nb === 1 || nb === 2 || nb === 3) {
             currentTab = nb;
         }
         var nb_copy = nb;
@@ -61,12 +61,14 @@ pub mod html {
     crate mod escape;
     crate mod item_type;
     crate mod format;
+    crate mod fulltext;
     crate mod layout;
     pub mod markdown;
     crate mod render;
     crate mod static_files;
     crate mod toc;
 }
+mod man;
 mod markdown;
 mod passes;
 mod visit_ast;
@@ -128,7 +130,7 @@ fn opts() -> Vec<RustcOptGroup> {
                      "[rust]")
         }),
         stable("w", |o| {
-            o.optopt("w", "output-format", "the output type to write", "[html]")
+            o.optopt("w", "output-format", "the output type to write", "[html|man|text]")
         }),
         stable("o", |o| o.optopt("o", "output", "where to place the output", "PATH")),
         stable("crate-name", |o| {
@@ -347,6 +349,12 @@ fn opts() -> Vec<RustcOptGroup> {
                       "generate-redirect-pages",
                       "Generate extra pages to support legacy URLs and tool links")
         }),
+        unstable("enable-fulltext-search", |o| {
+            o.optflag("",
+                      "enable-fulltext-search",
+                      "Generate an index of the documentation text, searchable from the \
+                       \"In Documentation\" tab of the search results")
+        }),
     ]
 }
 
@@ -391,18 +399,25 @@ fn main_args(args: &[String]) -> isize {
     let diag_opts = (options.error_format,
                      options.debugging_options.treat_err_as_bug,
                      options.debugging_options.ui_testing);
+    let output_format = options.output_format;
     rust_input(options, move |out| {
         let Output { krate, passes, renderinfo, renderopts } = out;
         info!("going to format");
         let (error_format, treat_err_as_bug, ui_testing) = diag_opts;
         let diag = core::new_handler(error_format, None, treat_err_as_bug, ui_testing);
-        match html::render::run(
-            krate,
-            renderopts,
-            passes.into_iter().collect(),
-            renderinfo,
-            &diag,
-        ) {
+        let res = match output_format {
+            config::OutputFormat::Html => html::render::run(
+                krate,
+                renderopts,
+                passes.into_iter().collect(),
+                renderinfo,
+                &diag,
+            ),
+            config::OutputFormat::Man | config::OutputFormat::Text => {
+                man::run(krate, &renderopts.output, output_format)
+            }
+        };
+        match res {
             Ok(_) => rustc_driver::EXIT_SUCCESS,
             Err(e) => {
                 diag.struct_err(&format!("couldn't generate documentation: {}", e.error))
@@ -0,0 +1,780 @@
+//! Documentation for reading without a browser: man pages and plain text.
+//!
+//! With `--output-format man` or `--output-format text`, rustdoc writes one page per documented
+//! item instead of the HTML site. Man pages go to `<output>/man3/<path>.3rs`, with the path
+//! separated by dots, so that `man -M <output> std.vec.Vec` works; text pages go to
+//! `<output>/text/<path>.txt` and are meant for a pager.
+//!
+//! Every page has the item's name and summary, its signature, the description from its
+//! documentation, and for types and traits their methods and implementations. Top-level headers
+//! in the documentation (`# Examples`, `# Panics`, ...) become sections of their own. The
+//! Markdown is read with `html::markdown::for_each_event`, so it is interpreted the same way as
+//! for the HTML output. Intra-doc links to items of the crate point to their pages, e.g.
+//! `std.vec.Vec(3rs)`.
+
+use std::fmt::Write as FmtWrite;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use pulldown_cmark::{Event, Tag};
+use rustc::hir;
+use rustc::hir::def_id::DefId;
+use rustc::util::nodemap::{FxHashMap, FxHashSet};
+
+use crate::clean::{self, GetDefId};
+use crate::config::OutputFormat;
+use crate::html::format::{AbiSpace, AsyncSpace, ConstnessSpace, Function, GenericBounds};
+use crate::html::format::{MutableSpace, UnsafetySpace, VisSpace, WhereClause};
+use crate::html::markdown::{for_each_event, plain_summary_line};
+use crate::html::render::Error;
+
+/// Writes the pages for every item of `krate` under `output`.
+pub fn run(krate: clean::Crate, output: &Path, format: OutputFormat) -> Result<(), Error> {
+    let module = match krate.module {
+        Some(ref module) => module,
+        None => return Ok(()),
+    };
+
+    let mut impls = FxHashMap::default();
+    collect_impls(module, &mut impls);
+
+    let dir = output.join(match format {
+        OutputFormat::Man => "man3",
+        _ => "text",
+    });
+    if let Err(e) = fs::create_dir_all(&dir) {
+        return Err(Error::new(e, &dir));
+    }
+
+    let mut names = FxHashMap::default();
+    name_pages(module, &mut vec![krate.name.clone()], &mut names, &mut FxHashSet::default());
+
+    let writer = PageWriter {
+        dir,
+        format,
+        version: krate.version.clone().unwrap_or_default(),
+        impls,
+        names,
+    };
+    writer.item(module)
+}
+
+/// Chooses the name of the page of every item which gets one, e.g. `std::vec::Vec`.
+///
+/// `written` holds the names given out so far, so that a type and a function sharing a name get
+/// pages of their own.
+fn name_pages(item: &clean::Item,
+              path: &mut Vec<String>,
+              names: &mut FxHashMap<DefId, String>,
+              written: &mut FxHashSet<String>) {
+    match item.inner {
+        clean::StrippedItem(..) | clean::ImportItem(..) | clean::ExternCrateItem(..) |
+        clean::ImplItem(..) => return,
+        _ => {}
+    }
+
+    let mut name = path.join("::");
+    if let clean::MacroItem(..) = item.inner {
+        name.push('!');
+    }
+    if !written.insert(name.clone()) {
+        name = format!("{}-{}", name, item.type_());
+        written.insert(name.clone());
+    }
+    names.insert(item.def_id, name);
+
+    if let clean::ModuleItem(ref m) = item.inner {
+        for child in &m.items {
+            if let Some(ref child_name) = child.name {
+                path.push(child_name.clone());
+                name_pages(child, path, names, written);
+                path.pop();
+            }
+        }
+    }
+}
+
+/// The file name of the page `name`, without its extension. Paths are separated by dots rather
+/// than `::`, which isn't allowed in file names on Windows.
+fn file_stem(name: &str) -> String {
+    name.replace("::", ".")
+}
+
+/// Maps types and traits to the impls of the crate which are about them.
+fn collect_impls<'a>(item: &'a clean::Item, impls: &mut FxHashMap<DefId, Vec<&'a clean::Impl>>) {
+    match item.inner {
+        clean::ModuleItem(ref m) => {
+            for item in &m.items {
+                collect_impls(item, impls);
+            }
+        }
+        clean::ImplItem(ref i) => {
+            if let Some(did) = i.for_.def_id() {
+                impls.entry(did).or_insert_with(Vec::new).push(i);
+            }
+            if let Some(did) = i.trait_.def_id() {
+                impls.entry(did).or_insert_with(Vec::new).push(i);
+            }
+        }
+        _ => {}
+    }
+}
+
+struct PageWriter<'a> {
+    dir: PathBuf,
+    format: OutputFormat,
+    version: String,
+    impls: FxHashMap<DefId, Vec<&'a clean::Impl>>,
+    /// The page name of every item which gets a page.
+    names: FxHashMap<DefId, String>,
+}
+
+impl<'a> PageWriter<'a> {
+    fn item(&self, item: &clean::Item) -> Result<(), Error> {
+        let name = match self.names.get(&item.def_id) {
+            Some(name) => name.clone(),
+            None => return Ok(()),
+        };
+        let page = self.page(item, name);
+        let stem = file_stem(&page.name);
+        let (file, contents) = match self.format {
+            OutputFormat::Man => (format!("{}.3rs", stem), page.to_roff(&self.version)),
+            _ => (format!("{}.txt", stem), page.to_text()),
+        };
+        let file = self.dir.join(file);
+        if let Err(e) = fs::write(&file, contents) {
+            return Err(Error::new(e, &file));
+        }
+
+        if let clean::ModuleItem(ref m) = item.inner {
+            for child in m.items.iter().filter(|child| child.name.is_some()) {
+                self.item(child)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the intra-doc links of `item` with the destinations to show for them: the page of
+    /// the target for items of the crate, and the online documentation for primitives. Links to
+    /// other crates are left out and only their text is shown.
+    fn links(&self, item: &clean::Item) -> Vec<(String, String)> {
+        item.attrs.links.iter().filter_map(|&(ref s, did, ref fragment)| {
+            let dest = match did {
+                Some(did) => {
+                    let stem = file_stem(self.names.get(&did)?);
+                    match self.format {
+                        OutputFormat::Man => format!("{}(3rs)", stem),
+                        _ => format!("{}.txt", stem),
+                    }
+                }
+                None => {
+                    let fragment = fragment.as_ref()?;
+                    let tail = fragment.find('#').unwrap_or_else(|| fragment.len());
+                    format!("https://doc.rust-lang.org/nightly/std/primitive.{}.html{}",
+                            &fragment[..tail], &fragment[tail..])
+                }
+            };
+            Some((s.clone(), dest))
+        }).collect()
+    }
+
+    fn page(&self, item: &clean::Item, name: String) -> Page {
+        let docs = item.collapsed_doc_value().unwrap_or_default();
+        let mut page = Page {
+            summary: plain_summary_line(&docs),
+            name,
+            sections: Vec::new(),
+        };
+
+        if let Some(sig) = signature(item) {
+            page.section("SYNOPSIS").push(Block::Code(sig));
+        }
+        page.section("DESCRIPTION");
+        page.markdown(&docs, &self.links(item), false);
+
+        match item.inner {
+            clean::ModuleItem(ref m) => {
+                let mut items = m.items.iter()
+                    .filter(|i| i.name.is_some() && !i.is_stripped() && !i.is_import())
+                    .collect::<Vec<_>>();
+                items.sort_by_key(|i| (i.type_().css_class(), i.name.clone()));
+                if !items.is_empty() {
+                    let section = page.section("ITEMS");
+                    for child in items {
+                        let summary = plain_summary_line(child.doc_value().unwrap_or(""));
+                        section.push(Block::Item(vec![
+                            Span(Style::Strong, format!("{} {}", child.type_(),
+                                                        child.name.as_ref().unwrap())),
+                            Span(Style::Plain, if summary.is_empty() {
+                                String::new()
+                            } else {
+                                format!(": {}", summary)
+                            }),
+                        ]));
+                    }
+                }
+            }
+            clean::StructItem(clean::Struct { ref fields, .. }) |
+            clean::UnionItem(clean::Union { ref fields, .. }) => {
+                page.members("FIELDS", fields, self);
+            }
+            clean::EnumItem(ref e) => {
+                page.members("VARIANTS", e.variants.as_slice(), self);
+            }
+            clean::TraitItem(ref t) => {
+                page.members("REQUIRED ITEMS", &t.items.iter()
+                    .filter(|i| match i.inner {
+                        clean::TyMethodItem(..) | clean::AssociatedTypeItem(_, None) |
+                        clean::AssociatedConstItem(_, None) => true,
+                        _ => false,
+                    })
+                    .cloned()
+                    .collect::<Vec<_>>(), self);
+                page.members("PROVIDED ITEMS", &t.items.iter()
+                    .filter(|i| match i.inner {
+                        clean::MethodItem(..) | clean::AssociatedTypeItem(_, Some(_)) |
+                        clean::AssociatedConstItem(_, Some(_)) => true,
+                        _ => false,
+                    })
+                    .cloned()
+                    .collect::<Vec<_>>(), self);
+            }
+            _ => {}
+        }
+
+        let impls = match self.impls.get(&item.def_id) {
+            Some(impls) => &impls[..],
+            None => &[],
+        };
+        let is_trait = item.is_trait();
+        for i in impls.iter().filter(|i| i.trait_.is_none() && !is_trait) {
+            let items = i.items.iter().filter(|i| !i.is_stripped()).cloned().collect::<Vec<_>>();
+            page.members("METHODS", &items, self);
+        }
+        let trait_impls = impls.iter()
+            .filter(|i| i.trait_.is_some())
+            .map(|i| Block::Code(format!("{:#}", i).replace("&nbsp;", " ").trim().to_owned()))
+            .collect::<Vec<_>>();
+        if !trait_impls.is_empty() {
+            let title = if is_trait { "IMPLEMENTORS" } else { "TRAIT IMPLEMENTATIONS" };
+            page.section(title).extend(trait_impls);
+        }
+
+        page.sections.retain(|s| !s.blocks.is_empty());
+        page
+    }
+}
+
+/// Renders the declaration of `item` as plain Rust code.
+fn signature(item: &clean::Item) -> Option<String> {
+    let name = item.name.as_ref()?;
+    let vis = VisSpace(&item.visibility);
+    let sig = match item.inner {
+        clean::FunctionItem(ref f) | clean::ForeignFunctionItem(ref f) => {
+            fn_signature(&item.visibility, name, &f.header, &f.generics, &f.decl)
+        }
+        clean::MethodItem(ref m) => {
+            fn_signature(&item.visibility, name, &m.header, &m.generics, &m.decl)
+        }
+        clean::TyMethodItem(ref m) => {
+            format!("{};", fn_signature(&None, name, &m.header, &m.generics, &m.decl))
+        }
+        clean::StructItem(ref s) => {
+            format!("{:#}struct {}{:#}{}", vis, name, s.generics,
+                    fields_signature(&s.generics, s.struct_type, &s.fields, s.fields_stripped))
+        }
+        clean::UnionItem(ref u) => {
+            format!("{:#}union {}{:#}{}", vis, name, u.generics,
+                    fields_signature(&u.generics, u.struct_type, &u.fields, u.fields_stripped))
+        }
+        clean::EnumItem(ref e) => {
+            let mut sig = format!("{:#}enum {}{:#}{:#} {{\n", vis, name, e.generics,
+                                  WhereClause { gens: &e.generics, indent: 0, end_newline: true });
+            for variant in &e.variants {
+                if let clean::VariantItem(ref v) = variant.inner {
+                    let name = variant.name.as_ref().unwrap();
+                    match v.kind {
+                        clean::VariantKind::CLike => writeln!(sig, "    {},", name),
+                        clean::VariantKind::Tuple(ref tys) => {
+                            let tys = tys.iter().map(|t| format!("{:#}", t)).collect::<Vec<_>>();
+                            writeln!(sig, "    {}({}),", name, tys.join(", "))
+                        }
+                        clean::VariantKind::Struct(_) => writeln!(sig, "    {} {{ .. }},", name),
+                    }.unwrap();
+                }
+            }
+            if e.variants_stripped {
+                sig.push_str("    // some variants omitted\n");
+            }
+            sig.push('}');
+            sig
+        }
+        clean::TraitItem(ref t) => {
+            let bounds = if t.bounds.is_empty() {
+                String::new()
+            } else {
+                format!(": {:#}", GenericBounds(&t.bounds))
+            };
+            let mut sig = format!("{:#}{:#}{}trait {}{:#}{}{:#} {{\n",
+                                  vis, UnsafetySpace(t.unsafety),
+                                  if t.is_auto { "auto " } else { "" },
+                                  name, t.generics, bounds,
+                                  WhereClause { gens: &t.generics, indent: 0, end_newline: true });
+            for item in &t.items {
+                if let Some(item_sig) = signature(item) {
+                    let item_sig = match item.inner {
+                        clean::MethodItem(..) => format!("{} {{ ... }}", item_sig),
+                        _ => item_sig,
+                    };
+                    for line in item_sig.lines() {
+                        writeln!(sig, "    {}", line).unwrap();
+                    }
+                }
+            }
+            sig.push('}');
+            sig
+        }
+        clean::TypedefItem(ref t, false) => {
+            format!("{:#}type {}{:#}{:#} = {:#};", vis, name, t.generics,
+                    WhereClause { gens: &t.generics, indent: 0, end_newline: false }, t.type_)
+        }
+        clean::TypedefItem(ref t, true) => format!("type {} = {:#};", name, t.type_),
+        clean::ConstantItem(ref c) => {
+            format!("{:#}const {}: {:#} = {};", vis, name, c.type_, c.expr)
+        }
+        clean::StaticItem(ref s) | clean::ForeignStaticItem(ref s) => {
+            format!("{:#}static {}{}: {:#};", vis, MutableSpace(s.mutability), name, s.type_)
+        }
+        clean::AssociatedConstItem(ref ty, ref default) => {
+            match *default {
+                Some(ref default) => format!("const {}: {:#} = {};", name, ty, default),
+                None => format!("const {}: {:#};", name, ty),
+            }
+        }
+        clean::AssociatedTypeItem(ref bounds, ref default) => {
+            let mut sig = format!("type {}", name);
+            if !bounds.is_empty() {
+                write!(sig, ": {:#}", GenericBounds(bounds)).unwrap();
+            }
+            if let Some(ref default) = *default {
+                write!(sig, " = {:#}", default).unwrap();
+            }
+            sig.push(';');
+            sig
+        }
+        clean::StructFieldItem(ref ty) => format!("{:#}{}: {:#}", vis, name, ty),
+        clean::VariantItem(..) => name.clone(),
+        clean::ForeignTypeItem => format!("{:#}type {};", vis, name),
+        clean::MacroItem(ref m) => m.source.clone(),
+        _ => return None,
+    };
+    // The plain-text forms of the HTML formatters still use non-breaking spaces to indent
+    // wrapped argument lists.
+    Some(sig.replace("&nbsp;", " "))
+}
+
+fn fn_signature(vis: &Option<clean::Visibility>, name: &str, header: &hir::FnHeader,
+                generics: &clean::Generics, decl: &clean::FnDecl) -> String {
+    let head = format!("{:#}{}{}{}{:#}fn {}{:#}",
+                       VisSpace(vis),
+                       ConstnessSpace(header.constness),
+                       UnsafetySpace(header.unsafety),
+                       AsyncSpace(header.asyncness),
+                       AbiSpace(header.abi),
+                       name,
+                       generics);
+    format!("{}{:#}{:#}",
+            head,
+            Function { decl, header_len: head.len(), indent: 0, asyncness: header.asyncness },
+            WhereClause { gens: generics, indent: 0, end_newline: false })
+}
+
+fn fields_signature(generics: &clean::Generics, struct_type: crate::doctree::StructType,
+                    fields: &[clean::Item], fields_stripped: bool) -> String {
+    use crate::doctree::StructType;
+
+    let where_clause = WhereClause { gens: generics, indent: 0, end_newline: false };
+    let fields = fields.iter().filter_map(|f| match f.inner {
+        clean::StructFieldItem(ref ty) => Some((f, ty)),
+        _ => None,
+    });
+    match struct_type {
+        StructType::Plain => {
+            let mut sig = format!("{:#} {{\n", where_clause);
+            for (field, ty) in fields {
+                writeln!(sig, "    {:#}{}: {:#},", VisSpace(&field.visibility),
+                         field.name.as_ref().unwrap(), ty).unwrap();
+            }
+            if fields_stripped {
+                sig.push_str("    // some fields omitted\n");
+            }
+            sig.push('}');
+            sig
+        }
+        StructType::Tuple => {
+            let fields = fields.map(|(field, ty)| format!("{:#}{:#}",
+                                                         VisSpace(&field.visibility), ty))
+                .collect::<Vec<_>>();
+            format!("({}){:#};", fields.join(", "), where_clause)
+        }
+        StructType::Unit => format!("{:#};", where_clause),
+    }
+}
+
+/// A page of documentation, independent of the output format.
+struct Page {
+    /// The full path of the item, e.g. `std::vec::Vec`.
+    name: String,
+    summary: String,
+    sections: Vec<Section>,
+}
+
+struct Section {
+    title: String,
+    blocks: Vec<Block>,
+}
+
+enum Block {
+    Paragraph(Vec<Span>),
+    /// A paragraph in a bulleted list.
+    Item(Vec<Span>),
+    Heading(String),
+    /// Preformatted text, shown without reflowing.
+    Code(String),
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Style {
+    Plain,
+    Code,
+    Emphasis,
+    Strong,
+}
+
+struct Span(Style, String);
+
+impl Page {
+    /// Returns the blocks of the section named `title`, adding it at the end if needed.
+    fn section(&mut self, title: &str) -> &mut Vec<Block> {
+        match self.sections.iter().position(|s| s.title == title) {
+            Some(i) => &mut self.sections[i].blocks,
+            None => {
+                self.sections.push(Section { title: title.to_owned(), blocks: Vec::new() });
+                &mut self.sections.last_mut().unwrap().blocks
+            }
+        }
+    }
+
+    /// Adds one entry per item to the section `title`, with its signature and documentation.
+    fn members(&mut self, title: &str, items: &[clean::Item], writer: &PageWriter<'_>) {
+        for item in items.iter().filter(|i| !i.is_stripped()) {
+            let sig = match signature(item) {
+                Some(sig) => sig,
+                None => continue,
+            };
+            self.section(title).push(Block::Code(sig));
+            if let Some(docs) = item.collapsed_doc_value() {
+                self.markdown(&docs, &writer.links(item), true);
+            }
+        }
+    }
+
+    /// Appends the rendered Markdown `md` to the last section, with `item_links` as the resolved
+    /// intra-doc links. Top-level headers start new sections unless `nested` is set.
+    fn markdown(&mut self, md: &str, item_links: &[(String, String)], nested: bool) {
+        let mut blocks = Vec::new();
+        let mut spans: Vec<Span> = Vec::new();
+        let mut text = String::new();
+        let mut styles = vec![Style::Plain];
+        let mut links = Vec::new();
+        let mut in_item = false;
+        let mut in_code_block = false;
+
+        for_each_event(md, item_links, |event| match event {
+            Event::Start(Tag::Header(_)) | Event::Start(Tag::CodeBlock(_)) => {
+                flush(&mut blocks, &mut spans, in_item);
+                in_code_block = true;
+                text.clear();
+            }
+            Event::End(Tag::Header(level)) => {
+                in_code_block = false;
+                if level == 1 && !nested {
+                    blocks.push(Block::Heading(text.to_uppercase()));
+                    blocks.push(Block::Heading(String::new()));
+                } else {
+                    blocks.push(Block::Heading(text.clone()));
+                }
+            }
+            Event::End(Tag::CodeBlock(_)) => {
+                in_code_block = false;
+                blocks.push(Block::Code(text.trim_end().to_owned()));
+            }
+            Event::Text(ref t) if in_code_block => text.push_str(t),
+            Event::Start(Tag::Item) => {
+                flush(&mut blocks, &mut spans, in_item);
+                in_item = true;
+            }
+            Event::End(Tag::Item) => {
+                flush(&mut blocks, &mut spans, in_item);
+                in_item = false;
+            }
+            Event::End(Tag::Paragraph) | Event::End(Tag::TableRow) |
+            Event::End(Tag::TableHead) => {
+                flush(&mut blocks, &mut spans, in_item);
+                // Only the first paragraph of a list item gets the bullet.
+                in_item = false;
+            }
+            Event::End(Tag::TableCell) => spans.push(Span(Style::Plain, " | ".to_owned())),
+            Event::Start(Tag::Code) => styles.push(Style::Code),
+            Event::Start(Tag::Emphasis) => styles.push(Style::Emphasis),
+            Event::Start(Tag::Strong) => styles.push(Style::Strong),
+            Event::End(Tag::Code) | Event::End(Tag::Emphasis) | Event::End(Tag::Strong) => {
+                styles.pop();
+            }
+            Event::Start(Tag::Link(dest, _)) => links.push(dest.into_owned()),
+            Event::End(Tag::Link(..)) => {
+                if let Some(dest) = links.pop() {
+                    if dest.starts_with("http://") || dest.starts_with("https://") {
+                        spans.push(Span(Style::Plain, format!(" <{}>", dest)));
+                    } else if item_links.iter().any(|link| link.1 == dest) {
+                        spans.push(Span(Style::Plain, format!(" (see {})", dest)));
+                    }
+                }
+            }
+            Event::Text(t) => spans.push(Span(*styles.last().unwrap(), t.into_owned())),
+            Event::SoftBreak | Event::HardBreak => spans.push(Span(Style::Plain, " ".to_owned())),
+            Event::FootnoteReference(name) => {
+                spans.push(Span(Style::Plain, format!("[{}]", name)));
+            }
+            _ => {}
+        });
+        flush(&mut blocks, &mut spans, in_item);
+
+        // A top-level header was pushed as its title followed by an empty marker heading.
+        let mut blocks = blocks.into_iter().peekable();
+        while let Some(block) = blocks.next() {
+            let starts_section = match (&block, blocks.peek()) {
+                (Block::Heading(_), Some(Block::Heading(marker))) => marker.is_empty(),
+                _ => false,
+            };
+            if starts_section {
+                blocks.next();
+                if let Block::Heading(title) = block {
+                    self.section(&title);
+                    // Move the new section to the end, even if it already existed.
+                    let i = self.sections.iter().position(|s| s.title == title).unwrap();
+                    let section = self.sections.remove(i);
+                    self.sections.push(section);
+                }
+            } else {
+                let last = self.sections.len() - 1;
+                self.sections[last].blocks.push(block);
+            }
+        }
+    }
+
+    fn to_roff(&self, version: &str) -> String {
+        let mut out = format!(".TH \"{}\" 3 \"\" \"{}\" \"Rust Library Documentation\"\n",
+                              roff_escape(&self.name), roff_escape(version));
+        out.push_str(".SH NAME\n");
+        if self.summary.is_empty() {
+            writeln!(out, "{}", roff_escape(&self.name)).unwrap();
+        } else {
+            writeln!(out, "{} \\- {}", roff_escape(&self.name), roff_escape(&self.summary))
+                .unwrap();
+        }
+        for section in &self.sections {
+            writeln!(out, ".SH {}", roff_escape(&section.title)).unwrap();
+            for block in &section.blocks {
+                match *block {
+                    Block::Paragraph(ref spans) => {
+                        writeln!(out, ".PP\n{}", roff_spans(spans)).unwrap();
+                    }
+                    Block::Item(ref spans) => {
+                        writeln!(out, ".IP \\(bu 2\n{}", roff_spans(spans)).unwrap();
+                    }
+                    Block::Heading(ref text) => {
+                        writeln!(out, ".SS {}", roff_escape(text)).unwrap();
+                    }
+                    Block::Code(ref code) => {
+                        out.push_str(".PP\n.RS 4\n.nf\n");
+                        for line in code.lines() {
+                            writeln!(out, "{}", roff_line(line)).unwrap();
+                        }
+                        out.push_str(".fi\n.RE\n");
+                    }
+                }
+            }
+        }
+        out
+    }
+
+    fn to_text(&self) -> String {
+        let mut out = String::from("NAME\n");
+        if self.summary.is_empty() {
+            writeln!(out, "    {}", self.name).unwrap();
+        } else {
+            writeln!(out, "    {} - {}", self.name, self.summary).unwrap();
+        }
+        for section in &self.sections {
+            writeln!(out, "\n{}", section.title).unwrap();
+            for block in &section.blocks {
+                match *block {
+                    Block::Paragraph(ref spans) => {
+                        out.push('\n');
+                        wrap(&mut out, &text_spans(spans), "    ", "    ");
+                    }
+                    Block::Item(ref spans) => {
+                        out.push('\n');
+                        wrap(&mut out, &text_spans(spans), "    * ", "      ");
+                    }
+                    Block::Heading(ref text) => writeln!(out, "\n  {}", text).unwrap(),
+                    Block::Code(ref code) => {
+                        out.push('\n');
+                        for line in code.lines() {
+                            writeln!(out, "        {}", line).unwrap();
+                        }
+                    }
+                }
+            }
+        }
+        out
+    }
+}
+
+/// Turns the collected inline spans into a block, if there are any.
+fn flush(blocks: &mut Vec<Block>, spans: &mut Vec<Span>, in_item: bool) {
+    if spans.iter().all(|s| s.1.trim().is_empty()) {
+        spans.clear();
+        return;
+    }
+    let spans = spans.drain(..).collect();
+    blocks.push(if in_item { Block::Item(spans) } else { Block::Paragraph(spans) });
+}
+
+/// Escapes text for use within a roff line.
+fn roff_escape(s: &str) -> String {
+    s.replace('\\', "\\e").replace('-', "\\-")
+}
+
+/// Escapes a whole line of roff input, so that it isn't taken for a request.
+fn roff_line(line: &str) -> String {
+    let line = roff_escape(line);
+    if line.starts_with('.') || line.starts_with('\'') {
+        format!("\\&{}", line)
+    } else {
+        line
+    }
+}
+
+fn roff_spans(spans: &[Span]) -> String {
+    let mut out = String::new();
+    for &Span(style, ref text) in spans {
+        let text = roff_escape(text);
+        match style {
+            Style::Plain => out.push_str(&text),
+            Style::Code | Style::Strong => write!(out, "\\fB{}\\fR", text).unwrap(),
+            Style::Emphasis => write!(out, "\\fI{}\\fR", text).unwrap(),
+        }
+    }
+    roff_line(out.trim())
+}
+
+fn text_spans(spans: &[Span]) -> String {
+    let mut out = String::new();
+    for &Span(style, ref text) in spans {
+        match style {
+            Style::Code => write!(out, "`{}`", text).unwrap(),
+            _ => out.push_str(text),
+        }
+    }
+    out
+}
+
+/// Reflows `text` to lines of at most 80 columns, starting with `first` and continuing with
+/// `rest` as indentation.
+fn wrap(out: &mut String, text: &str, first: &str, rest: &str) {
+    const WIDTH: usize = 80;
+
+    let mut line = String::from(first);
+    let mut empty = true;
+    for word in text.split_whitespace() {
+        if !empty && line.chars().count() + 1 + word.chars().count() > WIDTH {
+            out.push_str(&line);
+            out.push('\n');
+            line = String::from(rest);
+            empty = true;
+        }
+        if !empty {
+            line.push(' ');
+        }
+        line.push_str(word);
+        empty = false;
+    }
+    out.push_str(&line);
+    out.push('\n');
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Block, Page, Style, wrap};
+
+    fn page(md: &str) -> Page {
+        let mut page = Page {
+            name: "foo::bar".to_owned(),
+            summary: "Does things.".to_owned(),
+            sections: Vec::new(),
+        };
+        page.section("DESCRIPTION");
+        page.markdown(md, &[("Baz".to_owned(), "foo.Baz(3rs)".to_owned())], false);
+        page
+    }
+
+    #[test]
+    fn test_sections() {
+        let page = page("Does things.\n\n# Examples\n\n```\n# let x = 1;\nbar(x);\n```\n\n\
+                         ## Nested\n\n* one `1`\n* two\n");
+        let titles = page.sections.iter().map(|s| &s.title[..]).collect::<Vec<_>>();
+        assert_eq!(titles, ["DESCRIPTION", "EXAMPLES"]);
+        let examples = &page.sections[1].blocks;
+        match examples[0] {
+            Block::Code(ref code) => assert_eq!(code, "bar(x);"),
+            _ => panic!("expected a code block"),
+        }
+        match examples[1] {
+            Block::Heading(ref text) => assert_eq!(text, "Nested"),
+            _ => panic!("expected a heading"),
+        }
+        match examples[2] {
+            Block::Item(ref spans) => {
+                assert!(spans.iter().any(|s| s.0 == Style::Code && s.1 == "1"));
+            }
+            _ => panic!("expected a list item"),
+        }
+    }
+
+    #[test]
+    fn test_roff() {
+        let roff = page("Uses `a-b`.\n\n.hidden dot").to_roff("1.0");
+        assert_eq!(roff, ".TH \"foo::bar\" 3 \"\" \"1.0\" \"Rust Library Documentation\"\n\
+                          .SH NAME\nfoo::bar \\- Does things.\n\
+                          .SH DESCRIPTION\n.PP\nUses \\fBa\\-b\\fR.\n\
+                          .PP\n\\&.hidden dot\n");
+    }
+
+    #[test]
+    fn test_text() {
+        let text = page("*Very* `useful`.").to_text();
+        assert_eq!(text, "NAME\n    foo::bar - Does things.\n\n\
+                          DESCRIPTION\n\n    Very `useful`.\n");
+
+        let text = page("Makes a [`Baz`].").to_text();
+        assert_eq!(text, "NAME\n    foo::bar - Does things.\n\n\
+                          DESCRIPTION\n\n    Makes a `Baz` (see foo.Baz(3rs)).\n");
+
+        let mut out = String::new();
+        wrap(&mut out, &"word ".repeat(20), "    * ", "      ");
+        assert_eq!(out, format!("    * {}\n      {}\n", ["word"; 14].join(" "),
+                                ["word"; 6].join(" ")));
+    }
+}
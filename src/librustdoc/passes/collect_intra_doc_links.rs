@@ -1,13 +1,12 @@
+use errors::{Applicability, DiagnosticBuilder};
 use rustc::lint as lint;
-use rustc::hir;
 use rustc::hir::def::Def;
 use rustc::hir::def_id::DefId;
 use rustc::ty;
-use syntax;
 use syntax::ast::{self, Ident, NodeId};
 use syntax::feature_gate::UnstableFeatures;
 use syntax::symbol::Symbol;
-use syntax_pos::DUMMY_SP;
+use syntax_pos::{Span, DUMMY_SP};
 
 use std::ops::Range;
 
@@ -33,7 +32,7 @@ pub fn collect_intra_doc_links(krate: Crate, cx: &DocContext<'_, '_, '_>) -> Cra
     }
 }
 
-#[derive(Debug)]
+#[derive(Clone, Copy, Debug, PartialEq)]
 enum PathKind {
     /// Either a value or type, but not a macro
     Unknown,
@@ -45,6 +44,44 @@ enum PathKind {
     Type,
 }
 
+/// What an intra-doc link resolved to.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Target {
+    /// An item known to the resolver.
+    Def(Def),
+    /// A primitive type. These are documented on the `primitive.*.html` pages of `std`, whose
+    /// URL is built from the fragment of the link.
+    Primitive,
+}
+
+/// The disambiguators that can be written in front of a link, as in `struct@Foo`, along with
+/// the namespace they select and the kinds of item (as returned by `link_kind`) they accept.
+/// An empty list of kinds accepts anything found in the namespace.
+const DISAMBIGUATORS: &[(&str, PathKind, &[&str])] = &[
+    ("struct", PathKind::Type, &["struct"]),
+    ("enum", PathKind::Type, &["enum"]),
+    ("trait", PathKind::Type, &["trait"]),
+    ("union", PathKind::Type, &["union"]),
+    ("type", PathKind::Type, &[]),
+    ("mod", PathKind::Type, &["mod"]),
+    ("module", PathKind::Type, &["mod"]),
+    ("prim", PathKind::Type, &["primitive"]),
+    ("primitive", PathKind::Type, &["primitive"]),
+    ("const", PathKind::Value, &["constant", "associatedconstant"]),
+    ("constant", PathKind::Value, &["constant", "associatedconstant"]),
+    ("static", PathKind::Value, &["static"]),
+    ("fn", PathKind::Value, &["fn", "method", "tymethod"]),
+    ("function", PathKind::Value, &["fn", "method", "tymethod"]),
+    ("method", PathKind::Value, &["fn", "method", "tymethod"]),
+    ("value", PathKind::Value, &[]),
+    ("variant", PathKind::Value, &["variant"]),
+    ("field", PathKind::Value, &["structfield"]),
+    ("macro", PathKind::Macro, &["macro"]),
+];
+
+/// The kinds of item accepted by the `name()` suffix, mirroring `fn@`.
+const FN_KINDS: &[&str] = &["fn", "method", "tymethod"];
+
 struct LinkCollector<'a, 'tcx: 'a, 'rcx: 'a> {
     cx: &'a DocContext<'a, 'tcx, 'rcx>,
     mod_ids: Vec<NodeId>,
@@ -62,13 +99,13 @@ impl<'a, 'tcx, 'rcx> LinkCollector<'a, 'tcx, 'rcx> {
 
     /// Resolves a given string as a path, along with whether or not it is
     /// in the value namespace. Also returns an optional URL fragment in the case
-    /// of variants and methods.
+    /// of variants, fields and associated items.
     fn resolve(&self,
                path_str: &str,
                is_val: bool,
                current_item: &Option<String>,
                parent_id: Option<NodeId>)
-        -> Result<(Def, Option<String>), ()>
+        -> Result<(Target, Option<String>), ()>
     {
         let cx = self.cx;
 
@@ -89,24 +126,25 @@ impl<'a, 'tcx, 'rcx> LinkCollector<'a, 'tcx, 'rcx> {
                 let value = match result.def {
                     Def::Method(_) | Def::AssociatedConst(_) => true,
                     Def::AssociatedTy(_) => false,
-                    Def::Variant(_) => return handle_variant(cx, result.def),
+                    Def::Variant(_) | Def::VariantCtor(..) => {
+                        return handle_variant(cx, result.def)
+                    }
+                    Def::PrimTy(_) => {
+                        return Ok((Target::Primitive, Some(path_str.to_owned())))
+                    }
                     // Not a trait item; just return what we found.
-                    _ => return Ok((result.def, None))
+                    _ => return Ok((Target::Def(result.def), None))
                 };
 
                 if value != is_val {
                     return Err(())
                 }
-            } else if let Some(prim) = is_primitive(path_str, is_val) {
-                return Ok((prim, Some(path_str.to_owned())))
-            } else {
-                // If resolution failed, it may still be a method
-                // because methods are not handled by the resolver
-                // If so, bail when we're not looking for a value.
-                if !is_val {
-                    return Err(())
-                }
+            } else if is_primitive(path_str, is_val) {
+                return Ok((Target::Primitive, Some(path_str.to_owned())))
             }
+            // If resolution failed, it may still be an associated item, a field or a
+            // variant, none of which are handled by the resolver. Associated types live in
+            // the type namespace, so keep looking even when we're not looking for a value.
 
             // Try looking for methods and associated items.
             let mut split = path_str.rsplitn(2, "::");
@@ -127,15 +165,18 @@ impl<'a, 'tcx, 'rcx> LinkCollector<'a, 'tcx, 'rcx> {
                     path = name.clone();
                 }
             }
-            if let Some(prim) = is_primitive(&path, false) {
-                let did = primitive_impl(cx, &path).ok_or(())?;
-                return cx.tcx.associated_items(did)
+            if is_primitive(&path, false) {
+                return primitive_impls(cx, &path).into_iter()
+                    .flat_map(|did| cx.tcx.associated_items(did))
                     .find(|item| item.ident.name == item_name)
                     .and_then(|item| match item.kind {
-                        ty::AssociatedKind::Method => Some("method"),
+                        ty::AssociatedKind::Method if is_val => Some("method"),
+                        ty::AssociatedKind::Const if is_val => Some("associatedconstant"),
                         _ => None,
                     })
-                    .map(|out| (prim, Some(format!("{}#{}.{}", path, out, item_name))))
+                    .map(|out| {
+                        (Target::Primitive, Some(format!("{}#{}.{}", path, out, item_name)))
+                    })
                     .ok_or(());
             }
 
@@ -157,34 +198,50 @@ impl<'a, 'tcx, 'rcx> LinkCollector<'a, 'tcx, 'rcx> {
                             ty::AssociatedKind::Const if is_val => "associatedconstant",
                             _ => return Err(())
                         };
-                        Ok((ty.def, Some(format!("{}.{}", out, item_name))))
+                        Ok((Target::Def(ty.def), Some(format!("{}.{}", out, item_name))))
+                    } else if !is_val {
+                        // Fields and variants are only looked up as values, so that they
+                        // aren't reported as ambiguous with themselves.
+                        Err(())
                     } else {
                         match cx.tcx.type_of(did).sty {
                             ty::Adt(def, _) => {
-                                if let Some(item) = if def.is_enum() {
-                                    def.all_fields().find(|item| item.ident.name == item_name)
+                                if def.is_enum() {
+                                    def.variants.iter()
+                                        .find(|variant| variant.ident.name == item_name)
+                                        .map(|variant| {
+                                            (Target::Def(ty.def),
+                                             Some(format!("variant.{}", variant.ident)))
+                                        })
+                                        .ok_or(())
                                 } else {
                                     def.non_enum_variant()
                                        .fields
                                        .iter()
-                                       .find(|item| item.ident.name == item_name)
-                                } {
-                                    Ok((ty.def,
-                                        Some(format!("{}.{}",
-                                                     if def.is_enum() {
-                                                         "variant"
-                                                     } else {
-                                                         "structfield"
-                                                     },
-                                                     item.ident))))
-                                } else {
-                                    Err(())
+                                       .find(|field| field.ident.name == item_name)
+                                       .map(|field| {
+                                           (Target::Def(ty.def),
+                                            Some(format!("structfield.{}", field.ident)))
+                                       })
+                                       .ok_or(())
                                 }
                             }
                             _ => Err(()),
                         }
                     }
                 }
+                Def::Variant(did) if is_val => {
+                    // A field of a struct-like variant, e.g. `Enum::Variant::field`.
+                    use rustc::ty::DefIdTree;
+
+                    let variant = cx.tcx.expect_variant_def(ty.def);
+                    let field = variant.fields.iter()
+                                       .find(|field| field.ident.name == item_name)
+                                       .ok_or(())?;
+                    let parent = cx.tcx.parent(did).ok_or(())?;
+                    Ok((Target::Def(Def::Enum(parent)),
+                        Some(format!("variant.{}.field.{}", variant.ident, field.ident))))
+                }
                 Def::Trait(did) => {
                     let item = cx.tcx.associated_item_def_ids(did).iter()
                                  .map(|item| cx.tcx.associated_item(*item))
@@ -203,7 +260,7 @@ impl<'a, 'tcx, 'rcx> LinkCollector<'a, 'tcx, 'rcx> {
                             _ => return Err(())
                         };
 
-                        Ok((ty.def, Some(format!("{}.{}", kind, item_name))))
+                        Ok((Target::Def(ty.def), Some(format!("{}.{}", kind, item_name))))
                     } else {
                         Err(())
                     }
@@ -214,6 +271,70 @@ impl<'a, 'tcx, 'rcx> LinkCollector<'a, 'tcx, 'rcx> {
             Err(())
         }
     }
+
+    /// Resolves `path_str` in the namespace selected by `kind`. For `PathKind::Unknown`, the
+    /// type namespace is tried first.
+    fn resolve_in(&self,
+                  path_str: &str,
+                  kind: PathKind,
+                  current_item: &Option<String>,
+                  parent_id: Option<NodeId>)
+        -> Option<(Target, Option<String>)>
+    {
+        match kind {
+            PathKind::Macro => macro_resolve(self.cx, path_str).map(|def| (Target::Def(def), None)),
+            PathKind::Type => self.resolve(path_str, false, current_item, parent_id).ok(),
+            PathKind::Value => self.resolve(path_str, true, current_item, parent_id).ok(),
+            PathKind::Unknown => {
+                self.resolve_in(path_str, PathKind::Type, current_item, parent_id)
+                    .or_else(|| self.resolve_in(path_str, PathKind::Value, current_item, parent_id))
+            }
+        }
+    }
+
+    /// Explains why `path_str` failed to resolve in the namespace selected by `kind`, and
+    /// returns the kinds of item it resolves to in the other namespaces.
+    fn failure_hints(&self,
+                     path_str: &str,
+                     kind: PathKind,
+                     current_item: &Option<String>,
+                     parent_id: Option<NodeId>)
+        -> (Vec<String>, Vec<&'static str>)
+    {
+        let mut notes = vec![];
+        let mut suggestions = vec![];
+
+        for &other in &[PathKind::Type, PathKind::Value, PathKind::Macro] {
+            if other == kind || (kind == PathKind::Unknown && other != PathKind::Macro) {
+                continue;
+            }
+            if let Some((target, fragment)) =
+                self.resolve_in(path_str, other, current_item, parent_id)
+            {
+                let found = link_kind(target, &fragment);
+                notes.push(format!("there is {} {} named `{}`", article(found), found, path_str));
+                suggestions.push(found);
+            }
+        }
+
+        if suggestions.is_empty() {
+            if let Some(pos) = path_str.rfind("::") {
+                let (parent, name) = (&path_str[..pos], &path_str[pos + 2..]);
+                if let Some((target, fragment)) =
+                    self.resolve_in(parent, PathKind::Type, current_item, parent_id)
+                {
+                    let found = link_kind(target, &fragment);
+                    notes.push(format!("{} `{}` has no field, variant or associated item \
+                                        named `{}`",
+                                       found, parent, name));
+                } else {
+                    notes.push(format!("no item named `{}` is in scope", parent));
+                }
+            }
+        }
+
+        (notes, suggestions)
+    }
 }
 
 impl<'a, 'tcx, 'rcx> DocFolder for LinkCollector<'a, 'tcx, 'rcx> {
@@ -290,131 +411,97 @@ impl<'a, 'tcx, 'rcx> DocFolder for LinkCollector<'a, 'tcx, 'rcx> {
                 continue;
             }
             let link = ori_link.replace("`", "");
-            let (def, fragment) = {
-                let mut kind = PathKind::Unknown;
-                let path_str = if let Some(prefix) =
-                    ["struct@", "enum@", "type@",
-                     "trait@", "union@"].iter()
-                                      .find(|p| link.starts_with(**p)) {
-                    kind = PathKind::Type;
-                    link.trim_start_matches(prefix)
-                } else if let Some(prefix) =
-                    ["const@", "static@",
-                     "value@", "function@", "mod@",
-                     "fn@", "module@", "method@"]
-                        .iter().find(|p| link.starts_with(**p)) {
-                    kind = PathKind::Value;
-                    link.trim_start_matches(prefix)
-                } else if link.ends_with("()") {
-                    kind = PathKind::Value;
-                    link.trim_end_matches("()")
-                } else if link.starts_with("macro@") {
-                    kind = PathKind::Macro;
-                    link.trim_start_matches("macro@")
-                } else if link.ends_with('!') {
-                    kind = PathKind::Macro;
-                    link.trim_end_matches('!')
-                } else {
-                    &link[..]
-                }.trim();
+            let (kind, allowed_kinds, disambiguator, path_str) = parse_disambiguator(&link);
+            let path_str = path_str.trim();
 
-                if path_str.contains(|ch: char| !(ch.is_alphanumeric() ||
-                                                  ch == ':' || ch == '_')) {
-                    continue;
-                }
+            if path_str.contains(|ch: char| !(ch.is_alphanumeric() ||
+                                              ch == ':' || ch == '_')) {
+                continue;
+            }
 
-                match kind {
-                    PathKind::Value => {
-                        if let Ok(def) = self.resolve(path_str, true, &current_item, parent_node) {
-                            def
-                        } else {
-                            resolution_failure(cx, &item.attrs, path_str, &dox, link_range);
-                            // This could just be a normal link or a broken link
-                            // we could potentially check if something is
-                            // "intra-doc-link-like" and warn in that case.
-                            continue;
-                        }
+            let (target, fragment) = match kind {
+                PathKind::Unknown => {
+                    // Try everything!
+                    let type_res = self.resolve(path_str, false, &current_item, parent_node).ok();
+                    let value_res = self.resolve(path_str, true, &current_item, parent_node).ok();
+                    let macro_res = macro_resolve(cx, path_str)
+                        .map(|def| (Target::Def(def), None));
+
+                    // It is imperative we search for not-a-value first
+                    // Otherwise we will find struct ctors for when we are looking
+                    // for structs, and the link won't work if there is something in
+                    // both namespaces.
+                    let mut candidates = vec![];
+                    if let Some(res) = type_res.clone() {
+                        candidates.push(res);
                     }
-                    PathKind::Type => {
-                        if let Ok(def) = self.resolve(path_str, false, &current_item, parent_node) {
-                            def
-                        } else {
-                            resolution_failure(cx, &item.attrs, path_str, &dox, link_range);
-                            // This could just be a normal link.
-                            continue;
+                    if let Some(res) = value_res {
+                        // Structs, variants and modules are found in both namespaces; only
+                        // count them once.
+                        if Some(&res) != type_res.as_ref() &&
+                           (value_ns_kind(res.0).is_some() || type_res.is_none()) {
+                            candidates.push(res);
                         }
                     }
-                    PathKind::Unknown => {
-                        // Try everything!
-                        if let Some(macro_def) = macro_resolve(cx, path_str) {
-                            if let Ok(type_def) =
-                                self.resolve(path_str, false, &current_item, parent_node)
-                            {
-                                let (type_kind, article, type_disambig)
-                                    = type_ns_kind(type_def.0, path_str);
-                                ambiguity_error(cx, &item.attrs, path_str,
-                                                article, type_kind, &type_disambig,
-                                                "a", "macro", &format!("macro@{}", path_str));
-                                continue;
-                            } else if let Ok(value_def) =
-                                self.resolve(path_str, true, &current_item, parent_node)
-                            {
-                                let (value_kind, value_disambig)
-                                    = value_ns_kind(value_def.0, path_str)
-                                        .expect("struct and mod cases should have been \
-                                                 caught in previous branch");
-                                ambiguity_error(cx, &item.attrs, path_str,
-                                                "a", value_kind, &value_disambig,
-                                                "a", "macro", &format!("macro@{}", path_str));
-                            }
-                            (macro_def, None)
-                        } else if let Ok(type_def) =
-                            self.resolve(path_str, false, &current_item, parent_node)
-                        {
-                            // It is imperative we search for not-a-value first
-                            // Otherwise we will find struct ctors for when we are looking
-                            // for structs, and the link won't work if there is something in
-                            // both namespaces.
-                            if let Ok(value_def) =
-                                self.resolve(path_str, true, &current_item, parent_node)
-                            {
-                                let kind = value_ns_kind(value_def.0, path_str);
-                                if let Some((value_kind, value_disambig)) = kind {
-                                    let (type_kind, article, type_disambig)
-                                        = type_ns_kind(type_def.0, path_str);
-                                    ambiguity_error(cx, &item.attrs, path_str,
-                                                    article, type_kind, &type_disambig,
-                                                    "a", value_kind, &value_disambig);
-                                    continue;
-                                }
-                            }
-                            type_def
-                        } else if let Ok(value_def) =
-                            self.resolve(path_str, true, &current_item, parent_node)
-                        {
-                            value_def
-                        } else {
-                            resolution_failure(cx, &item.attrs, path_str, &dox, link_range);
+                    if let Some(res) = macro_res {
+                        candidates.push(res);
+                    }
+
+                    match candidates.len() {
+                        0 => {
+                            let (notes, suggestions) = self.failure_hints(
+                                path_str, kind, &current_item, parent_node);
+                            resolution_failure(cx, &item.attrs, path_str, &dox, link_range,
+                                               &ori_link, &notes, &suggestions);
                             // this could just be a normal link
                             continue;
                         }
-                    }
-                    PathKind::Macro => {
-                        if let Some(def) = macro_resolve(cx, path_str) {
-                            (def, None)
-                        } else {
-                            resolution_failure(cx, &item.attrs, path_str, &dox, link_range);
-                            continue
+                        1 => candidates.pop().unwrap(),
+                        _ => {
+                            let kinds = candidates.iter()
+                                                  .map(|&(target, ref fragment)| {
+                                                      link_kind(target, fragment)
+                                                  })
+                                                  .collect::<Vec<_>>();
+                            ambiguity_error(cx, &item.attrs, path_str, &dox, link_range,
+                                            &ori_link, &kinds);
+                            continue;
                         }
                     }
                 }
+                _ => {
+                    if let Some(res) =
+                        self.resolve_in(path_str, kind, &current_item, parent_node)
+                    {
+                        res
+                    } else {
+                        let (notes, suggestions) = self.failure_hints(
+                            path_str, kind, &current_item, parent_node);
+                        resolution_failure(cx, &item.attrs, path_str, &dox, link_range,
+                                           &ori_link, &notes, &suggestions);
+                        // This could just be a normal link or a broken link
+                        // we could potentially check if something is
+                        // "intra-doc-link-like" and warn in that case.
+                        continue;
+                    }
+                }
             };
 
-            if let Def::PrimTy(_) = def {
-                item.attrs.links.push((ori_link, None, fragment));
-            } else {
-                let id = register_def(cx, def);
-                item.attrs.links.push((ori_link, Some(id), fragment));
+            // Check that the disambiguator, if any, matches what the link resolved to, so that
+            // `struct@Foo` can't silently point to an enum.
+            let found = link_kind(target, &fragment);
+            if !allowed_kinds.is_empty() && !allowed_kinds.contains(&found) {
+                disambiguator_error(cx, &item.attrs, path_str, &dox, link_range,
+                                    &ori_link, disambiguator, found);
+                continue;
+            }
+
+            match target {
+                Target::Primitive => item.attrs.links.push((ori_link, None, fragment)),
+                Target::Def(def) => {
+                    let id = register_def(cx, def);
+                    item.attrs.links.push((ori_link, Some(id), fragment));
+                }
             }
         }
 
@@ -457,30 +544,52 @@ fn macro_resolve(cx: &DocContext<'_, '_, '_>, path_str: &str) -> Option<Def> {
     None
 }
 
-/// Reports a resolution failure diagnostic.
+/// Splits the disambiguator off a link, e.g. `struct@Foo`, `foo()` or `foo!`. Returns the
+/// namespace to look the path up in, the kinds of item the link may resolve to (empty when
+/// there is no constraint), the disambiguator as written and the remaining path.
+fn parse_disambiguator(link: &str) -> (PathKind, &'static [&'static str], &str, &str) {
+    if let Some(pos) = link.find('@') {
+        let prefix = &link[..pos];
+        if let Some(&(_, kind, kinds)) = DISAMBIGUATORS.iter().find(|d| d.0 == prefix) {
+            return (kind, kinds, &link[..pos + 1], &link[pos + 1..]);
+        }
+    }
+    if link.ends_with("()") {
+        (PathKind::Value, FN_KINDS, "()", link.trim_end_matches("()"))
+    } else if link.ends_with('!') {
+        (PathKind::Macro, &[], "!", link.trim_end_matches('!'))
+    } else {
+        (PathKind::Unknown, &[], "", link)
+    }
+}
+
+/// Starts an intra-doc link diagnostic, pointing at the link itself when possible.
 ///
-/// If we cannot find the exact source span of the resolution failure, we use the span of the
+/// If we cannot find the exact source span of the link, we use the span of the
 /// documentation attributes themselves. This is a little heavy-handed, so we display the markdown
-/// line containing the failure as a note as well.
-fn resolution_failure(
-    cx: &DocContext<'_, '_, '_>,
+/// line containing the link as a note as well. Returns the span of the link if it was found.
+fn link_diagnostic<'a>(
+    cx: &'a DocContext<'_, '_, '_>,
     attrs: &Attributes,
-    path_str: &str,
+    msg: &str,
+    label: &str,
     dox: &str,
     link_range: Option<Range<usize>>,
-) {
+) -> (DiagnosticBuilder<'a>, Option<Span>) {
     let sp = span_of_attrs(attrs);
 
     let mut diag = cx.tcx.struct_span_lint_node(
         lint::builtin::INTRA_DOC_LINK_RESOLUTION_FAILURE,
         NodeId::from_u32(0),
         sp,
-        &format!("`[{}]` cannot be resolved, ignoring it...", path_str),
+        msg,
     );
+    let mut link_sp = None;
     if let Some(link_range) = link_range {
         if let Some(sp) = super::source_span_for_markdown_range(cx, dox, &link_range, attrs) {
             diag.set_span(sp);
-            diag.span_label(sp, "cannot be resolved, ignoring");
+            diag.span_label(sp, label);
+            link_sp = Some(sp);
         } else {
             // blah blah blah\nblah\nblah [blah] blah blah\nblah blah
             //                       ^     ~~~~
@@ -500,68 +609,220 @@ fn resolution_failure(
             ));
         }
     };
+    (diag, link_sp)
+}
+
+/// Suggests replacing the link at `link_sp` (written as `ori_link`) by the disambiguated link
+/// to each of the given kinds of item. Without an exact span, the suggestions are given as help
+/// messages instead.
+fn suggest_links(diag: &mut DiagnosticBuilder<'_>,
+                 link_sp: Option<Span>,
+                 ori_link: &str,
+                 path_str: &str,
+                 kinds: &[&'static str]) {
+    for &kind in kinds {
+        let replacement = disambiguated(path_str, kind);
+        let msg = if replacement.ends_with("()") {
+            format!("to link to the {}, add parentheses", kind)
+        } else if replacement.ends_with('!') {
+            format!("to link to the {}, add an exclamation mark", kind)
+        } else {
+            let prefix = &replacement[..replacement.find('@').unwrap() + 1];
+            format!("to link to the {}, prefix with `{}`", kind, prefix)
+        };
+        // Keep the backticks around code-formatted shortcut links.
+        let replacement = if ori_link.starts_with('`') {
+            format!("`{}`", replacement)
+        } else {
+            replacement
+        };
+        match link_sp {
+            Some(sp) => {
+                diag.span_suggestion(sp, &msg, replacement, Applicability::MaybeIncorrect);
+            }
+            None => {
+                diag.help(&format!("{}: `{}`", msg, replacement));
+            }
+        }
+    }
+}
+
+/// Reports a resolution failure diagnostic.
+///
+/// `notes` explain what was found instead, and `suggestions` are the kinds of item that a
+/// disambiguated link would resolve to.
+fn resolution_failure(
+    cx: &DocContext<'_, '_, '_>,
+    attrs: &Attributes,
+    path_str: &str,
+    dox: &str,
+    link_range: Option<Range<usize>>,
+    ori_link: &str,
+    notes: &[String],
+    suggestions: &[&'static str],
+) {
+    let (mut diag, link_sp) = link_diagnostic(
+        cx,
+        attrs,
+        &format!("`[{}]` cannot be resolved, ignoring it...", path_str),
+        "cannot be resolved, ignoring",
+        dox,
+        link_range,
+    );
+    for note in notes {
+        diag.note(note);
+    }
+    suggest_links(&mut diag, link_sp, ori_link, path_str, suggestions);
     diag.help("to escape `[` and `]` characters, just add '\\' before them like \
                `\\[` or `\\]`");
     diag.emit();
 }
 
-fn ambiguity_error(cx: &DocContext<'_, '_, '_>, attrs: &Attributes,
-                   path_str: &str,
-                   article1: &str, kind1: &str, disambig1: &str,
-                   article2: &str, kind2: &str, disambig2: &str) {
-    let sp = span_of_attrs(attrs);
-    cx.sess()
-      .struct_span_warn(sp,
-                        &format!("`{}` is both {} {} and {} {}",
-                                 path_str, article1, kind1,
-                                 article2, kind2))
-      .help(&format!("try `{}` if you want to select the {}, \
-                      or `{}` if you want to \
-                      select the {}",
-                      disambig1, kind1, disambig2,
-                      kind2))
-      .emit();
+/// Reports a link that resolves to several items in different namespaces, suggesting a
+/// disambiguated link for each of them.
+fn ambiguity_error(
+    cx: &DocContext<'_, '_, '_>,
+    attrs: &Attributes,
+    path_str: &str,
+    dox: &str,
+    link_range: Option<Range<usize>>,
+    ori_link: &str,
+    kinds: &[&'static str],
+) {
+    let candidates = kinds.iter()
+                          .map(|kind| format!("{} {}", article(kind), kind))
+                          .collect::<Vec<_>>();
+    let (mut diag, link_sp) = link_diagnostic(
+        cx,
+        attrs,
+        &format!("`{}` is both {}", path_str, candidates.join(" and ")),
+        "ambiguous link",
+        dox,
+        link_range,
+    );
+    suggest_links(&mut diag, link_sp, ori_link, path_str, kinds);
+    diag.emit();
 }
 
-/// Given a def, returns its name and disambiguator
-/// for a value namespace.
+/// Reports a link whose disambiguator doesn't match the kind of item it resolved to.
+fn disambiguator_error(
+    cx: &DocContext<'_, '_, '_>,
+    attrs: &Attributes,
+    path_str: &str,
+    dox: &str,
+    link_range: Option<Range<usize>>,
+    ori_link: &str,
+    disambiguator: &str,
+    found: &'static str,
+) {
+    let (mut diag, link_sp) = link_diagnostic(
+        cx,
+        attrs,
+        &format!("incompatible link kind for `{}`", path_str),
+        &format!("this link resolved to {} {}, not what `{}` selects",
+                 article(found), found, disambiguator),
+        dox,
+        link_range,
+    );
+    suggest_links(&mut diag, link_sp, ori_link, path_str, &[found]);
+    diag.emit();
+}
+
+/// Returns the kind of item a resolved link points to, using the names of `ItemType`.
+///
+/// For links to fields, variants and associated items, the target is their parent and the kind
+/// is taken from the fragment.
+fn link_kind(target: Target, fragment: &Option<String>) -> &'static str {
+    if let Some(ref fragment) = *fragment {
+        let fragment = match fragment.find('#') {
+            Some(pos) => &fragment[pos + 1..],
+            // A primitive page, without any anchor.
+            None if is_primitive(fragment, false) => return "primitive",
+            None => &fragment[..],
+        };
+        if fragment.contains(".field.") {
+            return "structfield";
+        } else if fragment.ends_with(".v") {
+            return "variant";
+        }
+        return match fragment.split('.').next() {
+            Some("method") => "method",
+            Some("tymethod") => "tymethod",
+            Some("associatedconstant") => "associatedconstant",
+            Some("associatedtype") => "associatedtype",
+            Some("variant") => "variant",
+            Some("structfield") => "structfield",
+            _ => "item",
+        };
+    }
+    match target {
+        Target::Primitive => "primitive",
+        Target::Def(def) => match def {
+            Def::Struct(..) | Def::StructCtor(..) | Def::SelfCtor(..) => "struct",
+            Def::Enum(..) => "enum",
+            Def::Trait(..) => "trait",
+            Def::TraitAlias(..) => "traitalias",
+            Def::Union(..) => "union",
+            Def::TyAlias(..) => "type",
+            Def::ForeignTy(..) => "foreigntype",
+            Def::Existential(..) => "existential",
+            Def::Mod(..) => "mod",
+            Def::Variant(..) | Def::VariantCtor(..) => "variant",
+            Def::Fn(..) => "fn",
+            Def::Method(..) => "method",
+            Def::Const(..) => "constant",
+            Def::AssociatedConst(..) => "associatedconstant",
+            Def::AssociatedTy(..) => "associatedtype",
+            Def::Static(..) => "static",
+            Def::Macro(..) => "macro",
+            _ => "item",
+        },
+    }
+}
+
+fn article(kind: &str) -> &'static str {
+    match kind.as_bytes().first() {
+        Some(b'a') | Some(b'e') | Some(b'i') | Some(b'o') | Some(b'u') => "an",
+        _ => "a",
+    }
+}
+
+/// Builds the disambiguated form of a link to `path_str`, given the kind of its target.
+fn disambiguated(path_str: &str, kind: &str) -> String {
+    match kind {
+        "fn" | "method" | "tymethod" => format!("{}()", path_str),
+        "macro" => format!("{}!", path_str),
+        "constant" | "associatedconstant" => format!("const@{}", path_str),
+        "structfield" => format!("field@{}", path_str),
+        "primitive" => format!("prim@{}", path_str),
+        "struct" | "enum" | "trait" | "union" | "mod" | "static" | "variant" => {
+            format!("{}@{}", kind, path_str)
+        }
+        "type" | "traitalias" | "foreigntype" | "existential" | "associatedtype" => {
+            format!("type@{}", path_str)
+        }
+        _ => format!("value@{}", path_str),
+    }
+}
+
+/// Given the target of a link in the value namespace, returns its kind.
 ///
 /// Returns `None` for things which cannot be ambiguous since
 /// they exist in both namespaces (structs and modules).
-fn value_ns_kind(def: Def, path_str: &str) -> Option<(&'static str, String)> {
-    match def {
+fn value_ns_kind(target: Target) -> Option<&'static str> {
+    match target {
         // Structs, variants, and mods exist in both namespaces; skip them.
-        Def::StructCtor(..) | Def::Mod(..) | Def::Variant(..) |
-        Def::VariantCtor(..) | Def::SelfCtor(..)
-            => None,
-        Def::Fn(..)
-            => Some(("function", format!("{}()", path_str))),
-        Def::Method(..)
-            => Some(("method", format!("{}()", path_str))),
-        Def::Const(..)
-            => Some(("const", format!("const@{}", path_str))),
-        Def::Static(..)
-            => Some(("static", format!("static@{}", path_str))),
-        _ => Some(("value", format!("value@{}", path_str))),
+        Target::Def(Def::StructCtor(..)) | Target::Def(Def::Mod(..)) |
+        Target::Def(Def::Variant(..)) | Target::Def(Def::VariantCtor(..)) |
+        Target::Def(Def::SelfCtor(..)) => None,
+        _ => Some(link_kind(target, &None)),
     }
 }
 
-/// Given a def, returns its name, the article to be used, and a disambiguator
-/// for the type namespace.
-fn type_ns_kind(def: Def, path_str: &str) -> (&'static str, &'static str, String) {
-    let (kind, article) = match def {
-        // We can still have non-tuple structs.
-        Def::Struct(..) => ("struct", "a"),
-        Def::Enum(..) => ("enum", "an"),
-        Def::Trait(..) => ("trait", "a"),
-        Def::Union(..) => ("union", "a"),
-        _ => ("type", "a"),
-    };
-    (kind, article, format!("{}@{}", kind, path_str))
-}
-
 /// Given an enum variant's def, return the def of its enum and the associated fragment.
-fn handle_variant(cx: &DocContext<'_, '_, '_>, def: Def) -> Result<(Def, Option<String>), ()> {
+fn handle_variant(cx: &DocContext<'_, '_, '_>, def: Def)
+    -> Result<(Target, Option<String>), ()>
+{
     use rustc::ty::DefIdTree;
 
     let parent = if let Some(parent) = cx.tcx.parent(def.def_id()) {
@@ -571,56 +832,46 @@ fn handle_variant(cx: &DocContext<'_, '_, '_>, def: Def) -> Result<(Def, Option<
     };
     let parent_def = Def::Enum(parent);
     let variant = cx.tcx.expect_variant_def(def);
-    Ok((parent_def, Some(format!("{}.v", variant.ident.name))))
+    Ok((Target::Def(parent_def), Some(format!("{}.v", variant.ident.name))))
 }
 
-const PRIMITIVES: &[(&str, Def)] = &[
-    ("u8",    Def::PrimTy(hir::PrimTy::Uint(syntax::ast::UintTy::U8))),
-    ("u16",   Def::PrimTy(hir::PrimTy::Uint(syntax::ast::UintTy::U16))),
-    ("u32",   Def::PrimTy(hir::PrimTy::Uint(syntax::ast::UintTy::U32))),
-    ("u64",   Def::PrimTy(hir::PrimTy::Uint(syntax::ast::UintTy::U64))),
-    ("u128",  Def::PrimTy(hir::PrimTy::Uint(syntax::ast::UintTy::U128))),
-    ("usize", Def::PrimTy(hir::PrimTy::Uint(syntax::ast::UintTy::Usize))),
-    ("i8",    Def::PrimTy(hir::PrimTy::Int(syntax::ast::IntTy::I8))),
-    ("i16",   Def::PrimTy(hir::PrimTy::Int(syntax::ast::IntTy::I16))),
-    ("i32",   Def::PrimTy(hir::PrimTy::Int(syntax::ast::IntTy::I32))),
-    ("i64",   Def::PrimTy(hir::PrimTy::Int(syntax::ast::IntTy::I64))),
-    ("i128",  Def::PrimTy(hir::PrimTy::Int(syntax::ast::IntTy::I128))),
-    ("isize", Def::PrimTy(hir::PrimTy::Int(syntax::ast::IntTy::Isize))),
-    ("f32",   Def::PrimTy(hir::PrimTy::Float(syntax::ast::FloatTy::F32))),
-    ("f64",   Def::PrimTy(hir::PrimTy::Float(syntax::ast::FloatTy::F64))),
-    ("str",   Def::PrimTy(hir::PrimTy::Str)),
-    ("bool",  Def::PrimTy(hir::PrimTy::Bool)),
-    ("char",  Def::PrimTy(hir::PrimTy::Char)),
+/// The primitive types documented on their own `primitive.*.html` page.
+const PRIMITIVES: &[&str] = &[
+    "u8", "u16", "u32", "u64", "u128", "usize",
+    "i8", "i16", "i32", "i64", "i128", "isize",
+    "f32", "f64", "str", "bool", "char",
+    "slice", "array", "tuple", "unit", "pointer", "reference", "fn", "never",
 ];
 
-fn is_primitive(path_str: &str, is_val: bool) -> Option<Def> {
-    if is_val {
-        None
-    } else {
-        PRIMITIVES.iter().find(|x| x.0 == path_str).map(|x| x.1)
-    }
+fn is_primitive(path_str: &str, is_val: bool) -> bool {
+    !is_val && PRIMITIVES.contains(&path_str)
 }
 
-fn primitive_impl(cx: &DocContext<'_, '_, '_>, path_str: &str) -> Option<DefId> {
-    let tcx = cx.tcx;
-    match path_str {
-        "u8" => tcx.lang_items().u8_impl(),
-        "u16" => tcx.lang_items().u16_impl(),
-        "u32" => tcx.lang_items().u32_impl(),
-        "u64" => tcx.lang_items().u64_impl(),
-        "u128" => tcx.lang_items().u128_impl(),
-        "usize" => tcx.lang_items().usize_impl(),
-        "i8" => tcx.lang_items().i8_impl(),
-        "i16" => tcx.lang_items().i16_impl(),
-        "i32" => tcx.lang_items().i32_impl(),
-        "i64" => tcx.lang_items().i64_impl(),
-        "i128" => tcx.lang_items().i128_impl(),
-        "isize" => tcx.lang_items().isize_impl(),
-        "f32" => tcx.lang_items().f32_impl(),
-        "f64" => tcx.lang_items().f64_impl(),
-        "str" => tcx.lang_items().str_impl(),
-        "char" => tcx.lang_items().char_impl(),
-        _ => None,
-    }
+/// Returns the inherent impls of a primitive type. Some primitives have their methods split
+/// across several impls, e.g. between `core` and `alloc`.
+fn primitive_impls(cx: &DocContext<'_, '_, '_>, path_str: &str) -> Vec<DefId> {
+    let lang_items = cx.tcx.lang_items();
+    let impls = match path_str {
+        "u8" => vec![lang_items.u8_impl()],
+        "u16" => vec![lang_items.u16_impl()],
+        "u32" => vec![lang_items.u32_impl()],
+        "u64" => vec![lang_items.u64_impl()],
+        "u128" => vec![lang_items.u128_impl()],
+        "usize" => vec![lang_items.usize_impl()],
+        "i8" => vec![lang_items.i8_impl()],
+        "i16" => vec![lang_items.i16_impl()],
+        "i32" => vec![lang_items.i32_impl()],
+        "i64" => vec![lang_items.i64_impl()],
+        "i128" => vec![lang_items.i128_impl()],
+        "isize" => vec![lang_items.isize_impl()],
+        "f32" => vec![lang_items.f32_impl(), lang_items.f32_runtime_impl()],
+        "f64" => vec![lang_items.f64_impl(), lang_items.f64_runtime_impl()],
+        "str" => vec![lang_items.str_impl(), lang_items.str_alloc_impl()],
+        "char" => vec![lang_items.char_impl()],
+        "slice" => vec![lang_items.slice_impl(), lang_items.slice_u8_impl(),
+                        lang_items.slice_alloc_impl(), lang_items.slice_u8_alloc_impl()],
+        "pointer" => vec![lang_items.const_ptr_impl(), lang_items.mut_ptr_impl()],
+        _ => vec![],
+    };
+    impls.into_iter().filter_map(|did| did).collect()
 }
@@ -1,26 +1,69 @@
 use std::sync::Arc;
 
-use crate::clean::{Crate, Item};
+use rustc::util::nodemap::FxHashSet;
+use syntax::symbol::Symbol;
+
+use crate::clean::{AttributesExt, Crate, Item};
 use crate::clean::cfg::Cfg;
 use crate::fold::DocFolder;
 use crate::passes::Pass;
 
 pub const PROPAGATE_DOC_CFG: Pass =
     Pass::late("propagate-doc-cfg", propagate_doc_cfg,
-        "propagates `#[doc(cfg(...))]` to child items");
+        "propagates `#[doc(cfg(...))]` and `#[cfg(...)]` to child items");
+
+/// Configuration names which are left out of the portability notes generated from `#[cfg]`,
+/// because documentation readers never build in a configuration where they make a difference.
+/// Crates can extend this list with `#![doc(cfg_hide(...))]`.
+const DEFAULT_HIDDEN_CFGS: &[&str] = &["test", "rustdoc"];
 
 pub fn propagate_doc_cfg(cr: Crate) -> Crate {
-    CfgPropagator { parent_cfg: None }.fold_crate(cr)
+    let mut hidden_cfgs: FxHashSet<Symbol> =
+        DEFAULT_HIDDEN_CFGS.iter().map(|name| Symbol::intern(name)).collect();
+    if let Some(ref module) = cr.module {
+        for attr in module.attrs.lists("doc").filter(|attr| attr.check_name("cfg_hide")) {
+            hidden_cfgs.extend(attr.meta_item_list().unwrap_or(&[]).iter()
+                                   .filter_map(|name| name.name()));
+        }
+    }
+
+    CfgPropagator { parent_cfg: None, hidden_cfgs }.fold_crate(cr)
 }
 
 struct CfgPropagator {
     parent_cfg: Option<Arc<Cfg>>,
+    hidden_cfgs: FxHashSet<Symbol>,
+}
+
+impl CfgPropagator {
+    /// Collects the conditions of the item's own `#[cfg(...)]` attributes, including the ones
+    /// produced by `#[cfg_attr(..., cfg(...))]`, and merges them into its `#[doc(cfg(...))]`.
+    fn merge_auto_cfg(&self, item: &mut Item) {
+        let mut cfg = Cfg::True;
+        for attr in item.attrs.lists("cfg") {
+            // Malformed `#[cfg]` attributes have been reported by the compiler already.
+            if let Some(Ok(new_cfg)) = attr.meta_item().map(Cfg::parse) {
+                if let Some(new_cfg) = new_cfg.without_hidden(&self.hidden_cfgs) {
+                    cfg &= new_cfg;
+                }
+            }
+        }
+        if cfg == Cfg::True {
+            return;
+        }
+
+        item.attrs.cfg = Some(Arc::new(match item.attrs.cfg.take() {
+            Some(doc_cfg) => Cfg::clone(&doc_cfg) & cfg,
+            None => cfg,
+        }));
+    }
 }
 
 impl DocFolder for CfgPropagator {
     fn fold_item(&mut self, mut item: Item) -> Option<Item> {
         let old_parent_cfg = self.parent_cfg.clone();
 
+        self.merge_auto_cfg(&mut item);
         let new_cfg = match (self.parent_cfg.take(), item.attrs.cfg.take()) {
             (None, None) => None,
             (Some(rc), None) | (None, Some(rc)) => Some(rc),
@@ -728,7 +728,8 @@ impl Tester for Collector {
         self.tests.push(testing::TestDescAndFn {
             desc: testing::TestDesc {
                 name: testing::DynTestName(name.clone()),
-                ignore: config.ignore,
+                // Doctests are always built for the host.
+                ignore: config.is_ignored_for(config::host_triple(), &cfgs),
                 // compiler failures are test failures
                 should_panic: testing::ShouldPanic::No,
                 allow_fail: config.allow_fail,
@@ -7,6 +7,7 @@ Core encoding and decoding interfaces.
 use std::borrow::Cow;
 use std::intrinsics;
 use std::marker::PhantomData;
+use std::ops::RangeInclusive;
 use std::path;
 use std::rc::Rc;
 use std::cell::{Cell, RefCell};
@@ -551,6 +552,25 @@ impl<T> Decodable for PhantomData<T> {
     }
 }
 
+impl<T: Encodable> Encodable for RangeInclusive<T> {
+    fn encode<S: Encoder>(&self, s: &mut S) -> Result<(), S::Error> {
+        s.emit_struct("RangeInclusive", 2, |s| {
+            s.emit_struct_field("start", 0, |s| self.start().encode(s))?;
+            s.emit_struct_field("end", 1, |s| self.end().encode(s))
+        })
+    }
+}
+
+impl<T: Decodable> Decodable for RangeInclusive<T> {
+    fn decode<D: Decoder>(d: &mut D) -> Result<RangeInclusive<T>, D::Error> {
+        d.read_struct("RangeInclusive", 2, |d| {
+            let start = d.read_struct_field("start", 0, |d| Decodable::decode(d))?;
+            let end = d.read_struct_field("end", 1, |d| Decodable::decode(d))?;
+            Ok(RangeInclusive::new(start, end))
+        })
+    }
+}
+
 impl<'a, T: ?Sized + Encodable> Encodable for &'a T {
     fn encode<S: Encoder>(&self, s: &mut S) -> Result<(), S::Error> {
         (**self).encode(s)
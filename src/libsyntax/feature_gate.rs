@@ -471,6 +471,21 @@ declare_features! (
 
     // #[repr(align(X))] on enums
     (active, repr_align_enum, "1.34.0", Some(57996), None),
+
+    // Allows `if`, `match`, `if let`, and short-circuiting `&&` and `||` in
+    // constants, statics and `const fn`.
+    (active, const_if_match, "1.34.0", Some(49146), None),
+
+    // Allows `loop` and `while` in constants, statics and `const fn`.
+    (active, const_loop, "1.34.0", Some(52000), None),
+
+    // Allows setting the number of steps const evaluation may take with
+    // `#![const_eval_limit = "N"]`.
+    (active, const_eval_limit, "1.34.0", Some(67217), None),
+
+    // Allows transient heap allocations in constants, statics and `const fn`
+    // through the `const_allocate` family of intrinsics.
+    (active, const_heap, "1.34.0", None, None),
 );
 
 declare_features! (
@@ -1264,6 +1279,10 @@ pub const BUILTIN_ATTRIBUTES: &[(&str, AttributeType, AttributeTemplate, Attribu
     ("no_builtins", CrateLevel, template!(Word), Ungated),
     ("recursion_limit", CrateLevel, template!(NameValueStr: "N"), Ungated),
     ("type_length_limit", CrateLevel, template!(NameValueStr: "N"), Ungated),
+    ("const_eval_limit", CrateLevel, template!(NameValueStr: "N"), Gated(Stability::Unstable,
+                    "const_eval_limit",
+                    "the `#[const_eval_limit]` attribute is an experimental feature",
+                    cfg_fn!(const_eval_limit))),
     ("test_runner", CrateLevel, template!(List: "path"), Gated(Stability::Unstable,
                     "custom_test_frameworks",
                     EXPLAIN_CUSTOM_TEST_FRAMEWORKS,
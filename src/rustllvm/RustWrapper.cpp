@@ -108,6 +108,20 @@ extern "C" void LLVMRustPrintPassTimings() {
   TimerGroup::printAll(OS);
 }
 
+extern "C" void LLVMRustWritePassTimingsJSONToString(RustStringRef Str) {
+  RawRustStringOstream OS(Str);
+  OS << "{\n";
+#if LLVM_VERSION_GE(7, 0)
+  TimerGroup::printAllJSONValues(OS, "");
+  // Unlike `printAll`, the above does not reset the timers, which would make
+  // LLVM print them again when it shuts down.
+  TimerGroup::clearAll();
+#endif
+  // Older versions of LLVM cannot print their timers as JSON, so the object
+  // stays empty and no LLVM passes are reported.
+  OS << "\n}\n";
+}
+
 extern "C" LLVMValueRef LLVMRustGetNamedValue(LLVMModuleRef M,
                                               const char *Name) {
   return wrap(unwrap(M)->getNamedValue(Name));
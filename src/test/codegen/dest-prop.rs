@@ -0,0 +1,45 @@
+// compile-flags: -C no-prepopulate-passes -Z mir-opt-level=2
+
+// Check that values moved between locals are computed straight into their final destination,
+// without any copies in between.
+
+#![crate_type = "lib"]
+
+pub struct Big([u64; 32]);
+
+#[inline(never)]
+#[no_mangle]
+pub fn produce() -> Big {
+    Big([7; 32])
+}
+
+#[inline(never)]
+#[no_mangle]
+pub fn produce_array() -> [u64; 32] {
+    [7; 32]
+}
+
+// CHECK-LABEL: @forward
+#[no_mangle]
+pub fn forward() -> Big {
+    // CHECK-NOT: memcpy
+    // CHECK: call void @produce(%Big* {{.*}} %0)
+    // CHECK-NOT: memcpy
+    // CHECK: ret void
+    let a = produce();
+    let b = a;
+    b
+}
+
+// CHECK-LABEL: @forward_array
+#[no_mangle]
+pub fn forward_array() -> [u64; 32] {
+    // CHECK-NOT: memcpy
+    // CHECK: call void @produce_array
+    // CHECK-NOT: memcpy
+    // CHECK: ret void
+    let a = produce_array();
+    let b = a;
+    let c = b;
+    c
+}
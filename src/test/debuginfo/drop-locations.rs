@@ -5,8 +5,9 @@
 
 #![allow(unused)]
 
-// compile-flags:-g -O -C no-prepopulate-passes
+// compile-flags:-g -O -C no-prepopulate-passes -Z inline-mir=no
 // -O -C no-prepopulate-passes added to work around https://bugs.llvm.org/show_bug.cgi?id=32123
+// -Z inline-mir=no keeps the calls stepped over below, which -O would otherwise inline
 
 // This test checks that drop glue code gets attributed to scope's closing brace,
 // and function epilogues - to function's closing brace.
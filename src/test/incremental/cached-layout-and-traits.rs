@@ -0,0 +1,74 @@
+// Checks that the results of layout and trait selection queries, which are
+// loaded from the on-disk cache when their nodes are green, are the same as
+// when they are recomputed from scratch, and that the signatures of the
+// functions which don't change stay green. The run-make test
+// `incremental-query-cache` checks that the results are actually loaded.
+
+// revisions: rpass1 rpass2 rpass3
+// compile-flags: -Z incremental-verify-queries=1 -Z query-dep-graph
+
+#![feature(rustc_attrs)]
+
+use std::fmt::Debug;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Point {
+    pub x: u32,
+    pub y: u32,
+}
+
+pub enum Shape {
+    Dot(Point),
+    Line(Point, Point),
+    Named(String, Box<Shape>),
+}
+
+pub trait Area {
+    fn area(&self) -> u32;
+}
+
+impl Area for Shape {
+    fn area(&self) -> u32 {
+        match *self {
+            Shape::Dot(_) => 0,
+            Shape::Line(a, b) => (b.x - a.x) * (b.y - a.y),
+            Shape::Named(_, ref shape) => shape.area(),
+        }
+    }
+}
+
+#[cfg(rpass1)]
+fn offset() -> u32 {
+    1
+}
+
+#[cfg(not(rpass1))]
+#[rustc_dirty(label="HirBody", cfg="rpass2")]
+#[rustc_clean(label="HirBody", cfg="rpass3")]
+fn offset() -> u32 {
+    2
+}
+
+#[rustc_clean(label="FnSignature,TypeckTables", cfg="rpass2")]
+#[rustc_clean(label="FnSignature,TypeckTables", cfg="rpass3")]
+fn total<T: Area + ?Sized>(shapes: &[Box<T>]) -> u32 {
+    shapes.iter().map(|shape| shape.area()).sum()
+}
+
+#[rustc_clean(label="FnSignature,TypeckTables", cfg="rpass2")]
+#[rustc_clean(label="FnSignature,TypeckTables", cfg="rpass3")]
+fn show<T: Debug + Copy>(value: T) -> String {
+    format!("{:?}", value)
+}
+
+fn main() {
+    let a = Point { x: 0, y: 0 };
+    let b = Point { x: offset(), y: offset() };
+    let shapes: Vec<Box<dyn Area>> = vec![
+        Box::new(Shape::Dot(a)),
+        Box::new(Shape::Named("line".to_string(), Box::new(Shape::Line(a, b)))),
+    ];
+    assert!(total(&shapes) >= 1);
+    assert_eq!(show(a), "Point { x: 0, y: 0 }");
+    assert!(std::mem::size_of::<Shape>() >= std::mem::size_of::<Point>() * 2);
+}
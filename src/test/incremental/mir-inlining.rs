@@ -0,0 +1,51 @@
+// Test that MIR inlining works with incremental compilation: changes to the body of an inlined
+// function must show up in its callers, and a call cycle that only appears in a later session
+// must still be detected.
+
+// revisions: rpass1 rpass2 rpass3
+// compile-flags: -Z query-dep-graph -Z inline-mir=yes
+
+#![feature(rustc_attrs)]
+
+#[inline]
+fn callee() -> u32 {
+    #[cfg(rpass1)]
+    return 1;
+
+    #[cfg(not(rpass1))]
+    return 2;
+}
+
+#[rustc_dirty(label="MirOptimized", cfg="rpass2")]
+#[rustc_clean(label="MirOptimized", cfg="rpass3")]
+#[rustc_clean(label="MirInlinerCallees", cfg="rpass2")]
+fn caller() -> u32 {
+    callee() + 1
+}
+
+// `ping` only calls back into `pong` starting with `rpass3`.
+#[inline]
+fn ping(n: u32) -> u32 {
+    #[cfg(not(rpass3))]
+    return n;
+
+    #[cfg(rpass3)]
+    return if n == 0 { 0 } else { pong(n - 1) };
+}
+
+#[inline]
+fn pong(n: u32) -> u32 {
+    if n == 0 { 1 } else { ping(n - 1) + 1 }
+}
+
+fn main() {
+    #[cfg(rpass1)]
+    assert_eq!(caller(), 2);
+    #[cfg(not(rpass1))]
+    assert_eq!(caller(), 3);
+
+    #[cfg(not(rpass3))]
+    assert_eq!(pong(3), 3);
+    #[cfg(rpass3)]
+    assert_eq!(pong(3), 2);
+}
@@ -0,0 +1,34 @@
+// Checks that the results of green queries are the same when they are
+// recomputed from scratch, across a change which leaves most of them green.
+
+// revisions: rpass1 rpass2 rpass3
+// compile-flags: -Z incremental-verify-queries=1
+
+pub struct Point {
+    pub x: u32,
+    pub y: u32,
+}
+
+#[cfg(rpass1)]
+fn scale() -> u32 {
+    2
+}
+
+#[cfg(not(rpass1))]
+fn scale() -> u32 {
+    3
+}
+
+fn scaled(p: &Point) -> Point {
+    Point { x: p.x * scale(), y: p.y * scale() }
+}
+
+fn sum(p: &Point) -> u32 {
+    p.x + p.y
+}
+
+fn main() {
+    let p = Point { x: 1, y: 2 };
+    assert_eq!(sum(&p), 3);
+    assert!(sum(&scaled(&p)) >= 6);
+}
@@ -0,0 +1,45 @@
+// compile-flags: -Z mir-opt-level=2
+
+struct Big([u64; 32]);
+
+#[inline(never)]
+fn produce() -> Big {
+    Big([7; 32])
+}
+
+fn forward() -> Big {
+    let a = produce();
+    let b = a;
+    b
+}
+
+fn main() {
+    // Make sure the function actually gets instantiated.
+    forward();
+}
+
+// END RUST SOURCE
+// START rustc.forward.DestinationPropagation.before.mir
+// bb0: {
+//     ...
+//     _1 = const produce() -> bb1;
+// }
+// bb1: {
+//     ...
+//     _0 = move _1;
+//     ...
+//     return;
+// }
+// END rustc.forward.DestinationPropagation.before.mir
+// START rustc.forward.DestinationPropagation.after.mir
+// bb0: {
+//     ...
+//     _0 = const produce() -> bb1;
+// }
+// bb1: {
+//     ...
+//     nop;
+//     ...
+//     return;
+// }
+// END rustc.forward.DestinationPropagation.after.mir
@@ -0,0 +1,48 @@
+// compile-flags: -Z span_free_formats -Z mir-opt-level=3
+
+fn main() {
+    caller();
+    println!("{}", ping(10));
+}
+
+// Defined after `callee`, which used to prevent inlining it.
+fn caller() {
+    callee();
+}
+
+#[inline]
+fn callee() {
+    sink();
+}
+
+#[inline(never)]
+fn sink() {}
+
+// Calls that lead back to the caller are never inlined.
+#[inline]
+fn ping(n: u32) -> u32 {
+    pong(n)
+}
+
+#[inline]
+fn pong(n: u32) -> u32 {
+    if n == 0 { 1 } else { ping(n - 1) }
+}
+
+// END RUST SOURCE
+// START rustc.caller.Inline.after.mir
+// ...
+// bb0: {
+// ...
+//     _1 = const sink() -> bb1;
+// }
+// ...
+// END rustc.caller.Inline.after.mir
+// START rustc.ping.Inline.after.mir
+// ...
+// bb0: {
+// ...
+//     _0 = const pong(move _2) -> bb1;
+// }
+// ...
+// END rustc.ping.Inline.after.mir
@@ -0,0 +1,99 @@
+// compile-flags: -Z mir-opt-level=2
+
+struct Point {
+    x: u32,
+    y: u32,
+}
+
+// Each field of `p` gets a local of its own.
+fn split(a: u32, b: u32) -> u32 {
+    let p = Point { x: a, y: b };
+    p.y
+}
+
+// A field of `p` is borrowed, so it is kept.
+fn borrowed(a: u32, b: u32) -> u32 {
+    let p = Point { x: a, y: b };
+    let r = &p.y;
+    *r
+}
+
+// `p` is moved as a whole, so it is kept.
+fn whole(a: u32, b: u32) -> Point {
+    let p = Point { x: a, y: b };
+    p
+}
+
+fn main() {
+    split(0, 1);
+    borrowed(0, 1);
+    whole(0, 1);
+}
+
+// END RUST SOURCE
+// START rustc.split.ScalarReplacementOfAggregates.before.mir
+// bb0: {
+//     StorageLive(_3);
+//     StorageLive(_4);
+//     _4 = _1;
+//     StorageLive(_5);
+//     _5 = _2;
+//     (_3.0: u32) = move _4;
+//     (_3.1: u32) = move _5;
+//     StorageDead(_5);
+//     StorageDead(_4);
+//     _0 = (_3.1: u32);
+//     StorageDead(_3);
+//     return;
+// }
+// END rustc.split.ScalarReplacementOfAggregates.before.mir
+// START rustc.split.ScalarReplacementOfAggregates.after.mir
+// let mut _6: u32;
+// let mut _7: u32;
+// ...
+// bb0: {
+//     StorageLive(_6);
+//     StorageLive(_7);
+//     StorageLive(_4);
+//     _4 = _1;
+//     StorageLive(_5);
+//     _5 = _2;
+//     _6 = move _4;
+//     _7 = move _5;
+//     StorageDead(_5);
+//     StorageDead(_4);
+//     _0 = _7;
+//     StorageDead(_6);
+//     StorageDead(_7);
+//     return;
+// }
+// END rustc.split.ScalarReplacementOfAggregates.after.mir
+// START rustc.borrowed.ScalarReplacementOfAggregates.after.mir
+// bb0: {
+//     StorageLive(_3);
+//     StorageLive(_4);
+//     _4 = _1;
+//     StorageLive(_5);
+//     _5 = _2;
+//     (_3.0: u32) = move _4;
+//     (_3.1: u32) = move _5;
+//     StorageDead(_5);
+//     StorageDead(_4);
+//     StorageLive(_6);
+//     _6 = &(_3.1: u32);
+//     _0 = (*_6);
+//     StorageDead(_6);
+//     StorageDead(_3);
+//     return;
+// }
+// END rustc.borrowed.ScalarReplacementOfAggregates.after.mir
+// START rustc.whole.ScalarReplacementOfAggregates.after.mir
+// bb0: {
+//     ...
+//     (_3.0: u32) = move _4;
+//     (_3.1: u32) = move _5;
+//     ...
+//     _0 = move _3;
+//     ...
+// }
+// END rustc.whole.ScalarReplacementOfAggregates.after.mir
@@ -0,0 +1,40 @@
+// compile-flags: -Z mir-opt-level=2
+
+fn test(a: u32, b: u32) -> u32 {
+    let pair = (a, b);
+    pair.0 + pair.1
+}
+
+fn main() {
+    // Make sure the function actually gets instantiated.
+    test(0, 1);
+}
+
+// END RUST SOURCE
+// START rustc.test.ScalarReplacementOfAggregates.before.mir
+// bb0: {
+//     StorageLive(_3);
+//     ...
+//     (_3.0: u32) = move _4;
+//     (_3.1: u32) = move _5;
+//     ...
+//     _6 = (_3.0: u32);
+//     ...
+//     _7 = (_3.1: u32);
+//     ...
+// }
+// END rustc.test.ScalarReplacementOfAggregates.before.mir
+// START rustc.test.ScalarReplacementOfAggregates.after.mir
+// bb0: {
+//     StorageLive(_9);
+//     StorageLive(_10);
+//     ...
+//     _9 = move _4;
+//     _10 = move _5;
+//     ...
+//     _6 = _9;
+//     ...
+//     _7 = _10;
+//     ...
+// }
+// END rustc.test.ScalarReplacementOfAggregates.after.mir
@@ -0,0 +1,10 @@
+#![crate_type = "rlib"]
+
+pub struct Point {
+    pub x: u32,
+    pub y: u32,
+}
+
+pub fn sum(p: &Point) -> u32 {
+    p.x + p.y
+}
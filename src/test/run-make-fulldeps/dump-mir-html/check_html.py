@@ -0,0 +1,33 @@
+#!/usr/bin/env python
+
+import re
+import sys
+
+with open(sys.argv[1]) as f:
+    html = f.read()
+
+assert html.startswith('<!DOCTYPE html>'), html[:100]
+
+# Everything has to be inlined so that the file works offline.
+assert '<script src' not in html
+assert '<link' not in html
+assert 'http://' not in html and 'https://' not in html
+
+titles = re.findall(r'<section data-title="([^"]*)">', html)
+assert len(titles) > 2, titles
+assert any('SimplifyCfg' in title for title in titles), titles
+assert html.count('<svg class="cfg"') == len(titles)
+
+# Locals and blocks are marked up, and statements point to their source lines.
+assert 'class="local" data-name="_0"' in html
+assert 'class="block" data-name="bb0"' in html
+assert 'data-block="bb0"' in html
+assert re.search(r'data-lo="\d+" data-hi="\d+"', html)
+assert 'data-line="1"' in html
+
+# Every block of the graph, cleanup blocks included, opens a line of the MIR.
+assert 'class="node cleanup"' in html
+for section in re.findall(r'<section .*?</section>', html, re.S):
+    nodes = set(re.findall(r'<g class="node[^"]*" data-name="(bb\d+)"', section))
+    blocks = set(re.findall(r'data-block="(bb\d+)"', section))
+    assert nodes == blocks, (nodes, blocks)
@@ -0,0 +1,11 @@
+fn main() {
+    // Dropping `name` while unwinding needs cleanup blocks.
+    let name = String::from("sum");
+    let mut sum = 0;
+    for i in 0..10 {
+        if i % 3 == 0 {
+            sum += i;
+        }
+    }
+    println!("{} = {}", name, sum);
+}
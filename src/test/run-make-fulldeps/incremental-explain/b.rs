@@ -0,0 +1,9 @@
+#![crate_type = "rlib"]
+
+pub fn changed() -> u32 {
+    3
+}
+
+pub fn unchanged() -> u32 {
+    2
+}
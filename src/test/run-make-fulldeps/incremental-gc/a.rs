@@ -0,0 +1,5 @@
+#![crate_type = "rlib"]
+
+pub fn a() -> u32 {
+    1
+}
@@ -0,0 +1,5 @@
+#![crate_type = "rlib"]
+
+pub fn b() -> u32 {
+    2
+}
@@ -0,0 +1,25 @@
+#[derive(Clone, Copy)]
+pub struct Point {
+    pub x: u32,
+    pub y: u32,
+}
+
+pub fn scaled(p: Point, factor: u32) -> Point {
+    Point { x: p.x * factor, y: p.y * factor }
+}
+
+pub fn sum(p: Point) -> u32 {
+    p.x + p.y
+}
+
+#[cfg(rev1)]
+fn main() {
+    let p = Point { x: 1, y: 2 };
+    assert_eq!(sum(scaled(p, 2)), 6);
+}
+
+#[cfg(rev2)]
+fn main() {
+    let p = Point { x: 1, y: 2 };
+    assert_eq!(sum(scaled(p, 3)), 9);
+}
@@ -0,0 +1,27 @@
+#![crate_name = "foo"]
+
+/// A bar.
+///
+/// # Examples
+///
+/// ```
+/// let bar = foo::Bar::new(1);
+/// ```
+#[derive(Clone)]
+pub struct Bar {
+    /// The value.
+    pub x: u32,
+}
+
+impl Bar {
+    /// Makes a bar.
+    pub fn new(x: u32) -> Bar {
+        Bar { x }
+    }
+}
+
+/// Makes a [`Bar`], too.
+#[macro_export]
+macro_rules! bar {
+    ($x:expr) => { $crate::Bar::new($x) }
+}
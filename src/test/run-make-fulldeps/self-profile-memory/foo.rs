@@ -0,0 +1,5 @@
+pub struct Foo;
+
+fn main() {
+    let _foo: Foo = Foo;
+}
@@ -0,0 +1,23 @@
+#!/usr/bin/env python
+
+import sys
+import json
+
+passes = {}
+llvm_passes = []
+for line in sys.stdin:
+    entry = json.loads(line)
+    if 'llvm_pass' in entry:
+        assert entry['wall_time'] >= 0 and entry['cpu_time'] >= 0, line
+        llvm_passes.append(entry)
+    else:
+        for key in ('pass', 'depth', 'parent', 'wall_time', 'cpu_time', 'rss'):
+            assert key in entry, line
+        passes[entry['pass']] = entry
+
+assert passes['parsing']['depth'] == 0, passes['parsing']
+assert passes['parsing']['parent'] is None, passes['parsing']
+assert passes['expand crate']['parent'] == 'expansion', passes['expand crate']
+assert passes['expand crate']['depth'] == 1, passes['expand crate']
+assert 'LLVM passes' in passes
+assert llvm_passes, 'no LLVM pass timings'
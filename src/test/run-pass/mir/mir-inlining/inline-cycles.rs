@@ -0,0 +1,50 @@
+// run-pass
+// compile-flags:-O
+
+// Inlining is on by default when optimizing. Check that it copes with call cycles, both direct
+// and through generic code from other crates.
+
+#[inline]
+fn even(n: u32) -> bool {
+    if n == 0 { true } else { odd(n - 1) }
+}
+
+#[inline]
+fn odd(n: u32) -> bool {
+    if n == 0 { false } else { even(n - 1) }
+}
+
+#[inline]
+fn fact(n: u64) -> u64 {
+    if n <= 1 { 1 } else { n * fact(n - 1) }
+}
+
+fn tree_size(depth: u32) -> u32 {
+    1 + (0..depth).map(|d| tree_size(d)).sum::<u32>()
+}
+
+trait Visit {
+    fn visit(&self, n: u32) -> u32;
+}
+
+struct Node;
+
+impl Visit for Node {
+    #[inline]
+    fn visit(&self, n: u32) -> u32 {
+        if n == 0 { 0 } else { 1 + walk(self, n - 1) }
+    }
+}
+
+#[inline]
+fn walk<V: Visit>(v: &V, n: u32) -> u32 {
+    v.visit(n)
+}
+
+fn main() {
+    assert!(even(10));
+    assert!(odd(7));
+    assert_eq!(fact(10), 3628800);
+    assert_eq!(tree_size(5), 32);
+    assert_eq!(walk(&Node, 9), 9);
+}
@@ -0,0 +1,47 @@
+// run-pass
+// compile-flags: -Z mir-opt-level=2
+
+// Aggregates that get split into their fields must keep behaving like the original ones.
+
+#[derive(Clone, Copy)]
+struct Point {
+    x: i32,
+    y: i32,
+}
+
+fn swap_fields(a: i32, b: i32) -> (i32, i32) {
+    let mut pair = (a, b);
+    let tmp = pair.0;
+    pair.0 = pair.1;
+    pair.1 = tmp;
+    (pair.0, pair.1)
+}
+
+fn borrow_field(x: i32) -> i32 {
+    let mut p = Point { x, y: 0 };
+    {
+        let y = &mut p.y;
+        *y += x;
+    }
+    p.x + p.y
+}
+
+fn nested(x: i32) -> i32 {
+    let outer = ((x, x + 1), Point { x: x + 2, y: x + 3 });
+    (outer.0).0 + (outer.0).1 + (outer.1).x + (outer.1).y
+}
+
+fn chain(v: &[i32]) -> i32 {
+    v.iter()
+        .zip(v.iter().skip(1))
+        .map(|(a, b)| (a * 2, b * 3))
+        .filter(|&(a, b)| a < b)
+        .fold(0, |acc, (a, b)| acc + a + b)
+}
+
+fn main() {
+    assert_eq!(swap_fields(1, 2), (2, 1));
+    assert_eq!(borrow_field(3), 9);
+    assert_eq!(nested(1), 10);
+    assert_eq!(chain(&[1, 2, 3, 4]), 2 + 6 + 4 + 9 + 6 + 12);
+}
@@ -0,0 +1,22 @@
+// compile-flags:--test --cfg feature="present"
+// normalize-stdout-test: "src/test/rustdoc-ui" -> "$$DIR"
+
+/// ```ignore-nonexistent-target
+/// assert!(true);
+/// ```
+pub struct Runs;
+
+/// ```only-nonexistent-target
+/// compile_error!("should not be built");
+/// ```
+pub struct Skipped;
+
+/// ```requires-feature="present"
+/// assert!(cfg!(feature = "present"));
+/// ```
+pub struct HasFeature;
+
+/// ```requires-feature="absent"
+/// compile_error!("should not be built");
+/// ```
+pub struct MissingFeature;
@@ -0,0 +1,20 @@
+#![deny(intra_doc_link_resolution_failure)]
+#![allow(non_snake_case)]
+
+pub struct Point {}
+
+pub fn Point() {}
+
+pub fn foo() {}
+
+/// [Point]
+//~^ ERROR `Point` is both a struct and a fn
+pub struct A;
+
+/// [enum@Point]
+//~^ ERROR incompatible link kind for `Point`
+pub struct B;
+
+/// [struct@foo]
+//~^ ERROR `[foo]` cannot be resolved
+pub struct C;
@@ -0,0 +1,41 @@
+// compile-flags: --cfg feature="serde" --cfg foo --cfg internal_build
+// ignore-tidy-linelength
+
+#![crate_name = "foo"]
+#![doc(cfg_hide(internal_build))]
+
+// @has foo/fn.with_serde.html '//*[@id="main"]/*[@class="stability"]/*[@class="stab portability"]' 'This is supported with crate feature serde only.'
+// @has foo/index.html '//*[@class="module-item"]//*[@class="stab portability"]' 'serde'
+#[cfg(feature = "serde")]
+pub fn with_serde() {}
+
+// `cfg(test)` and names listed in `doc(cfg_hide)` don't produce a note.
+// @has foo/fn.not_test.html
+// @!has - '//*[@class="stab portability"]' ''
+#[cfg(not(test))]
+#[cfg(internal_build)]
+pub fn not_test() {}
+
+// Conditions are combined with `doc(cfg)` and with the parent module's.
+// @has foo/foo_only/index.html '//*[@id="main"]/*[@class="stability"]/*[@class="stab portability"]' 'This is supported on foo only.'
+// @has foo/foo_only/struct.Both.html '//*[@id="main"]/*[@class="stability"]/*[@class="stab portability"]' 'This is supported on foo and crate feature serde only.'
+#[cfg(all(foo, not(test)))]
+pub mod foo_only {
+    #[cfg(any(test, feature = "serde"))]
+    pub struct Both;
+
+    // A condition which is already implied by the parent is not repeated.
+    // @has foo/foo_only/struct.Same.html '//*[@id="main"]/*[@class="stability"]/*[@class="stab portability"]' 'This is supported on foo only.'
+    #[cfg(foo)]
+    pub struct Same;
+}
+
+// @has foo/struct.Portable.html '//*[@class="impl"]/following-sibling::*[@class="stability"]' 'This is supported with crate feature serde only.'
+pub struct Portable;
+
+#[cfg(feature = "serde")]
+impl Clone for Portable {
+    fn clone(&self) -> Self {
+        Portable
+    }
+}
@@ -0,0 +1,18 @@
+// compile-flags: -Z unstable-options --enable-fulltext-search
+
+#![crate_name = "foo"]
+
+// @has 'fulltext-index/foo.js' 'fullTextIndex["foo"]'
+// @has - '"lazi"'
+// @has - '"buffer"'
+// @has - '"seek"'
+// @!has - '"hidden"'
+
+/// Allocates a buffer lazily.
+///
+/// Seeking past the end is an error.
+///
+/// ```
+/// let hidden = 1;
+/// ```
+pub struct Foo;
@@ -0,0 +1,51 @@
+// ignore-tidy-linelength
+
+#![crate_name = "foo"]
+#![deny(intra_doc_link_resolution_failure)]
+#![allow(non_snake_case)]
+
+// @has foo/index.html
+// @has - '//a/@href' '../foo/struct.Point.html'
+// @has - '//a/@href' '../foo/fn.Point.html'
+// @has - '//a/@href' '../foo/struct.Point.html#structfield.x'
+// @has - '//a/@href' '../foo/struct.Point.html#associatedconstant.ORIGIN'
+// @matches - '//a/@href' '^\.\./foo/enum\.Shape\.html#Square\.v$'
+// @matches - '//a/@href' '^\.\./foo/enum\.Shape\.html#variant\.Square\.field\.side$'
+// @has - '//a/@href' '../foo/trait.Storage.html#associatedtype.Key'
+// @has - '//a/@href' '../foo/trait.Storage.html#associatedconstant.CAPACITY'
+// @has - '//a/@href' '../foo/inner/index.html'
+// @has - '//a/@href' '../foo/macro.point.html'
+// @has - '//a/@href' 'https://doc.rust-lang.org/nightly/std/primitive.slice.html#method.iter'
+// @has - '//a/@href' 'https://doc.rust-lang.org/nightly/std/primitive.u8.html#method.max_value'
+//! * [struct@Point] and [fn@Point]
+//! * [`Point::x`], [field@Point::x] and [`Point::ORIGIN`]
+//! * [variant@Shape::Square] and [`Shape::Square::side`]
+//! * [`Storage::Key`] and [const@Storage::CAPACITY]
+//! * [mod@inner] and [macro@point]
+//! * [`slice::iter`] and [`u8::max_value`]
+
+pub struct Point {
+    pub x: i32,
+}
+
+impl Point {
+    pub const ORIGIN: Point = Point { x: 0 };
+}
+
+pub fn Point() {}
+
+pub enum Shape {
+    Square { side: u32 },
+}
+
+pub trait Storage {
+    type Key;
+    const CAPACITY: usize;
+}
+
+pub mod inner {}
+
+#[macro_export]
+macro_rules! point {
+    () => {};
+}
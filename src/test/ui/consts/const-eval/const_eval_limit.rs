@@ -0,0 +1,10 @@
+#![feature(const_loop, const_eval_limit)]
+#![const_eval_limit = "1000"]
+
+const fn spin(mut x: u32) -> u32 {
+    loop { x = !x; } //~ ERROR evaluation of constant value failed
+}
+
+fn main() {
+    let _ = [(); spin(1) as usize];
+}
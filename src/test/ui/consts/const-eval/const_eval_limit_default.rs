@@ -0,0 +1,11 @@
+// Check that const evaluation is stopped by the default step limit without `#![const_eval_limit]`.
+
+#![feature(const_loop)]
+
+const fn spin(mut x: u32) -> u32 {
+    loop { x = !x; } //~ ERROR evaluation of constant value failed
+}
+
+fn main() {
+    let _ = [(); spin(1) as usize];
+}
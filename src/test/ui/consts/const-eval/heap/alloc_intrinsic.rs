@@ -0,0 +1,31 @@
+// run-pass
+
+#![feature(core_intrinsics, const_fn, const_heap, const_raw_ptr_deref)]
+
+use std::intrinsics;
+
+// Builds a temporary buffer on the compile-time heap and frees it before returning.
+const fn sum_of_squares() -> u32 {
+    unsafe {
+        let buf = intrinsics::const_allocate(16, 4) as *mut [u32; 4];
+        *buf = [1, 2, 3, 4];
+        let sum = (*buf)[0] * (*buf)[0] + (*buf)[1] * (*buf)[1]
+            + (*buf)[2] * (*buf)[2] + (*buf)[3] * (*buf)[3];
+        intrinsics::const_deallocate(buf as *mut u8, 16, 4);
+        sum
+    }
+}
+
+const SUM: u32 = sum_of_squares();
+
+// Heap memory made global can be part of the final value.
+const TABLE: &[u32; 4] = unsafe {
+    let buf = intrinsics::const_allocate(16, 4) as *mut [u32; 4];
+    *buf = [1, 4, 9, 16];
+    &*(intrinsics::const_make_global(buf as *mut u8) as *const [u32; 4])
+};
+
+fn main() {
+    assert_eq!(SUM, 30);
+    assert_eq!(*TABLE, [1, 4, 9, 16]);
+}
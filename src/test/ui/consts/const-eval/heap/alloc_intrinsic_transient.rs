@@ -0,0 +1,11 @@
+#![feature(core_intrinsics, const_heap, const_raw_ptr_deref)]
+
+use std::intrinsics;
+
+const LEAK: &u32 = unsafe { //~ ERROR any use of this value will cause an error
+    let ptr = intrinsics::const_allocate(4, 4) as *mut u32;
+    *ptr = 42;
+    &*ptr
+};
+
+fn main() {}
@@ -0,0 +1,12 @@
+#![feature(core_intrinsics, const_heap, const_raw_ptr_deref)]
+
+use std::intrinsics;
+
+const USE_AFTER_FREE: u32 = unsafe { //~ ERROR any use of this value will cause an error
+    let ptr = intrinsics::const_allocate(4, 4) as *mut u32;
+    *ptr = 42;
+    intrinsics::const_deallocate(ptr as *mut u8, 4, 4);
+    *ptr
+};
+
+fn main() {}
@@ -0,0 +1,20 @@
+#![feature(const_heap, const_raw_ptr_deref, core_intrinsics)]
+
+// Intrinsics writing through a pointer argument are not supported by the interpreter, so
+// they cannot be used to write to memory that did not come from `const_allocate`.
+
+use std::intrinsics;
+
+const COPY: u32 = unsafe { //~ ERROR any use of this value will cause an error
+    let x = 0u32;
+    intrinsics::copy_nonoverlapping(&1, &x as *const u32 as *mut u32, 1);
+    x
+};
+
+const WRITE_BYTES: u32 = unsafe { //~ ERROR any use of this value will cause an error
+    let x = 0u32;
+    intrinsics::write_bytes(&x as *const u32 as *mut u32, 0xff, 1);
+    x
+};
+
+fn main() {}
@@ -0,0 +1,11 @@
+#![feature(const_heap, const_raw_ptr_deref)]
+
+// Writing through a raw pointer is only allowed into memory from `const_allocate`.
+const WRITE_LOCAL: u32 = unsafe { //~ ERROR any use of this value will cause an error
+    let x = 0u32;
+    let ptr = &x as *const u32 as *mut u32;
+    *ptr = 1;
+    x
+};
+
+fn main() {}
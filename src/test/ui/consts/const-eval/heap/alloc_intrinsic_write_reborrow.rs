@@ -0,0 +1,13 @@
+#![feature(const_heap, const_raw_ptr_deref)]
+
+// A reference reborrowed from a raw pointer cannot be used to get around the check that
+// only memory from `const_allocate` is written through pointers.
+const WRITE_REBORROW: u32 = unsafe {
+    let x = 0u32;
+    let ptr = &x as *const u32 as *mut u32;
+    let r = &mut *ptr; //~ ERROR references in constants may only refer to immutable values
+    *r = 1; //~ ERROR constant contains unimplemented expression type
+    x
+};
+
+fn main() {}
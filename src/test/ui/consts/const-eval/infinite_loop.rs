@@ -1,3 +1,7 @@
+// Check that the loop detector still stops evaluation once the step limit is lifted.
+#![feature(const_eval_limit)]
+#![const_eval_limit = "0"]
+
 fn main() {
     // Tests the Collatz conjecture with an incorrect base case (0 instead of 1).
     // The value of `n` will loop indefinitely (4 - 2 - 1 - 4).
@@ -0,0 +1,16 @@
+// compile-pass
+
+#![feature(const_if_match, const_loop, const_eval_limit)]
+#![const_eval_limit = "1000"]
+
+const STEPS: u32 = { //~ WARNING came close to the `const_eval_limit`
+    let mut i = 0;
+    while i < 200 {
+        i += 1;
+    }
+    i
+};
+
+fn main() {
+    assert_eq!(STEPS, 200);
+}
@@ -0,0 +1,70 @@
+// run-pass
+// Test that `if`, `match` and loops can be used in constants and `const fn`.
+
+#![feature(const_if_match, const_loop)]
+
+const fn gcd(mut a: u32, mut b: u32) -> u32 {
+    while b != 0 {
+        let t = b;
+        b = a % b;
+        a = t;
+    }
+    a
+}
+
+const fn hex_digit(c: u8) -> Option<u8> {
+    match c {
+        b'0'..=b'9' => Some(c - b'0'),
+        b'a'..=b'f' => Some(c - b'a' + 10),
+        _ => None,
+    }
+}
+
+const fn first_some(x: Option<u32>, y: Option<u32>) -> u32 {
+    if let Some(x) = x {
+        x
+    } else if let Some(y) = y {
+        y
+    } else {
+        0
+    }
+}
+
+const fn is_sorted(a: u32, b: u32, c: u32) -> bool {
+    a <= b && b <= c
+}
+
+const fn count_ones_table() -> [u8; 16] {
+    let mut table = [0; 16];
+    let mut i = 0;
+    while i < 16 {
+        table[i] = (i as u32).count_ones() as u8;
+        i += 1;
+    }
+    table
+}
+
+const GCD: u32 = gcd(48, 18);
+const DIGITS: [Option<u8>; 3] = [hex_digit(b'7'), hex_digit(b'c'), hex_digit(b'x')];
+const FIRST: [u32; 3] = [first_some(Some(1), Some(2)), first_some(None, Some(2)),
+                         first_some(None, None)];
+const ONES: [u8; 16] = count_ones_table();
+const SORTED: bool = is_sorted(1, 2, 3) && !is_sorted(3, 2, 1);
+const TEN: u32 = {
+    let mut n = 0;
+    loop {
+        n += 1;
+        if n == 10 {
+            break n;
+        }
+    }
+};
+
+fn main() {
+    assert_eq!(GCD, 6);
+    assert_eq!(DIGITS, [Some(7), Some(12), None]);
+    assert_eq!(FIRST, [1, 2, 0]);
+    assert_eq!(ONES, [0, 1, 1, 2, 1, 2, 2, 3, 1, 2, 2, 3, 2, 3, 3, 4]);
+    assert!(SORTED);
+    assert_eq!(TEN, 10);
+}
@@ -0,0 +1,33 @@
+// Test that a value which needs dropping and is only moved out of in one arm
+// of an `if` is still dropped, and rejected, on the path through the other.
+
+#![feature(const_if_match)]
+
+struct NeedsDrop(u32);
+
+impl Drop for NeedsDrop {
+    fn drop(&mut self) {}
+}
+
+const COND: bool = true;
+
+const MOVED_IN_ONE_ARM: Option<NeedsDrop> = {
+    let x = NeedsDrop(0); //~ ERROR destructors cannot be evaluated at compile-time
+    if COND {
+        Some(x)
+    } else {
+        None
+    }
+};
+
+const MOVED_IN_BOTH_ARMS: Option<NeedsDrop> = {
+    let x = NeedsDrop(0);
+    if COND {
+        Some(x)
+    } else {
+        let y = x;
+        Some(y)
+    }
+};
+
+fn main() {}
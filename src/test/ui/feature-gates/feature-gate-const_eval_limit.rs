@@ -0,0 +1,5 @@
+// check that #![const_eval_limit] is feature-gated
+
+#![const_eval_limit = "1000"] //~ ERROR the `#[const_eval_limit]` attribute is an experimental
+
+fn main() {}
@@ -0,0 +1,9 @@
+#![feature(core_intrinsics, const_fn)]
+
+use std::intrinsics;
+
+const fn scratch() -> *mut u8 {
+    unsafe { intrinsics::const_allocate(4, 4) } //~ ERROR heap allocations in constant functions
+}
+
+fn main() {}
@@ -0,0 +1,7 @@
+// Test that `if` and `match` in a `const fn` are gated behind `#![feature(const_if_match)]`.
+
+const fn abs(x: i32) -> i32 {
+    if x < 0 { -x } else { x } //~ ERROR `if`, `match`, `&&` and `||` are not stable in const fn
+}
+
+fn main() {}
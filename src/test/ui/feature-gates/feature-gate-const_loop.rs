@@ -0,0 +1,11 @@
+// Test that loops in a `const fn` are gated behind `#![feature(const_loop)]`, even when
+// `#![feature(const_if_match)]` allows the `if` in the loop.
+
+#![feature(const_if_match)]
+
+const fn ten() -> u32 {
+    let mut n = 0;
+    loop { n += 1; if n == 10 { break n; } } //~ ERROR loops are not allowed in const fn
+}
+
+fn main() {}
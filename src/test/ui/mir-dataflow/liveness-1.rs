@@ -0,0 +1,29 @@
+// General test of the liveness of locals computed by the generic MIR dataflow framework.
+
+#![feature(core_intrinsics, rustc_attrs)]
+
+use std::intrinsics::rustc_peek;
+
+#[rustc_mir(rustc_peek_liveness, stop_after_dataflow)]
+fn foo(test: bool, a: i32, b: i32) -> i32 {
+    let x;
+
+    // `a` is read further down, so it is live here...
+    unsafe { rustc_peek(&a); }
+
+    // ... but `x` is assigned before it is read.
+    unsafe { rustc_peek(&x); } //~ ERROR rustc_peek: bit not set
+
+    x = if test { a } else { b };
+
+    // `x` is returned, while `a` and `b` are never read again.
+    unsafe { rustc_peek(&x); }
+    unsafe { rustc_peek(&a); } //~ ERROR rustc_peek: bit not set
+    unsafe { rustc_peek(&b); } //~ ERROR rustc_peek: bit not set
+
+    x
+}
+
+fn main() {
+    foo(true, 1, 2);
+}
@@ -0,0 +1,12 @@
+// Check that `-Z borrowck-explain=graph` lists the constraints keeping a borrow alive.
+// compile-flags: -Z borrowck=mir -Z borrowck-explain=graph
+
+fn use_ref(_: &i32) {}
+
+fn main() {
+    let mut x = 0;
+    let r = &x;
+    let s = r;
+    x = 1; //~ ERROR cannot assign to `x` because it is borrowed
+    use_ref(s);
+}
@@ -0,0 +1,49 @@
+// Check that Polonius accepts returning a borrow on one path while the borrowed data is mutated
+// on another, which NLL rejects ("problem case #3" of the NLL RFC). `-Z polonius` on its own
+// selects the MIR borrowck.
+// revisions: mir polonius
+// ignore-compare-mode-nll
+// ignore-compare-mode-polonius
+//[mir] compile-flags: -Z borrowck=mir
+//[polonius] compile-flags: -Z polonius
+//[polonius] compile-pass
+
+use std::collections::HashMap;
+use std::hash::Hash;
+
+fn get_default<'r, K, V>(map: &'r mut HashMap<K, V>, key: K) -> &'r mut V
+where
+    K: Hash + Eq + Copy,
+    V: Default,
+{
+    match map.get_mut(&key) {
+        Some(value) => value,
+        None => {
+            map.insert(key, V::default());
+            //[mir]~^ ERROR cannot borrow `*map` as mutable more than once at a time
+            map.get_mut(&key).unwrap()
+            //[mir]~^ ERROR cannot borrow `*map` as mutable more than once at a time
+        }
+    }
+}
+
+fn find_or_push(v: &mut Vec<u32>, x: u32) -> &mut u32 {
+    if let Some(e) = v.iter_mut().find(|e| **e == x) {
+        return e;
+    }
+    v.push(x);
+    //[mir]~^ ERROR cannot borrow `*v` as mutable more than once at a time
+    v.last_mut().unwrap()
+    //[mir]~^ ERROR cannot borrow `*v` as mutable more than once at a time
+}
+
+fn main() {
+    let mut map = HashMap::new();
+    *get_default(&mut map, 1) += 1u32;
+    *get_default(&mut map, 1) += 1;
+    assert_eq!(map[&1], 2);
+
+    let mut v = vec![1, 2];
+    *find_or_push(&mut v, 3) += 1;
+    assert_eq!(v, [1, 2, 4]);
+}
@@ -0,0 +1,19 @@
+// Check that under `-Z polonius`, a lifetime that has to outlive another one according to the
+// facts given to Polonius is an error unless the where clauses say so.
+// ignore-compare-mode-nll
+// ignore-compare-mode-polonius
+// compile-flags: -Z polonius
+
+fn known<'a, 'b>(x: &'a u32) -> &'b u32
+where
+    'a: 'b,
+{
+    &*x
+}
+
+fn unknown<'a, 'b>(x: &'a u32, _y: &'b u32) -> &'b u32 {
+    &*x
+    //~^ ERROR lifetime may not live long enough
+}
+
+fn main() {}
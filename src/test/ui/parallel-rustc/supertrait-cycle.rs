@@ -0,0 +1,13 @@
+// Checks that query cycles are reported, and not deadlocked on, when the
+// compiler runs with several threads.
+
+// compile-flags: -Z threads=4
+
+trait T1 : T2 {
+//~^ ERROR cycle detected
+}
+
+trait T2 : T1 {
+}
+
+fn main() { }
@@ -92,6 +92,9 @@ impl fmt::Display for Mode {
 pub enum CompareMode {
     Nll,
     Polonius,
+    /// Runs the compiler with several threads. This only has an effect if it
+    /// was built with `parallel-compiler = true`.
+    Parallel,
 }
 
 impl CompareMode {
@@ -99,6 +102,7 @@ impl CompareMode {
         match *self {
             CompareMode::Nll => "nll",
             CompareMode::Polonius => "polonius",
+            CompareMode::Parallel => "parallel",
         }
     }
 
@@ -106,6 +110,7 @@ impl CompareMode {
         match s.as_str() {
             "nll" => CompareMode::Nll,
             "polonius" => CompareMode::Polonius,
+            "parallel" => CompareMode::Parallel,
             x => panic!("unknown --compare-mode option: {}", x),
         }
     }
@@ -239,6 +244,10 @@ pub struct Config {
     /// mode describing what file the actual ui output will be compared to
     pub compare_mode: Option<CompareMode>,
 
+    /// Whether incremental tests recompute every green query of each revision
+    /// with `-Z incremental-verify-queries`
+    pub verify_incremental_queries: bool,
+
     // Configuration for various run-make tests frobbing things like C compilers
     // or querying about various LLVM component information.
     pub cc: String,
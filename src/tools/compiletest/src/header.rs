@@ -735,6 +735,7 @@ impl Config {
                 match self.compare_mode {
                     Some(CompareMode::Nll) => name == "compare-mode-nll",
                     Some(CompareMode::Polonius) => name == "compare-mode-polonius",
+                    Some(CompareMode::Parallel) => name == "compare-mode-parallel",
                     None => false,
                 } ||
                 (cfg!(debug_assertions) && name == "debug") {
@@ -1753,6 +1753,9 @@ impl<'test> TestCx<'test> {
                 rustc.args(&["-C", &format!("incremental={}", incremental_dir.display())]);
                 rustc.args(&["-Z", "incremental-verify-ich"]);
                 rustc.args(&["-Z", "incremental-queries"]);
+                if self.config.verify_incremental_queries {
+                    rustc.args(&["-Z", "incremental-verify-queries=1"]);
+                }
             }
 
             if self.config.mode == CodegenUnits {
@@ -1842,6 +1845,9 @@ impl<'test> TestCx<'test> {
             Some(CompareMode::Polonius) => {
                 rustc.args(&["-Zpolonius", "-Zborrowck=mir", "-Ztwo-phase-borrows"]);
             }
+            Some(CompareMode::Parallel) => {
+                rustc.args(&["-Zthreads=4"]);
+            }
             None => {}
         }
 
@@ -2745,7 +2751,7 @@ impl<'test> TestCx<'test> {
             errors += self.compare_output("stderr", &normalized_stderr, &expected_stderr);
         }
 
-        let modes_to_prune = vec![CompareMode::Nll];
+        let modes_to_prune = vec![CompareMode::Nll, CompareMode::Polonius, CompareMode::Parallel];
         self.prune_duplicate_outputs(&modes_to_prune);
 
         if self.config.compare_mode.is_some() {
@@ -1,6 +1,7 @@
 const fs = require('fs');
 
 const TEST_FOLDER = 'src/test/rustdoc-js/';
+const FULL_TEXT_CASES = 'src/librustdoc/html/fulltext-tokenize.json';
 
 function getNextStep(content, pos, stop) {
     while (pos < content.length && content[pos] !== stop &&
@@ -310,7 +311,36 @@ function main(argv) {
             console.log("OK");
         }
     });
+    errors += checkFullTextTokenize(mainJs);
     return errors;
 }
 
+// The full-text index is tokenized by rustdoc and the queries by `fullTextTokenize`, so both
+// must split text the same way. rustdoc is checked against the same cases by its unit tests.
+function checkFullTextTokenize(mainJs) {
+    var js = "";
+    js += loadThings(["FULL_TEXT_STOP_WORDS"], 'array', extractArrayVariable, mainJs);
+    js += loadThings(["FULL_TEXT_MAX_TERM_LEN"], 'variable', extractVariable, mainJs);
+    js += loadThings(["fullTextStem", "fullTextTokenize"], 'function', extractFunction, mainJs);
+    var loaded = loadContent(js);
+
+    process.stdout.write('Checking "' + FULL_TEXT_CASES + '" ... ');
+    var error_text = [];
+    JSON.parse(readFile(FULL_TEXT_CASES)).forEach(function(testCase) {
+        var output = JSON.stringify(loaded.fullTextTokenize(testCase[0]));
+        var expected = JSON.stringify(testCase[1]);
+        if (output !== expected) {
+            error_text.push("==> Tokenizing " + JSON.stringify(testCase[0]) + " gave " + output +
+                            " instead of " + expected);
+        }
+    });
+    if (error_text.length !== 0) {
+        console.error("FAILED");
+        console.error(error_text.join("\n"));
+        return 1;
+    }
+    console.log("OK");
+    return 0;
+}
+
 process.exit(main(process.argv));